@@ -1,29 +1,43 @@
-use std::collections::HashSet;
-use std::fs::File;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
-use binrw::BinReaderExt;
-use binrw::BinWriterExt;
 use eframe::{egui, Storage};
 use egui::{KeyboardShortcut, Modifiers, Key, RichText, SliderClamping, ViewportCommand};
 
-use crate::game::*;
-use crate::save::*;
-use crate::uobject::Stringable;
+use shf_save_editor::backup::{self, Backup};
+use shf_save_editor::diff::{self, Difference, DifferenceKind};
+use shf_save_editor::game::*;
+use shf_save_editor::history::{Edit, EditStep, History, PropertyInfo, SaveDataInfo};
+use shf_save_editor::json;
+use shf_save_editor::records::*;
+use shf_save_editor::save::*;
+use shf_save_editor::uobject::Stringable;
+use shf_save_editor::verify;
+
 
 const BINARY_DATA_CUTOFF: usize = 10;
 
 const MIN_UI_SCALE: f32 = 0.5;
 const MAX_UI_SCALE: f32 = 2.0;
 
+const MAX_BACKUP_GENERATIONS: usize = 100;
+
+const MAX_TAG_SUGGESTIONS: usize = 10;
+
 const SETTINGS_KEY: &str = "shf_settings";
 
+pub const APP_TITLE: &str = "Silent Hill f Save Editor";
+
 #[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 struct Settings {
     default_pixels_per_point: Option<f32>,
     ui_scale: f32,
     last_directory: Option<PathBuf>,
+    backup_generations: usize,
+    detect_custom_structs: bool,
 }
 
 impl Default for Settings {
@@ -32,6 +46,8 @@ impl Default for Settings {
             default_pixels_per_point: None,
             ui_scale: 1.0,
             last_directory: None,
+            backup_generations: backup::DEFAULT_GENERATIONS,
+            detect_custom_structs: false,
         }
     }
 }
@@ -50,6 +66,37 @@ impl ListAction {
             *self = action;
         }
     }
+
+    /// Applies the action to a list, using `make_default` to create inserted elements
+    fn apply<T>(self, values: &mut Vec<T>, make_default: impl FnOnce() -> T) {
+        match self {
+            Self::Insert(index) => values.insert(index, make_default()),
+            Self::Delete(index) => {
+                values.remove(index);
+            }
+            Self::None => (),
+        }
+    }
+}
+
+/// An action that would discard unsaved changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GuardedAction {
+    Open,
+    ImportJson,
+    RestoreBackup(PathBuf),
+    Exit,
+}
+
+impl GuardedAction {
+    const fn description(&self) -> &'static str {
+        match self {
+            Self::Open => "opening another save",
+            Self::ImportJson => "importing JSON",
+            Self::RestoreBackup(_) => "restoring a backup",
+            Self::Exit => "exiting",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -72,11 +119,75 @@ impl AppTab {
     }
 }
 
+/// The differences between the open save and another save file
+struct Comparison {
+    other_path: PathBuf,
+    other: SaveGame,
+    differences: Vec<Difference>,
+    filter: String,
+}
+
+/// Values used in the open save, offered as choices when editing values of the same kind
+#[derive(Debug, Default)]
+struct KnownValues {
+    tags: Vec<String>,
+    enum_values: BTreeMap<String, Vec<String>>,
+}
+
+impl KnownValues {
+    fn from_save(save: &SaveGame) -> Self {
+        let mut enum_values = save.enum_values();
+        Self::add_difficulty_levels::<ActionLevel>(&mut enum_values);
+        Self::add_difficulty_levels::<RiddleLevel>(&mut enum_values);
+        Self { tags: save.gameplay_tags(), enum_values }
+    }
+
+    /// Adds all values of a difficulty level enum, which the save may not contain
+    fn add_difficulty_levels<T: DifficultyLevel>(enum_values: &mut BTreeMap<String, Vec<String>>) {
+        let values = enum_values.entry(format!("{}.{}", T::namespace(), T::type_name())).or_default();
+        values.extend(T::all().iter().map(|level| level.as_str().to_string()));
+        values.sort();
+        values.dedup();
+    }
+}
+
+/// What the Advanced view needs to show values and record edits to them
+struct EditContext<'a> {
+    known: &'a KnownValues,
+    edits: &'a mut Vec<Edit>,
+}
+
+impl EditContext<'_> {
+    fn edit_value(&mut self, path: &[EditStep], old: PropertyValue, new: PropertyValue) {
+        self.edits.push(Edit::Value { path: path.to_vec(), old, new });
+    }
+}
+
+/// Returns the path of a child of the value at `path`
+fn child_path(path: &[EditStep], step: EditStep) -> Vec<EditStep> {
+    [path, &[step]].concat()
+}
+
 pub struct AppState {
     save_path: Option<PathBuf>,
     last_directory: Option<PathBuf>,
     save: Option<SaveGame>,
+    /// Classes of objects in the open save whose custom struct data couldn't be decoded
+    undecoded_classes: Vec<String>,
+    known: KnownValues,
+    /// Edits made to the save this frame, which are added to the undo history at the end of the frame
+    edits: Vec<Edit>,
     error_message: Option<String>,
+    comparison: Option<Comparison>,
+    history: History<egui::Id>,
+    /// Whether the open save was read with custom struct detection, which saving and comparing have to match
+    save_detects_custom_structs: bool,
+    pending_action: Option<GuardedAction>,
+    allow_close: bool,
+    backups: Option<Vec<Backup>>,
+    backup_generations: usize,
+    detect_custom_structs: bool,
+    title: String,
     tab: AppTab,
     default_pixels_per_point: Option<f32>,
     ui_scale: f32,
@@ -88,7 +199,19 @@ impl Default for AppState {
             save_path: None,
             last_directory: Self::get_default_save_directory(),
             save: None,
+            undecoded_classes: Vec::new(),
+            known: KnownValues::default(),
+            edits: Vec::new(),
             error_message: None,
+            comparison: None,
+            history: History::default(),
+            save_detects_custom_structs: false,
+            pending_action: None,
+            allow_close: false,
+            backups: None,
+            backup_generations: backup::DEFAULT_GENERATIONS,
+            detect_custom_structs: false,
+            title: String::new(),
             tab: AppTab::default(),
             default_pixels_per_point: None,
             ui_scale: 1.0,
@@ -97,12 +220,21 @@ impl Default for AppState {
 }
 
 impl AppState {
-    pub fn load_app(cc: &eframe::CreationContext<'_>, initial_path: Option<PathBuf>) -> Self {
+    /// Creates the app, restoring its settings and opening `initial_path` if given
+    ///
+    /// `classes_error` is an error from loading the custom struct classes at startup, which is shown to the user since
+    /// the GUI has no console on Windows.
+    pub fn load_app(cc: &eframe::CreationContext<'_>, initial_path: Option<PathBuf>, classes_error: Option<String>) -> Self {
         let mut app = Self::default();
+        if let Some(err) = classes_error {
+            app.error_message = Some(format!("Failed to load custom struct classes: {err}"));
+        }
 
         if let Some(storage) = cc.storage && let Some(settings) = eframe::get_value::<Settings>(storage, SETTINGS_KEY) {
             app.default_pixels_per_point = settings.default_pixels_per_point;
             app.ui_scale = settings.ui_scale;
+            app.backup_generations = settings.backup_generations;
+            app.detect_custom_structs = settings.detect_custom_structs;
             if let Some(last_directory) = settings.last_directory {
                 app.last_directory = Some(last_directory);
             }
@@ -148,6 +280,95 @@ impl AppState {
         }
     }
 
+    fn is_modified(&self) -> bool {
+        self.save.is_some() && self.history.is_modified()
+    }
+
+    fn window_title(&self) -> String {
+        if self.save.is_none() {
+            return String::from(APP_TITLE);
+        }
+
+        let name = self.save_path.as_ref()
+            .and_then(|p| p.file_name())
+            .map_or_else(|| String::from("(unsaved)"), |n| n.to_string_lossy().into_owned());
+        let modified = if self.is_modified() { "*" } else { "" };
+        format!("{name}{modified} - {APP_TITLE}")
+    }
+
+    fn update_title(&mut self, ctx: &egui::Context) {
+        let title = self.window_title();
+        if title != self.title {
+            ctx.send_viewport_cmd(ViewportCommand::Title(title.clone()));
+            self.title = title;
+        }
+    }
+
+    /// Performs an action that would discard the open save, asking the user first if there are unsaved changes
+    fn guard(&mut self, ctx: &egui::Context, action: GuardedAction) {
+        if self.is_modified() {
+            self.pending_action = Some(action);
+        } else {
+            self.perform(ctx, action);
+        }
+    }
+
+    fn perform(&mut self, ctx: &egui::Context, action: GuardedAction) {
+        match action {
+            GuardedAction::Open => self.open_save(),
+            GuardedAction::ImportJson => self.import_json(),
+            GuardedAction::RestoreBackup(path) => self.restore_backup(&path),
+            GuardedAction::Exit => {
+                self.allow_close = true;
+                ctx.send_viewport_cmd(ViewportCommand::Close);
+            }
+        }
+    }
+
+    fn unsaved_changes_modal(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.pending_action.clone() else {
+            return;
+        };
+
+        let mut choice = None;
+        let response = egui::Modal::new(egui::Id::new("Unsaved Changes Modal")).show(ctx, |ui| {
+            ui.label(RichText::new("Unsaved changes").strong());
+            ui.separator();
+            ui.label(format!("Do you want to save your changes before {}?", action.description()));
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    choice = Some(true);
+                }
+                if ui.button("Discard").clicked() {
+                    choice = Some(false);
+                }
+                if ui.button("Cancel").clicked() {
+                    self.pending_action = None;
+                }
+            });
+        });
+
+        if response.should_close() {
+            self.pending_action = None;
+        }
+
+        match choice {
+            Some(true) => {
+                self.pending_action = None;
+                self.save();
+                // if the save failed or the user cancelled the file dialog, keep the changes
+                if !self.is_modified() {
+                    self.perform(ctx, action);
+                }
+            }
+            Some(false) => {
+                self.pending_action = None;
+                self.perform(ctx, action);
+            }
+            None => (),
+        }
+    }
+
     fn error_modal(&mut self, ctx: &egui::Context) {
         let Some(ref error_message) = self.error_message else {
             return;
@@ -167,11 +388,43 @@ impl AppState {
         }
     }
 
+    /// Returns the options saves are read with
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions::lenient().with_custom_struct_detection(self.detect_custom_structs)
+    }
+
+    /// Returns the options the open save was read with
+    fn save_parse_options(&self) -> ParseOptions {
+        ParseOptions::lenient().with_custom_struct_detection(self.save_detects_custom_structs)
+    }
+
+    /// Makes the given save the one being edited, starting a new undo history
+    fn set_save(&mut self, save: SaveGame) {
+        self.save_detects_custom_structs = self.detect_custom_structs;
+        self.history.reset();
+        self.edits.clear();
+        self.undecoded_classes = save.undecoded_classes();
+        self.known = KnownValues::from_save(&save);
+        self.save = Some(save);
+    }
+
     fn load_save(&mut self, save_path: PathBuf) -> Result<()> {
-        let mut file = File::open(&save_path)?;
-        let save: SaveGame = file.read_le()?;
+        let data = std::fs::read(&save_path)?;
+        let options = self.parse_options();
+        let save = SaveGame::read_with_options(&mut Cursor::new(&data), options.clone())?;
+        let quarantined = options.quarantined();
+        if let Err(err) = verify::verify_roundtrip(&data, &save) {
+            self.error_message = Some(format!("Warning: this save can't be saved back exactly as it was read ({err}). Saving it may corrupt it."));
+        } else if !quarantined.is_empty() {
+            let errors: Vec<_> = quarantined.iter().map(ParseError::to_string).collect();
+            self.error_message = Some(format!(
+                "Warning: some values in this save couldn't be parsed. They will be shown as raw bytes and saved unchanged.\n\n{}",
+                errors.join("\n"),
+            ));
+        }
+        self.set_save(save);
+        self.history.mark_saved();
         self.save_path = Some(save_path);
-        self.save = Some(save);
         Ok(())
     }
 
@@ -200,21 +453,31 @@ impl AppState {
             return;
         };
 
-        let result: Result<()> = (|| {
-            let mut file = File::create(&path)?;
-            file.write_le(save)?;
-            Ok(())
+        let backup_generations = self.backup_generations;
+        let options = self.save_parse_options();
+        let result: Result<Vec<u8>> = (|| {
+            // only back up the existing save once the new data is known to be good, so a failed save doesn't prune a
+            // good backup
+            let pending = verify::prepare_write(save, &path, options)?;
+            backup::backup_before_overwrite(&path, backup_generations)
+                .map_err(|e| anyhow::anyhow!("Couldn't back up the existing save, so it was not overwritten: {e}"))?;
+            pending.commit()
         })();
 
-        if let Err(err) = result {
-            self.error_message = Some(format!("Failed to save: {err}"));
+        match result {
+            Ok(_) => self.history.mark_saved(),
+            Err(err) => self.error_message = Some(format!("Failed to save: {err}")),
         }
 
         self.save_path = Some(path);
     }
 
     fn save(&mut self) {
-        let Some(save_path) = self.save_path.take() else { return; };
+        let Some(save_path) = self.save_path.take() else {
+            // e.g. a save that was imported from JSON
+            self.save_as();
+            return;
+        };
         self.save_to(save_path);
     }
 
@@ -239,6 +502,239 @@ impl AppState {
         }
     }
 
+    fn json_dialog(&self) -> rfd::FileDialog {
+        let dialog = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"]);
+
+        match &self.last_directory {
+            Some(path) => dialog.set_directory(path),
+            None => dialog,
+        }
+    }
+
+    fn export_json(&mut self) {
+        let Some(ref save) = self.save else {
+            return;
+        };
+
+        let mut dialog = self.json_dialog();
+        if let Some(stem) = self.save_path.as_ref().and_then(|p| p.file_stem()) {
+            dialog = dialog.set_file_name(format!("{}.json", stem.to_string_lossy()));
+        }
+
+        if let Some(path) = dialog.save_file() {
+            let result = json::to_json_string(save).and_then(|json| Ok(std::fs::write(&path, json)?));
+            if let Err(err) = result {
+                self.error_message = Some(format!("Failed to export JSON: {err}"));
+            }
+        }
+    }
+
+    fn import_json(&mut self) {
+        let Some(path) = self.json_dialog().pick_file() else {
+            return;
+        };
+
+        let result = std::fs::read_to_string(&path).map_err(anyhow::Error::from).and_then(|json| json::from_json_str(&json));
+        match result {
+            Ok(save) => {
+                // the imported save isn't associated with a .sav file until the user saves it somewhere
+                self.save_path = None;
+                self.set_save(save);
+            }
+            Err(err) => self.error_message = Some(format!("Failed to import JSON: {err:#}")),
+        }
+    }
+
+    fn undo(&mut self) {
+        let Some(ref mut save) = self.save else {
+            return;
+        };
+
+        if let Some(Err(err)) = self.history.undo(save) {
+            self.error_message = Some(format!("Failed to undo: {err}"));
+        }
+    }
+
+    fn redo(&mut self) {
+        let Some(ref mut save) = self.save else {
+            return;
+        };
+
+        if let Some(Err(err)) = self.history.redo(save) {
+            self.error_message = Some(format!("Failed to redo: {err}"));
+        }
+    }
+
+    /// Adds the edits made to the save this frame to the undo history
+    fn record_edits(&mut self, ctx: &egui::Context) {
+        // changes made while typing in the same field or dragging the same control are grouped into a single edit
+        let group = ctx.memory(|m| m.focused())
+            .or_else(|| ctx.input(|i| i.pointer.any_down()).then(|| egui::Id::new("pointer_edit")));
+        self.history.record(std::mem::take(&mut self.edits), group);
+    }
+
+    fn show_backup_browser(&mut self) {
+        let Some(ref save_path) = self.save_path else {
+            return;
+        };
+
+        match backup::list_backups(save_path) {
+            Ok(backups) => self.backups = Some(backups),
+            Err(err) => self.error_message = Some(format!("Failed to list backups: {err}")),
+        }
+    }
+
+    /// Replaces the open save with the contents of a backup
+    ///
+    /// The backup is associated with the open save's path, so saving writes it over the save (backing up the save
+    /// first).
+    fn restore_backup(&mut self, backup_path: &Path) {
+        let options = self.parse_options();
+        let result = std::fs::read(backup_path).map_err(anyhow::Error::from).and_then(|data| SaveGame::read_with_options(&mut Cursor::new(&data), options));
+        match result {
+            Ok(save) => {
+                self.set_save(save);
+                self.backups = None;
+            }
+            Err(err) => self.error_message = Some(format!("Failed to restore backup: {err}")),
+        }
+    }
+
+    fn backup_window(&mut self, ctx: &egui::Context) {
+        let Some(ref backups) = self.backups else {
+            return;
+        };
+
+        let mut open = true;
+        let mut restore = None;
+        egui::Window::new("Restore Backup")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if backups.is_empty() {
+                    ui.label("There are no backups of this save.");
+                    return;
+                }
+
+                ui.label("Restoring a backup loads it into the editor. Save to write it over the current save.");
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("backups").striped(true).show(ui, |ui| {
+                            for backup in backups {
+                                ui.label(backup.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                                ui.label(format!("{} bytes", backup.size));
+                                if ui.button("Restore").clicked() {
+                                    restore = Some(backup.path.clone());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+            });
+
+        if let Some(path) = restore {
+            self.guard(ctx, GuardedAction::RestoreBackup(path));
+        }
+
+        if !open {
+            self.backups = None;
+        }
+    }
+
+    fn compare_with(&mut self) {
+        let Some(ref save) = self.save else {
+            return;
+        };
+
+        let mut dialog = rfd::FileDialog::new()
+            .add_filter("Silent Hill f save", &["sav"]);
+
+        if let Some(path) = &self.last_directory {
+            dialog = dialog.set_directory(path);
+        }
+
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+
+        // read the other save the same way as the open one so the same values are decoded in both
+        let options = self.save_parse_options();
+        let result = std::fs::read(&path).map_err(anyhow::Error::from).and_then(|data| SaveGame::read_with_options(&mut Cursor::new(&data), options));
+        match result {
+            Ok(other) => {
+                self.comparison = Some(Comparison {
+                    differences: diff::diff_saves(&other, save),
+                    other_path: path,
+                    other,
+                    filter: String::new(),
+                });
+            }
+            Err(err) => self.error_message = Some(format!("Failed to load save for comparison: {err}")),
+        }
+    }
+
+    fn show_comparison(&mut self, ctx: &egui::Context) {
+        let Some(ref mut comparison) = self.comparison else {
+            return;
+        };
+
+        let mut open = true;
+        let mut refresh = false;
+        egui::Window::new("Compare")
+            .open(&mut open)
+            .default_size([700.0, 400.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Changes from {} to the open save", comparison.other_path.display()));
+                ui.horizontal(|ui| {
+                    ui.label("Filter");
+                    ui.text_edit_singleline(&mut comparison.filter);
+                    refresh = ui.button("Refresh").clicked();
+                });
+                ui.separator();
+
+                if comparison.differences.is_empty() {
+                    ui.label("The saves are identical.");
+                    return;
+                }
+
+                let filter = comparison.filter.to_lowercase();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("comparison").striped(true).show(ui, |ui| {
+                            ui.label("");
+                            ui.label(RichText::new("Path").strong());
+                            ui.label(RichText::new("Old").strong());
+                            ui.label(RichText::new("New").strong());
+                            ui.end_row();
+
+                            for difference in comparison.differences.iter().filter(|d| d.path.to_lowercase().contains(&filter)) {
+                                let (symbol, color) = match difference.kind {
+                                    DifferenceKind::Added => ("+", egui::Color32::GREEN),
+                                    DifferenceKind::Removed => ("-", egui::Color32::RED),
+                                    DifferenceKind::Changed => ("~", egui::Color32::YELLOW),
+                                };
+                                ui.colored_label(color, symbol);
+                                ui.label(&difference.path);
+                                ui.label(difference.old.as_deref().unwrap_or_default());
+                                ui.label(difference.new.as_deref().unwrap_or_default());
+                                ui.end_row();
+                            }
+                        });
+                    });
+            });
+
+        if refresh && let Some(ref save) = self.save {
+            comparison.differences = diff::diff_saves(&comparison.other, save);
+        }
+
+        if !open {
+            self.comparison = None;
+        }
+    }
+
     fn typed_input<T: Stringable + ?Sized>(ui: &mut egui::Ui, label: &str, value: &mut T) -> bool {
         ui.horizontal(|ui| {
             if !label.is_empty() {
@@ -254,11 +750,11 @@ impl AppState {
         }).inner
     }
 
-    fn text_input(ui: &mut egui::Ui, label: &str, value: &mut FString) {
+    fn text_input(ui: &mut egui::Ui, label: &str, value: &mut FString) -> bool {
         ui.horizontal(|ui| {
             ui.label(format!("{label}: "));
-            ui.text_edit_singleline(value.as_mut());
-        });
+            ui.text_edit_singleline(value.as_mut()).changed()
+        }).inner
     }
 
     fn show_header(&mut self, ui: &mut egui::Ui) {
@@ -266,22 +762,27 @@ impl AppState {
             return;
         };
 
-        Self::typed_input(ui, "Save Game Version", &mut save.header.save_game_version);
+        let old = save.header.clone();
+        let mut changed = Self::typed_input(ui, "Save Game Version", &mut save.header.save_game_version);
         egui::CollapsingHeader::new("Package Version")
             .default_open(true)
             .show(ui, |ui| {
-                Self::typed_input(ui, "UE4", &mut save.header.package_version.0);
-                Self::typed_input(ui, "UE5", &mut save.header.package_version.1);
+                changed |= Self::typed_input(ui, "UE4", &mut save.header.package_version.0);
+                changed |= Self::typed_input(ui, "UE5", &mut save.header.package_version.1);
             });
         egui::CollapsingHeader::new("Engine Version")
             .default_open(true)
             .show(ui, |ui| {
-                Self::typed_input(ui, "Major", &mut save.header.engine_version.major);
-                Self::typed_input(ui, "Minor", &mut save.header.engine_version.minor);
-                Self::typed_input(ui, "Patch", &mut save.header.engine_version.patch);
-                Self::typed_input(ui, "Build", &mut save.header.engine_version.build);
-                Self::text_input(ui, "Build ID", &mut save.header.engine_version.build_id);
+                changed |= Self::typed_input(ui, "Major", &mut save.header.engine_version.major);
+                changed |= Self::typed_input(ui, "Minor", &mut save.header.engine_version.minor);
+                changed |= Self::typed_input(ui, "Patch", &mut save.header.engine_version.patch);
+                changed |= Self::typed_input(ui, "Build", &mut save.header.engine_version.build);
+                changed |= Self::text_input(ui, "Build ID", &mut save.header.engine_version.build_id);
             });
+
+        if changed {
+            self.edits.push(Edit::Header { old, new: save.header.clone() });
+        }
     }
 
     fn show_custom_format(&mut self, ui: &mut egui::Ui) {
@@ -289,7 +790,8 @@ impl AppState {
             return;
         };
 
-        Self::typed_input(ui, "Version", &mut save.custom_format_data.version);
+        let old = save.custom_format_data.clone();
+        let mut changed = Self::typed_input(ui, "Version", &mut save.custom_format_data.version);
 
         let num_entries = save.custom_format_data.entries.len();
         egui::CollapsingHeader::new(format!("Entries ({num_entries})"))
@@ -297,15 +799,19 @@ impl AppState {
                 for (i, entry) in save.custom_format_data.entries.iter_mut().enumerate() {
                     egui::CollapsingHeader::new(i.to_string())
                         .show(ui, |ui| {
-                            Self::typed_input(ui, "GUID", &mut entry.guid);
-                            Self::typed_input(ui, "Value", &mut entry.value);
+                            changed |= Self::typed_input(ui, "GUID", &mut entry.guid);
+                            changed |= Self::typed_input(ui, "Value", &mut entry.value);
                         });
                 }
             });
+
+        if changed {
+            self.edits.push(Edit::CustomFormat { old, new: save.custom_format_data.clone() });
+        }
     }
 
-    fn show_type(ui: &mut egui::Ui, property_type: &mut PropertyType) {
-        Self::text_input(ui, "Name", &mut property_type.name);
+    fn show_type(ui: &mut egui::Ui, property_type: &mut PropertyType) -> bool {
+        let mut changed = Self::text_input(ui, "Name", &mut property_type.name);
 
         let num_tags = property_type.tags.len();
         egui::CollapsingHeader::new(format!("Tags ({num_tags})"))
@@ -314,8 +820,8 @@ impl AppState {
                     egui::CollapsingHeader::new(i.to_string())
                         .default_open(true)
                         .show(ui, |ui| {
-                            Self::typed_input(ui, "Kind", &mut tag.kind);
-                            Self::text_input(ui, "Value", &mut tag.value);
+                            changed |= Self::typed_input(ui, "Kind", &mut tag.kind);
+                            changed |= Self::text_input(ui, "Value", &mut tag.value);
                         });
                 }
             });
@@ -325,9 +831,11 @@ impl AppState {
         for inner_type in &mut property_type.inner_types {
             egui::CollapsingHeader::new(format!("Inner Type: {}", inner_type.name))
                 .show(ui, |ui| {
-                    Self::show_type(ui, inner_type);
+                    changed |= Self::show_type(ui, inner_type);
                 });
         }
+
+        changed
     }
 
     fn show_binary_data(ui: &mut egui::Ui, label: &str, data: &[u8]) {
@@ -358,92 +866,313 @@ impl AppState {
         }).inner.unwrap_or_default()
     }
 
-    fn show_property_value(ui: &mut egui::Ui, label: &str, property_value: &mut PropertyValue, flags: Option<&mut u8>, property_type: &PropertyType) {
+    /// Shows the elements of a list, each with a menu to insert or delete elements, and returns the chosen action
+    ///
+    /// An empty list gets an "Insert" button instead.
+    fn show_list<T>(ui: &mut egui::Ui, values: &mut [T], mut show_element: impl FnMut(&mut egui::Ui, usize, &mut T)) -> ListAction {
+        let mut action = ListAction::None;
+        for (i, value) in values.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                action.update(Self::show_list_context_menu(ui, i));
+                show_element(ui, i, value);
+            });
+        }
+
+        if values.is_empty() && ui.button("Insert").clicked() {
+            action = ListAction::Insert(0);
+        }
+
+        action
+    }
+
+    fn show_format_argument(ui: &mut egui::Ui, label: &str, value: &mut FormatArgumentValue) -> bool {
+        match value {
+            FormatArgumentValue::Int(i) => Self::typed_input(ui, &format!("{label} (Int)"), i),
+            FormatArgumentValue::UInt(u) => Self::typed_input(ui, &format!("{label} (UInt)"), u),
+            FormatArgumentValue::Float(f) => Self::typed_input(ui, &format!("{label} (Float)"), f),
+            FormatArgumentValue::Double(d) => Self::typed_input(ui, &format!("{label} (Double)"), d),
+            FormatArgumentValue::Text(text) => Self::show_text(ui, label, &mut text.flags, &mut text.data),
+            FormatArgumentValue::Gender(g) => Self::typed_input(ui, &format!("{label} (Gender)"), g),
+        }
+    }
+
+    fn show_format_number(ui: &mut egui::Ui, number: &mut FormatNumber) -> bool {
+        let mut changed = Self::show_format_argument(ui, "Source Value", &mut number.source_value);
+        if let Some(options) = &mut number.options {
+            egui::CollapsingHeader::new("Formatting Options")
+                .show(ui, |ui| {
+                    changed |= ui.checkbox(&mut options.always_sign, "Always Sign").changed();
+                    changed |= ui.checkbox(&mut options.use_grouping, "Use Grouping").changed();
+                    changed |= Self::typed_input(ui, "Rounding Mode", &mut options.rounding_mode);
+                    changed |= Self::typed_input(ui, "Minimum Integral Digits", &mut options.minimum_integral_digits);
+                    changed |= Self::typed_input(ui, "Maximum Integral Digits", &mut options.maximum_integral_digits);
+                    changed |= Self::typed_input(ui, "Minimum Fractional Digits", &mut options.minimum_fractional_digits);
+                    changed |= Self::typed_input(ui, "Maximum Fractional Digits", &mut options.maximum_fractional_digits);
+                });
+        }
+        changed | Self::text_input(ui, "Culture Name", &mut number.culture_name)
+    }
+
+    fn show_text(ui: &mut egui::Ui, label: &str, flags: &mut TextFlags, data: &mut TextData) -> bool {
+        egui::CollapsingHeader::new(label)
+            .show(ui, |ui| {
+                let mut int_flags = flags.bits();
+                let mut changed = Self::typed_input(ui, "Flags", &mut int_flags);
+                *flags = TextFlags::from_bits(int_flags).unwrap();
+
+                let mut selected_history = data.history_name();
+                egui::ComboBox::from_label("History Type")
+                    .selected_text(selected_history)
+                    .show_ui(ui, |ui| {
+                        for history_name in TextData::HISTORY_NAMES {
+                            ui.selectable_value(&mut selected_history, history_name, history_name);
+                        }
+                    });
+                if selected_history != data.history_name() && let Some(converted) = data.converted(selected_history) {
+                    *data = converted;
+                    changed = true;
+                }
+
+                match data {
+                    TextData::None { values } => {
+                        let num_values = values.len();
+                        egui::CollapsingHeader::new(format!("Values ({num_values})"))
+                            .show(ui, |ui| {
+                                let action = Self::show_list(ui, values, |ui, i, value| {
+                                    changed |= Self::text_input(ui, &i.to_string(), value);
+                                });
+                                changed |= action != ListAction::None;
+                                action.apply(values, FString::new);
+                            });
+                    }
+                    TextData::Base { namespace, key, source_string } => {
+                        changed |= Self::text_input(ui, "Namespace", namespace);
+                        changed |= Self::text_input(ui, "Key", key);
+                        changed |= Self::text_input(ui, "Source String", source_string);
+                    }
+                    TextData::NamedFormat { source_format, arguments } => {
+                        changed |= Self::show_text(ui, "Source Format", &mut source_format.flags, &mut source_format.data);
+                        egui::CollapsingHeader::new(format!("Arguments ({})", arguments.len()))
+                            .show(ui, |ui| {
+                                for (i, (name, value)) in arguments.iter_mut().enumerate() {
+                                    ui.push_id(i, |ui| {
+                                        changed |= Self::text_input(ui, "Name", name);
+                                        changed |= Self::show_format_argument(ui, "Value", value);
+                                    });
+                                }
+                            });
+                    }
+                    TextData::OrderedFormat { source_format, arguments } => {
+                        changed |= Self::show_text(ui, "Source Format", &mut source_format.flags, &mut source_format.data);
+                        egui::CollapsingHeader::new(format!("Arguments ({})", arguments.len()))
+                            .show(ui, |ui| {
+                                for (i, value) in arguments.iter_mut().enumerate() {
+                                    changed |= Self::show_format_argument(ui, &i.to_string(), value);
+                                }
+                            });
+                    }
+                    TextData::ArgumentFormat { source_format, arguments } => {
+                        changed |= Self::show_text(ui, "Source Format", &mut source_format.flags, &mut source_format.data);
+                        egui::CollapsingHeader::new(format!("Arguments ({})", arguments.len()))
+                            .show(ui, |ui| {
+                                for (i, argument) in arguments.iter_mut().enumerate() {
+                                    ui.push_id(i, |ui| {
+                                        changed |= Self::text_input(ui, "Name", &mut argument.name);
+                                        changed |= match &mut argument.value {
+                                            FormatArgumentDataValue::Int(i) => Self::typed_input(ui, "Value (Int)", i),
+                                            FormatArgumentDataValue::Float(f) => Self::typed_input(ui, "Value (Float)", f),
+                                            FormatArgumentDataValue::Double(d) => Self::typed_input(ui, "Value (Double)", d),
+                                            FormatArgumentDataValue::Text(text) => Self::show_text(ui, "Value", &mut text.flags, &mut text.data),
+                                            FormatArgumentDataValue::Gender(g) => Self::typed_input(ui, "Value (Gender)", g),
+                                        };
+                                    });
+                                }
+                            });
+                    }
+                    TextData::AsNumber { number } | TextData::AsPercent { number } => {
+                        changed |= Self::show_format_number(ui, number);
+                    }
+                    TextData::AsCurrency { currency_code, number } => {
+                        changed |= Self::text_input(ui, "Currency Code", currency_code);
+                        changed |= Self::show_format_number(ui, number);
+                    }
+                    TextData::AsDate { ticks, date_style, time_zone, culture_name } => {
+                        changed |= Self::typed_input(ui, "Ticks", ticks);
+                        changed |= Self::typed_input(ui, "Date Style", date_style);
+                        changed |= Self::text_input(ui, "Time Zone", time_zone);
+                        changed |= Self::text_input(ui, "Culture Name", culture_name);
+                    }
+                    TextData::AsTime { ticks, time_style, time_zone, culture_name } => {
+                        changed |= Self::typed_input(ui, "Ticks", ticks);
+                        changed |= Self::typed_input(ui, "Time Style", time_style);
+                        changed |= Self::text_input(ui, "Time Zone", time_zone);
+                        changed |= Self::text_input(ui, "Culture Name", culture_name);
+                    }
+                    TextData::AsDateTime { ticks, date_style, time_style, time_zone, culture_name } => {
+                        changed |= Self::typed_input(ui, "Ticks", ticks);
+                        changed |= Self::typed_input(ui, "Date Style", date_style);
+                        changed |= Self::typed_input(ui, "Time Style", time_style);
+                        changed |= Self::text_input(ui, "Time Zone", time_zone);
+                        changed |= Self::text_input(ui, "Culture Name", culture_name);
+                    }
+                    TextData::Transform { source_text, transform_type } => {
+                        changed |= Self::show_text(ui, "Source Text", &mut source_text.flags, &mut source_text.data);
+                        changed |= Self::typed_input(ui, "Transform Type", transform_type);
+                    }
+                    TextData::StringTableEntry { table, key } => {
+                        changed |= Self::text_input(ui, "Table", table);
+                        changed |= Self::text_input(ui, "Key", key);
+                    }
+                }
+
+                changed
+            }).body_returned.unwrap_or(false)
+    }
+
+    fn show_tag_container(ui: &mut egui::Ui, label: &str, container: &mut GameplayTagContainer, known: &KnownValues) -> bool {
+        egui::CollapsingHeader::new(format!("{label} ({})", container.tags.len()))
+            .id_salt(label)
+            .show(ui, |ui| {
+                let mut changed = false;
+                let mut delete_index = None;
+                for (i, tag) in container.tags.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.button("🗑").clicked() {
+                            delete_index = Some(i);
+                        }
+                        changed |= ui.text_edit_singleline(tag.as_mut()).changed();
+                    });
+                }
+                if let Some(index) = delete_index {
+                    container.tags.remove(index);
+                    changed = true;
+                }
+
+                // the tag being typed isn't part of the save, so it's kept in egui's memory between frames
+                let new_tag_id = ui.id().with("new_tag");
+                let mut new_tag = ui.data_mut(|d| d.get_temp::<String>(new_tag_id)).unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut new_tag);
+                    if ui.add_enabled(!new_tag.is_empty() && !container.contains(&new_tag), egui::Button::new("Add")).clicked() {
+                        container.add(&new_tag);
+                        new_tag.clear();
+                        changed = true;
+                    }
+                });
+
+                if !new_tag.is_empty() {
+                    let search = new_tag.to_lowercase();
+                    let suggestions = known.tags.iter()
+                        .filter(|tag| tag.to_lowercase().contains(&search) && !container.contains(tag))
+                        .take(MAX_TAG_SUGGESTIONS);
+                    for suggestion in suggestions {
+                        if ui.selectable_label(false, suggestion).clicked() {
+                            new_tag = suggestion.clone();
+                        }
+                    }
+                }
+                ui.data_mut(|d| d.insert_temp(new_tag_id, new_tag));
+
+                changed
+            }).body_returned.unwrap_or(false)
+    }
+
+    /// Shows a value that has no children with their own paths, returning whether it changed
+    fn show_scalar_value(ui: &mut egui::Ui, label: &str, property_value: &mut PropertyValue, flags: Option<&mut u8>, property_type: &PropertyType, known: &KnownValues) -> bool {
         match property_value {
             PropertyValue::StrProperty(s) | PropertyValue::NameProperty(s) | PropertyValue::EnumProperty(s) | PropertyValue::ObjectProperty(s) => {
-                Self::text_input(ui, label, s);
+                Self::text_input(ui, label, s)
+            }
+            PropertyValue::ByteEnumProperty(value) => {
+                let enum_type = property_type.enum_type().unwrap_or_default();
+                let enum_values = known.enum_values.get(&enum_type).map(Vec::as_slice).unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.label(format!("{label}: ")).on_hover_text(&enum_type);
+                    // the dropdown only offers values used elsewhere in the save, so others can be typed in
+                    let mut changed = ui.text_edit_singleline(value.as_mut()).changed();
+                    ui.add_enabled_ui(!enum_values.is_empty(), |ui| {
+                        egui::ComboBox::from_id_salt(label)
+                            .selected_text("Known values")
+                            .show_ui(ui, |ui| {
+                                for enum_value in enum_values {
+                                    if ui.selectable_label(value == enum_value.as_str(), enum_value).clicked() {
+                                        *value = FString::from_str(enum_value);
+                                        changed = true;
+                                    }
+                                }
+                            });
+                    });
+                    changed
+                }).inner
             }
             PropertyValue::BoolProperty(b) => {
                 if let Some(value) = b {
-                    ui.checkbox(value, label);
+                    ui.checkbox(value, label).changed()
                 } else {
                     let flags = flags.expect("flags should not be None if the BoolProperty value is also None");
-                    let mut value = *flags & 0x10 != 0;
+                    let mut value = *flags & BOOL_TRUE_FLAG != 0;
                     ui.checkbox(&mut value, label);
                     if value {
-                        *flags |= 0x10;
+                        *flags |= BOOL_TRUE_FLAG;
                     } else {
-                        *flags &= !0x10;
+                        *flags &= !BOOL_TRUE_FLAG;
                     }
+                    // the value is in the property's flags, so the change is recorded with the property
+                    false
                 }
             }
-            PropertyValue::ByteProperty(b) => {
-                Self::typed_input(ui, label, b);
-            }
-            PropertyValue::IntProperty(i) => {
-                Self::typed_input(ui, label, i);
-            }
-            PropertyValue::FloatProperty(f) => {
-                Self::typed_input(ui, label, f);
-            }
-            PropertyValue::DoubleProperty(d) => {
-                Self::typed_input(ui, label, d);
+            PropertyValue::ByteProperty(b) => Self::typed_input(ui, label, b),
+            PropertyValue::IntProperty(i) => Self::typed_input(ui, label, i),
+            PropertyValue::Int8Property(i) => Self::typed_input(ui, label, i),
+            PropertyValue::Int16Property(i) => Self::typed_input(ui, label, i),
+            PropertyValue::Int64Property(i) => Self::typed_input(ui, label, i),
+            PropertyValue::UInt16Property(i) => Self::typed_input(ui, label, i),
+            PropertyValue::UInt32Property(i) => Self::typed_input(ui, label, i),
+            PropertyValue::UInt64Property(i) => Self::typed_input(ui, label, i),
+            PropertyValue::FloatProperty(f) => Self::typed_input(ui, label, f),
+            PropertyValue::DoubleProperty(d) => Self::typed_input(ui, label, d),
+            PropertyValue::TextProperty { flags, data } => Self::show_text(ui, label, flags, data),
+            PropertyValue::SoftObjectProperty(path) | PropertyValue::SoftClassProperty(path) => {
+                egui::CollapsingHeader::new(label)
+                    .show(ui, |ui| {
+                        let mut changed = Self::text_input(ui, "Package Name", &mut path.package_name);
+                        changed |= Self::text_input(ui, "Asset Name", &mut path.asset_name);
+                        changed | Self::text_input(ui, "Sub-path", &mut path.sub_path)
+                    }).body_returned.unwrap_or(false)
             }
-            PropertyValue::TextProperty { flags, data } => {
+            PropertyValue::CoreUObjectStructProperty(object) => {
                 egui::CollapsingHeader::new(label)
+                    .default_open(true)
                     .show(ui, |ui| {
-                        let mut int_flags = flags.bits();
-                        Self::typed_input(ui, "Flags", &mut int_flags);
-                        *flags = TextFlags::from_bits(int_flags).unwrap();
-                        // TODO: implement selector for TextData type
-                        match data {
-                            TextData::None { values } => {
-                                let num_values = values.len();
-                                egui::CollapsingHeader::new(format!("Values ({num_values})"))
-                                    .show(ui, |ui| {
-                                        for (i, value) in values.iter_mut().enumerate() {
-                                            Self::text_input(ui, &i.to_string(), value);
-                                        }
-                                    });
-                            }
-                            TextData::Base { namespace, key, source_string } => {
-                                Self::text_input(ui, "Namespace", namespace);
-                                Self::text_input(ui, "Key", key);
-                                Self::text_input(ui, "Source String", source_string);
-                            }
-                            TextData::AsDateTime { ticks, date_style, time_style, time_zone, culture_name } => {
-                                Self::typed_input(ui, "Ticks", ticks);
-                                Self::typed_input(ui, "Date Style", date_style);
-                                Self::typed_input(ui, "Time Style", time_style);
-                                Self::text_input(ui, "Time Zone", time_zone);
-                                Self::text_input(ui, "Culture Name", culture_name);
-                            }
-                            TextData::StringTableEntry { table, key } => {
-                                Self::text_input(ui, "Table", table);
-                                Self::text_input(ui, "Key", key);
-                            }
+                        let mut changed = false;
+                        for (name, field) in object.fields_mut() {
+                            changed |= Self::typed_input(ui, name, field);
                         }
-                    });
+                        changed
+                    }).body_returned.unwrap_or(false)
             }
+            PropertyValue::GameplayTagContainerProperty(container) => Self::show_tag_container(ui, label, container, known),
+            _ => unreachable!("containers are shown by show_property_value"),
+        }
+    }
+
+    fn show_property_value(ui: &mut egui::Ui, label: &str, property_value: &mut PropertyValue, flags: Option<&mut u8>, property_type: &PropertyType, path: &[EditStep], cx: &mut EditContext) {
+        match property_value {
             PropertyValue::StructProperty(props) => {
-                Self::show_properties(ui, label, props);
+                Self::show_properties(ui, label, props, path, cx);
             }
             PropertyValue::CustomStructProperty(custom_struct) => {
                 egui::CollapsingHeader::new(label)
                     .default_open(true)
                     .show(ui, |ui| {
-                        Self::typed_input(ui, "Flags", &mut custom_struct.flags);
-                        Self::show_properties(ui, "Properties", &mut custom_struct.properties);
-                        Self::show_binary_data(ui, "Extra", &custom_struct.extra);
-                    });
-            }
-            PropertyValue::CoreUObjectStructProperty(object) => {
-                egui::CollapsingHeader::new(label)
-                    .default_open(true)
-                    .show(ui, |ui| {
-                        for (name, field) in object.fields_mut() {
-                            Self::typed_input(ui, name, field);
+                        let old_flags = custom_struct.flags;
+                        if Self::typed_input(ui, "Flags", &mut custom_struct.flags) {
+                            let mut old = custom_struct.clone();
+                            old.flags = old_flags;
+                            cx.edit_value(path, PropertyValue::CustomStructProperty(old), PropertyValue::CustomStructProperty(custom_struct.clone()));
                         }
+                        Self::show_properties(ui, "Properties", &mut custom_struct.properties, path, cx);
+                        Self::show_binary_data(ui, "Extra", &custom_struct.extra);
                     });
             }
             PropertyValue::ArrayProperty { values } => {
@@ -454,103 +1183,104 @@ impl AppState {
                 }
 
                 let element_type = property_type.element_type();
+                let flags = flags.map_or(0, |flags| *flags);
                 egui::CollapsingHeader::new(format!("{label} ({num_values})"))
                     .id_salt(label)
                     .show(ui, |ui| {
-                        let mut action = ListAction::None;
-                        for (i, value) in values.iter_mut().enumerate() {
-                            ui.horizontal(|ui| {
-                                action.update(Self::show_list_context_menu(ui, i));
-                                Self::show_property_value(ui, &i.to_string(), value, None, &element_type);
-                            });
+                        let action = Self::show_list(ui, values, |ui, i, value| {
+                            Self::show_property_value(ui, &i.to_string(), value, None, &element_type, &child_path(path, EditStep::Element(i)), cx);
+                        });
+                        if action != ListAction::None {
+                            let old = PropertyValue::ArrayProperty { values: values.clone() };
+                            action.apply(values, || element_type.make_default_value(flags));
+                            cx.edit_value(path, old, PropertyValue::ArrayProperty { values: values.clone() });
                         }
-
-                        let flags = match flags {
-                            Some(flags) => *flags,
-                            None => 0,
-                        };
-
-                        match action {
-                            ListAction::Insert(index) => {
-                                values.insert(index, element_type.make_default_value(flags));
-                            }
-                            ListAction::Delete(index) => {
-                                values.remove(index);
-                            }
-                            ListAction::None => (),
+                    });
+            }
+            PropertyValue::SetProperty { removed_count, values } => {
+                let num_values = values.len();
+                let element_type = property_type.element_type();
+                let flags = flags.map_or(0, |flags| *flags);
+                egui::CollapsingHeader::new(format!("{label} ({num_values})"))
+                    .id_salt(label)
+                    .show(ui, |ui| {
+                        let old_removed_count = *removed_count;
+                        if Self::typed_input(ui, "Removed", removed_count) {
+                            let old = PropertyValue::SetProperty { removed_count: old_removed_count, values: values.clone() };
+                            cx.edit_value(path, old, PropertyValue::SetProperty { removed_count: *removed_count, values: values.clone() });
                         }
 
-                        if values.is_empty() && ui.button("Insert").clicked() {
-                            values.push(element_type.make_default_value(flags));
+                        let action = Self::show_list(ui, values, |ui, i, value| {
+                            Self::show_property_value(ui, &i.to_string(), value, None, &element_type, &child_path(path, EditStep::Element(i)), cx);
+                        });
+                        if action != ListAction::None {
+                            let old = PropertyValue::SetProperty { removed_count: *removed_count, values: values.clone() };
+                            action.apply(values, || element_type.make_default_value(flags));
+                            cx.edit_value(path, old, PropertyValue::SetProperty { removed_count: *removed_count, values: values.clone() });
                         }
                     });
             }
             PropertyValue::MapProperty { removed_count, values } => {
                 let num_values = values.len();
+                let flags = flags.map_or(0, |flags| *flags);
                 egui::CollapsingHeader::new(format!("{label} ({num_values})"))
                     .show(ui, |ui| {
-                        Self::typed_input(ui, "Removed", removed_count);
+                        let old_removed_count = *removed_count;
+                        if Self::typed_input(ui, "Removed", removed_count) {
+                            let old = PropertyValue::MapProperty { removed_count: old_removed_count, values: values.clone() };
+                            cx.edit_value(path, old, PropertyValue::MapProperty { removed_count: *removed_count, values: values.clone() });
+                        }
 
-                        let mut action = ListAction::None;
                         let key_type = property_type.element_type();
                         let Some(value_type) = property_type.inner_types.last() else { return; };
-                        for (i, value) in values.iter_mut().enumerate() {
-                            ui.horizontal(|ui| {
-                                action.update(Self::show_list_context_menu(ui, i));
-                                egui::CollapsingHeader::new(i.to_string())
-                                    .default_open(true)
-                                    .show(ui, |ui| {
-                                        Self::show_property_value(ui, "Key", &mut value.0, None, &key_type);
-                                        Self::show_property_value(ui, "Value", &mut value.1, None, value_type);
-                                    });
-                            });
-                        }
-
-                        let flags = match flags {
-                            Some(flags) => *flags,
-                            None => 0,
-                        };
-
-                        match action {
-                            ListAction::Insert(index) => {
-                                let key = key_type.make_default_value(flags);
-                                let value = value_type.make_default_value(flags);
-                                values.insert(index, (key, value));
-                            }
-                            ListAction::Delete(index) => {
-                                values.remove(index);
-                            }
-                            ListAction::None => (),
-                        }
-
-                        if values.is_empty() && ui.button("Insert").clicked() {
-                            let key = key_type.make_default_value(flags);
-                            let value = value_type.make_default_value(flags);
-                            values.push((key, value));
+                        let action = Self::show_list(ui, values, |ui, i, value| {
+                            egui::CollapsingHeader::new(i.to_string())
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    Self::show_property_value(ui, "Key", &mut value.0, None, &key_type, &child_path(path, EditStep::MapKey(i)), cx);
+                                    Self::show_property_value(ui, "Value", &mut value.1, None, value_type, &child_path(path, EditStep::MapValue(i)), cx);
+                                });
+                        });
+                        if action != ListAction::None {
+                            let old = PropertyValue::MapProperty { removed_count: *removed_count, values: values.clone() };
+                            action.apply(values, || (key_type.make_default_value(flags), value_type.make_default_value(flags)));
+                            cx.edit_value(path, old, PropertyValue::MapProperty { removed_count: *removed_count, values: values.clone() });
                         }
                     });
             }
             PropertyValue::UnknownProperty(data) => {
                 Self::show_binary_data(ui, label, data);
             }
+            _ => {
+                let old = property_value.clone();
+                if Self::show_scalar_value(ui, label, property_value, flags, property_type, cx.known) {
+                    cx.edit_value(path, old, property_value.clone());
+                }
+            }
         }
     }
 
-    fn show_property(ui: &mut egui::Ui, property: &mut Property) {
-        Self::text_input(ui, "Name", &mut property.name);
+    fn show_property(ui: &mut egui::Ui, property: &mut Property, path: &[EditStep], cx: &mut EditContext) {
+        let old = PropertyInfo::of(property);
+        let mut changed = Self::text_input(ui, "Name", &mut property.name);
 
-        let Some(property) = &mut property.body else {
-            return;
-        };
+        if let Some(body) = &mut property.body {
+            egui::CollapsingHeader::new(format!("Type: {}", body.property_type.describe()))
+                .id_salt("type")
+                .show(ui, |ui| {
+                    changed |= Self::show_type(ui, &mut body.property_type);
+                });
+            changed |= Self::typed_input(ui, "Flags", &mut body.flags);
 
-        egui::CollapsingHeader::new(format!("Type: {}", property.property_type.describe()))
-            .id_salt("type")
-            .show(ui, |ui| {
-                Self::show_type(ui, &mut property.property_type);
-            });
-        Self::typed_input(ui, "Flags", &mut property.flags);
+            // a bool value can be stored in the flags
+            let old_flags = body.flags;
+            Self::show_property_value(ui, "Value", &mut body.value, Some(&mut body.flags), &body.property_type, path, cx);
+            changed |= body.flags != old_flags;
+        }
 
-        Self::show_property_value(ui, "Value", &mut property.value, Some(&mut property.flags), &property.property_type);
+        if changed {
+            cx.edits.push(Edit::Property { path: path.to_vec(), old, new: PropertyInfo::of(property) });
+        }
     }
 
     fn show_type_menu(ui: &mut egui::Ui, selected_type: &mut Option<&'static str>) -> bool {
@@ -566,7 +1296,7 @@ impl AppState {
         selected
     }
 
-    fn show_properties(ui: &mut egui::Ui, label: &str, properties: &mut Vec<Property>) {
+    fn show_properties(ui: &mut egui::Ui, label: &str, properties: &mut Vec<Property>, path: &[EditStep], cx: &mut EditContext) {
         let num_properties = properties.len();
         egui::CollapsingHeader::new(format!("{label} ({num_properties})"))
             .id_salt(label)
@@ -605,7 +1335,7 @@ impl AppState {
                         egui::CollapsingHeader::new(format!("{}: {}", i, property.name))
                             .id_salt(i.to_string())
                             .show(ui, |ui| {
-                                Self::show_property(ui, property);
+                                Self::show_property(ui, property, &child_path(path, EditStep::Property(i)), cx);
                             });
                     });
                 }
@@ -614,13 +1344,16 @@ impl AppState {
                     ListAction::Insert(index) => {
                         let Some(selected_type) = selected_type else { return; };
                         let field_name = format!("Field{index}");
-                        properties.insert(index, match selected_type {
+                        let property = match selected_type {
                             "EnumProperty" => Property::new_enum(&field_name, "", "", ""),
                             _ => Property::new_scalar(&field_name, PropertyValue::default_for_type(selected_type)),
-                        });
+                        };
+                        properties.insert(index, property.clone());
+                        cx.edits.push(Edit::InsertProperty { path: path.to_vec(), index, property });
                     }
                     ListAction::Delete(index) => {
-                        properties.remove(index);
+                        let property = properties.remove(index);
+                        cx.edits.push(Edit::DeleteProperty { path: path.to_vec(), index, property });
                     },
                     ListAction::None => (),
                 }
@@ -632,10 +1365,16 @@ impl AppState {
             return;
         };
 
-        Self::text_input(ui, "Type", &mut save.save_data.type_name);
-        Self::typed_input(ui, "Flags", &mut save.save_data.flags);
-        Self::show_properties(ui, "Properties", &mut save.save_data.properties);
-        Self::typed_input(ui, "Extra", &mut save.save_data.extra);
+        let mut cx = EditContext { known: &self.known, edits: &mut self.edits };
+        let old = SaveDataInfo::of(&save.save_data);
+        let mut changed = Self::text_input(ui, "Type", &mut save.save_data.type_name);
+        changed |= Self::typed_input(ui, "Flags", &mut save.save_data.flags);
+        Self::show_properties(ui, "Properties", &mut save.save_data.properties, &[], &mut cx);
+        changed |= Self::typed_input(ui, "Extra", &mut save.save_data.extra);
+
+        if changed {
+            cx.edits.push(Edit::SaveData { old, new: SaveDataInfo::of(&save.save_data) });
+        }
     }
 
     fn show_advanced_view(&mut self, ui: &mut egui::Ui) {
@@ -649,80 +1388,78 @@ impl AppState {
             .show(ui, |ui| self.show_save_game(ui));
     }
 
-    fn show_upgrade_level_selector(ui: &mut egui::Ui, player_stats: &mut impl Indexable, level_key: &str, buy_key: &str) -> (bool, i32) {
+    fn show_upgrade_level_selector(ui: &mut egui::Ui, level: &mut i32, buy_level: &mut Option<i32>) -> bool {
         ui.horizontal(|ui| {
-            let Some(current_level) = player_stats.get_key_mut(level_key) else {
-                ui.colored_label(egui::Color32::RED, "Missing upgrade level");
-                return (false, 0);
-            };
             ui.label("Upgrade level: ");
 
-            let mut selected_level = None;
-            for level in 0..=MAX_UPGRADE_LEVEL {
-                if ui.selectable_label(*current_level == level, level.to_string()).clicked() {
-                    selected_level = Some(level);
+            let mut changed = false;
+            for selected_level in 0..=MAX_UPGRADE_LEVEL {
+                if ui.selectable_label(*level == selected_level, selected_level.to_string()).clicked() {
+                    *level = selected_level;
+                    if let Some(buy_level) = buy_level {
+                        *buy_level = selected_level;
+                    }
+                    changed = true;
                 }
             }
 
-            let Some(selected_level) = selected_level else {
-                return (false, match current_level {
-                    PropertyValue::IntProperty(level) => *level,
-                    _ => 0,
-                });
-            };
-
-            *current_level = PropertyValue::IntProperty(selected_level);
-            if let Some(buy_level) = player_stats.get_key_mut(buy_key) {
-                *buy_level = PropertyValue::IntProperty(selected_level);
-            }
-
-            (true, selected_level)
+            changed
         }).inner
     }
 
-    fn show_stat_slider(ui: &mut egui::Ui, label: &str, stat_value: Option<&mut PropertyValue>) -> (bool, f32) {
-        let Some(PropertyValue::FloatProperty(stat_value)) = stat_value else {
-            ui.colored_label(egui::Color32::RED, "Error: Missing or invalid stat value");
-            return (false, 0.0);
-        };
-
+    fn show_stat_slider(ui: &mut egui::Ui, label: &str, stat_value: &mut f32) -> bool {
         // never clamp so the user can play around with unusual values if they want to
-        let response = ui.add(egui::Slider::new(stat_value, 0.0..=1.0).text(label).clamping(SliderClamping::Never));
-        (response.changed(), *stat_value)
+        ui.add(egui::Slider::new(stat_value, 0.0..=1.0).text(label).clamping(SliderClamping::Never)).changed()
     }
 
-    fn show_player_stats(ui: &mut egui::Ui, player_stats: &mut impl Indexable, health: &mut f32) {
-        ui.heading("Health");
+    fn show_player_stat(ui: &mut egui::Ui, stat: &mut PlayerStat) {
+        Self::show_stat_slider(ui, "Ratio", &mut stat.ratio);
+        Self::show_upgrade_level_selector(ui, &mut stat.max_level, &mut stat.buy_level);
+    }
+
+    fn show_health(ui: &mut egui::Ui, stat: &mut PlayerStat, health: Result<&mut f32, &RecordError>) {
+        let health = match health {
+            Ok(health) => health,
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, format!("Error: {e}"));
+                Self::show_player_stat(ui, stat);
+                return;
+            }
+        };
 
         let health_changed = Self::typed_input(ui, "Current", health);
-        let (ratio_changed, ratio) = Self::show_stat_slider(ui, "Ratio", player_stats.get_key_mut("HealthRatio"));
-        let (upgrade_level_changed, upgrade_level) = Self::show_upgrade_level_selector(ui, player_stats, "MaxHealthLevel", "BuyHealthLevel");
+        let ratio_changed = Self::show_stat_slider(ui, "Ratio", &mut stat.ratio);
+        let upgrade_level_changed = Self::show_upgrade_level_selector(ui, &mut stat.max_level, &mut stat.buy_level);
 
-        if health_changed && let Some(PropertyValue::FloatProperty(health_ratio)) = player_stats.get_key_mut("HealthRatio") {
-            *health_ratio = *health / (BASE_HEALTH + upgrade_level as f32 * HEALTH_PER_UPGRADE);
+        let max_health = BASE_HEALTH + stat.max_level as f32 * HEALTH_PER_UPGRADE;
+        if health_changed {
+            stat.ratio = *health / max_health;
         } else if ratio_changed || upgrade_level_changed {
-            *health = ratio * (BASE_HEALTH + upgrade_level as f32 * HEALTH_PER_UPGRADE);
+            *health = stat.ratio * max_health;
         }
+    }
 
+    /// Shows the player's stats, along with Hinako's current health
+    fn show_player_stats(ui: &mut egui::Ui, player_stats: &mut PlayerStats, health: Result<&mut f32, &RecordError>) {
+        ui.heading("Health");
+        Self::show_section(ui, &mut player_stats.health, |ui, stat| Self::show_health(ui, stat, health));
         ui.separator();
 
         ui.heading("Stamina");
-        Self::show_stat_slider(ui, "Ratio", player_stats.get_key_mut("StaminaRatio"));
-        Self::show_upgrade_level_selector(ui, player_stats, "MaxStaminaLevel", "BuyStaminaLevel");
+        Self::show_section(ui, &mut player_stats.stamina, Self::show_player_stat);
         ui.separator();
 
         ui.heading("Sanity");
-        Self::show_stat_slider(ui, "Ratio", player_stats.get_key_mut("SanityRatio"));
-        Self::show_stat_slider(ui, "Current Max Ratio", player_stats.get_key_mut("CurrentMaxSanityRatio"));
-        Self::show_upgrade_level_selector(ui, player_stats, "MaxSanityLevel", "BuySanityLevel");
+        Self::show_section(ui, &mut player_stats.sanity, Self::show_player_stat);
+        Self::show_section(ui, &mut player_stats.current_max_sanity_ratio, |ui, ratio| {
+            Self::show_stat_slider(ui, "Current Max Ratio", ratio);
+        });
         ui.separator();
 
-        let Some(PropertyValue::IntProperty(faith_value)) = player_stats.get_key_mut("FaithValue") else {
-            ui.colored_label(egui::Color32::RED, "Error: Missing faith value");
-            return;
-        };
         ui.heading("Faith");
-        Self::typed_input(ui, "", faith_value);
+        Self::show_section(ui, &mut player_stats.faith_value, |ui, faith_value| {
+            Self::typed_input(ui, "", faith_value);
+        });
     }
 
     fn show_inventory_delete(ui: &mut egui::Ui, index: usize, min_index: usize, delete_index: &mut Option<usize>) {
@@ -747,160 +1484,85 @@ impl AppState {
         });
     }
 
-    fn show_weapons(ui: &mut egui::Ui, inventory: &mut impl Indexable, world: &str) {
-        ui.heading(format!("{world} Weapons"));
-
-        let equip_key = format!("{world}EquippedWeaponIndex");
-        let mut equip_index = match inventory.get_key(&equip_key) {
-            Some(PropertyValue::IntProperty(equip_index)) => *equip_index,
-            _ => -1,
-        };
-        let mut set_equip_index = false;
-
-        let Some(PropertyValue::ArrayProperty { values, .. }) = inventory.get_key_mut(&format!("{world}Weapons")) else {
-            ui.colored_label(egui::Color32::RED, "Error: missing or invalid weapons");
-            return;
-        };
-
+    fn show_weapons(ui: &mut egui::Ui, weapons: &mut WeaponInventory, world: &str) {
+        let equipped_index = weapons.equipped_index;
+        let mut selected_index = None;
         let mut delete_index = None;
-        for (i, inventory_weapon) in values.iter_mut().enumerate() {
+        for (i, weapon) in weapons.weapons.iter_mut().enumerate() {
             ui.horizontal(|ui| {
                 Self::show_inventory_delete(ui, i, MIN_WEAPONS, &mut delete_index);
 
-                let max_durability = match inventory_weapon.get_key_mut("IDIndex") {
-                    Some(PropertyValue::IntProperty(id_index)) => {
-                        let weapon = get_weapon_from_id(*id_index);
+                let item = get_weapon_from_id(weapon.id_index);
+                ui.label("Weapon");
+                Self::show_item_dropdown(ui, &format!("{world} weapon {i}"), &mut weapon.id_index, item);
 
-                        ui.label("Weapon");
-                        Self::show_item_dropdown(ui, &format!("{world} weapon {i}"), id_index, weapon);
-
-                        // grab the weapon definition again in case it changed
-                        match get_weapon_from_id(*id_index) {
-                            Some(weapon) => weapon.max_durability,
-                            None => DEFAULT_MAX_WEAPON_DURABILITY,
-                        }
-                    }
-                    _ => {
-                        ui.colored_label(egui::Color32::RED, "Error: missing or invalid weapon ID index");
-                        DEFAULT_MAX_WEAPON_DURABILITY
-                    }
+                // grab the weapon definition again in case it changed
+                let max_durability = match get_weapon_from_id(weapon.id_index) {
+                    Some(weapon) => weapon.max_durability,
+                    None => DEFAULT_MAX_WEAPON_DURABILITY,
                 };
 
-                match inventory_weapon.get_key_mut("Durability") {
-                    Some(PropertyValue::FloatProperty(durability)) => {
-                        ui.label("Durability");
-                        ui.add(egui::Slider::new(durability, 0.0..=max_durability).clamping(SliderClamping::Never));
-                    }
-                    _ => {
-                        ui.colored_label(egui::Color32::RED, "Error: missing or invalid durability");
-                    }
-                }
+                ui.label("Durability");
+                ui.add(egui::Slider::new(&mut weapon.durability, 0.0..=max_durability).clamping(SliderClamping::Never));
 
-                let is_equipped = i as i32 == equip_index;
+                let is_equipped = i as i32 == equipped_index;
                 if ui.radio(is_equipped, "Equipped").clicked() {
-                    equip_index = i as i32;
-                    set_equip_index = true;
+                    selected_index = Some(i as i32);
                 }
             });
         }
 
         if let Some(index) = delete_index {
-            values.remove(index);
+            weapons.weapons.remove(index);
         }
 
-        if values.len() < MAX_WEAPONS && ui.button("Add weapon").clicked() {
-            values.push(
-                PropertyValue::StructProperty(vec![
-                    Property::new_scalar("Durability", PropertyValue::FloatProperty(0.0)),
-                    Property::new_scalar("IDIndex", PropertyValue::IntProperty(NO_WEAPON.id_index)),
-                    Property::new_none(),
-                ])
-            );
+        if weapons.weapons.len() < MAX_WEAPONS && ui.button("Add weapon").clicked() {
+            weapons.weapons.push(WeaponSlot::new(NO_WEAPON.id_index, 0.0));
         }
 
         // I *think* the equipped and target indexes are always set to the same value in practice, but I don't know for sure,
         // so we shouldn't change things unless the user explicitly requested a change
-        if set_equip_index {
-            if let Some(PropertyValue::IntProperty(equip_index_value)) = inventory.get_key_mut(&equip_key) {
-                *equip_index_value = equip_index;
-            }
-            if let Some(PropertyValue::IntProperty(target_index_value)) = inventory.get_key_mut(&format!("{world}TargetWeaponIndex")) {
-                *target_index_value = equip_index;
-            }
+        if let Some(index) = selected_index {
+            weapons.equipped_index = index;
+            weapons.target_index = index;
         }
     }
 
-    fn show_consumables(ui: &mut egui::Ui, inventory: &mut impl Indexable) {
-        ui.heading("Consumables");
-
-        let Some(PropertyValue::ArrayProperty { values, .. }) = inventory.get_key_mut("Consumables") else {
-            ui.colored_label(egui::Color32::RED, "Error: missing or invalid consumables");
-            return;
-        };
-
+    fn show_consumables(ui: &mut egui::Ui, consumables: &mut Vec<ConsumableSlot>) {
         let mut delete_index = None;
-        for (i, inventory_consumable) in values.iter_mut().enumerate() {
+        for (i, consumable) in consumables.iter_mut().enumerate() {
             ui.horizontal(|ui| {
                 Self::show_inventory_delete(ui, i, MIN_CONSUMABLE_ITEMS, &mut delete_index);
 
-                let max_quantity = match inventory_consumable.get_key_mut("IDIndex") {
-                    Some(PropertyValue::IntProperty(id_index)) => {
-                        let consumable = get_consumable_item_from_id(*id_index);
-
-                        ui.label("Item");
-                        Self::show_item_dropdown(ui, &format!("consumable {i}"), id_index, consumable);
+                let item = get_consumable_item_from_id(consumable.id_index);
+                ui.label("Item");
+                Self::show_item_dropdown(ui, &format!("consumable {i}"), &mut consumable.id_index, item);
 
-                        match consumable {
-                            Some(consumable) => consumable.max_stack,
-                            None => DEFAULT_MAX_CONSUMABLE_ITEM_STACK,
-                        }
-                    }
-                    _ => {
-                        ui.colored_label(egui::Color32::RED, "Error: missing or invalid consumable ID index");
-                        DEFAULT_MAX_CONSUMABLE_ITEM_STACK
-                    }
+                let max_quantity = match item {
+                    Some(item) => item.max_stack,
+                    None => DEFAULT_MAX_CONSUMABLE_ITEM_STACK,
                 };
 
-                match inventory_consumable.get_key_mut("Quantity") {
-                    Some(PropertyValue::IntProperty(quantity)) => {
-                        Self::typed_input(ui, "Quantity", quantity);
-                        ui.label(format!(" / {max_quantity}"));
-                    }
-                    _ => {
-                        ui.colored_label(egui::Color32::RED, "Error: missing or invalid quantity");
-                    }
-                }
+                Self::typed_input(ui, "Quantity", &mut consumable.quantity);
+                ui.label(format!(" / {max_quantity}"));
             });
         }
 
         if let Some(index) = delete_index {
-            values.remove(index);
+            consumables.remove(index);
         }
 
-        if values.len() < MAX_CONSUMABLE_ITEMS && ui.button("Add consumable").clicked() {
-            values.push(
-                PropertyValue::StructProperty(vec![
-                    Property::new_scalar("Quantity", PropertyValue::IntProperty(0)),
-                    Property::new_scalar("IDIndex", PropertyValue::IntProperty(NO_CONSUMABLE_ITEM.id_index)),
-                    Property::new_none(),
-                ])
-            );
+        if consumables.len() < MAX_CONSUMABLE_ITEMS && ui.button("Add consumable").clicked() {
+            consumables.push(ConsumableSlot::new(NO_CONSUMABLE_ITEM.id_index, 0));
         }
     }
 
-    fn show_named_items(ui: &mut egui::Ui, item_flags: &mut [PropertyValue], item_names: &[&str]) {
+    fn show_named_items(ui: &mut egui::Ui, item_flags: &mut [bool], item_names: &[&str]) {
         if ui.button("Add all").clicked() {
-            for item_flag in item_flags.iter_mut() {
-                *item_flag = PropertyValue::BoolProperty(Some(true));
-            }
+            item_flags.fill(true);
         }
 
         for (i, item_flag) in item_flags.iter_mut().enumerate() {
-            let PropertyValue::BoolProperty(Some(item_flag)) = item_flag else {
-                ui.colored_label(egui::Color32::RED, "Error: missing or invalid item flag");
-                continue;
-            };
-
             match item_names.get(i) {
                 Some(item_name) => ui.checkbox(item_flag, *item_name),
                 None => ui.checkbox(item_flag, format!("Unknown {i}")),
@@ -908,28 +1570,6 @@ impl AppState {
         }
     }
 
-    fn show_key_items(ui: &mut egui::Ui, inventory: &mut impl Indexable) {
-        ui.heading("Key Items");
-
-        let Some(PropertyValue::ArrayProperty { values, .. }) = inventory.get_key_mut("KeyItems") else {
-            ui.colored_label(egui::Color32::RED, "Error: missing or invalid key items");
-            return;
-        };
-
-        Self::show_named_items(ui, values, &KEY_ITEM_NAMES);
-    }
-
-    fn show_letters(ui: &mut egui::Ui, inventory: &mut impl Indexable) {
-        ui.heading("Letters");
-
-        let Some(PropertyValue::ArrayProperty { values, .. }) = inventory.get_key_mut("Letters") else {
-            ui.colored_label(egui::Color32::RED, "Error: missing or invalid letters");
-            return;
-        };
-
-        Self::show_named_items(ui, values, &LETTER_NAMES);
-    }
-
     fn set_omamori_dropdown_text(dropdown: egui::ComboBox, id_index: i32) -> egui::ComboBox {
         match OMAMORI_NAMES.get(id_index as usize) {
             Some(name) => dropdown.selected_text(*name),
@@ -938,140 +1578,129 @@ impl AppState {
         }
     }
 
-    fn show_omamori(ui: &mut egui::Ui, inventory: &mut impl Indexable) {
-        ui.heading("Omamori");
+    fn show_obtained_omamori(ui: &mut egui::Ui, omamories: &mut Vec<OmamoriSlot>, obtained: &HashSet<i32>) {
 
-        ui.label("Obtained");
-        let mut obtained = HashSet::new();
-        match inventory.get_key_mut("Omamories") {
-            Some(PropertyValue::ArrayProperty { values, .. }) => {
-                for value in values.iter() {
-                    if let Some(PropertyValue::IntProperty(id_index)) = value.get_key("IDIndex") && *id_index >= 0 {
-                        obtained.insert(*id_index);
-                    }
+        if ui.button("Add all").clicked() {
+            for i in 0..OMAMORI_NAMES.len() as i32 {
+                if !obtained.contains(&i) {
+                    omamories.push(OmamoriSlot::new(i));
                 }
+            }
+        }
 
-                if ui.button("Add all").clicked() {
-                    for i in 0..OMAMORI_NAMES.len() {
-                        if !obtained.contains(&(i as i32)) {
-                            values.push(
-                                PropertyValue::StructProperty(vec![
-                                    Property::new_scalar("IDIndex", PropertyValue::IntProperty(i as i32)),
-                                    Property::new_none(),
-                                ])
-                            );
+        let mut delete_index = None;
+        for (i, omamori) in omamories.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                Self::show_inventory_delete(ui, i, 0, &mut delete_index);
+
+                let id_index = &mut omamori.id_index;
+                let dropdown = egui::ComboBox::from_id_salt(format!("obtained omamori {i}"));
+                let dropdown = Self::set_omamori_dropdown_text(dropdown, *id_index);
+                dropdown.show_ui(ui, |ui| {
+                    ui.selectable_value(id_index, -1, "None");
+                    for (i, name) in OMAMORI_NAMES.iter().enumerate() {
+                        let i = i as i32;
+                        // only show this omamori + ones that we don't already have
+                        if i == *id_index || !obtained.contains(&i) {
+                            ui.selectable_value(id_index, i, *name);
                         }
                     }
-                }
-
-                let mut delete_index = None;
+                });
+            });
+        }
 
-                for (i, value) in values.iter_mut().enumerate() {
-                    ui.horizontal(|ui| {
-                        Self::show_inventory_delete(ui, i, 0, &mut delete_index);
+        if let Some(index) = delete_index {
+            omamories.remove(index);
+        }
 
-                        let Some(PropertyValue::IntProperty(id_index)) = value.get_key_mut("IDIndex") else {
-                            ui.colored_label(egui::Color32::RED, "Error: missing or invalid omamori index");
-                            return;
-                        };
+        if obtained.len() < OMAMORI_NAMES.len() && ui.button("Add omamori").clicked() {
+            omamories.push(OmamoriSlot::new(-1));
+        }
 
-                        let dropdown = egui::ComboBox::from_id_salt(format!("obtained omamori {i}"));
-                        let dropdown = Self::set_omamori_dropdown_text(dropdown, *id_index);
-                        dropdown.show_ui(ui, |ui| {
-                            ui.selectable_value(id_index, -1, "None");
-                            for (i, name) in OMAMORI_NAMES.iter().enumerate() {
-                                let i = i as i32;
-                                // only show this omamori + ones that we don't already have
-                                if i == *id_index || !obtained.contains(&i) {
-                                    ui.selectable_value(id_index, i, *name);
-                                }
-                            }
-                        });
-                    });
-                }
+    }
 
-                if let Some(index) = delete_index {
-                    values.remove(index);
-                }
+    fn show_equipped_omamori(ui: &mut egui::Ui, equipped_omamories: &mut Vec<i32>, obtained: &HashSet<i32>) {
+        let mut delete_index = None;
+        for (i, id_index) in equipped_omamories.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                Self::show_inventory_delete(ui, i, MIN_OMAMORI_SLOTS, &mut delete_index);
+
+                let dropdown = egui::ComboBox::from_id_salt(format!("equipped omamori {i}"));
+                let dropdown = Self::set_omamori_dropdown_text(dropdown, *id_index);
+                dropdown.show_ui(ui, |ui| {
+                    ui.selectable_value(id_index, -1, "None");
+                    for (i, name) in OMAMORI_NAMES.iter().enumerate() {
+                        let i = i as i32;
+                        // only show this omamori + ones that we have
+                        if i == *id_index || obtained.contains(&i) {
+                            ui.selectable_value(id_index, i, *name);
+                        }
+                    }
+                });
+            });
+        }
 
-                if obtained.len() < OMAMORI_NAMES.len() && ui.button("Add omamori").clicked() {
-                    values.push(
-                        PropertyValue::StructProperty(vec![
-                            Property::new_scalar("IDIndex", PropertyValue::IntProperty(-1)),
-                            Property::new_none(),
-                        ])
-                    );
-                }
-            }
-            _ => {
-                ui.colored_label(egui::Color32::RED, "Error: missing or invalid omamori inventory");
-            }
+        if let Some(index) = delete_index {
+            equipped_omamories.remove(index);
         }
 
-        ui.label("Equipped");
-        match inventory.get_key_mut("EquippedOmamories") {
-            Some(PropertyValue::ArrayProperty { values, .. }) => {
-                let mut delete_index = None;
+        if equipped_omamories.len() < MAX_OMAMORI_SLOTS && ui.button("Add omamori slot").clicked() {
+            equipped_omamories.push(-1);
+        }
+    }
 
-                for (i, value) in values.iter_mut().enumerate() {
-                    ui.horizontal(|ui| {
-                        Self::show_inventory_delete(ui, i, MIN_OMAMORI_SLOTS, &mut delete_index);
+    fn show_omamori(ui: &mut egui::Ui, omamories: &mut Section<Vec<OmamoriSlot>>, equipped_omamories: &mut Section<Vec<i32>>) {
+        ui.heading("Omamori");
 
-                        let PropertyValue::IntProperty(id_index) = value else {
-                            ui.colored_label(egui::Color32::RED, "Error: missing or invalid omamori index");
-                            return;
-                        };
+        let obtained: HashSet<_> = match omamories {
+            Ok(omamories) => omamories.iter().map(|omamori| omamori.id_index).filter(|id_index| *id_index >= 0).collect(),
+            Err(_) => HashSet::new(),
+        };
 
-                        let dropdown = egui::ComboBox::from_id_salt(format!("equipped omamori {i}"));
-                        let dropdown = Self::set_omamori_dropdown_text(dropdown, *id_index);
-                        dropdown.show_ui(ui, |ui| {
-                            ui.selectable_value(id_index, -1, "None");
-                            for (i, name) in OMAMORI_NAMES.iter().enumerate() {
-                                let i = i as i32;
-                                // only show this omamori + ones that we have
-                                if i == *id_index || obtained.contains(&i) {
-                                    ui.selectable_value(id_index, i, *name);
-                                }
-                            }
-                        });
-                    });
-                }
+        ui.label("Obtained");
+        Self::show_section(ui, omamories, |ui, omamories| Self::show_obtained_omamori(ui, omamories, &obtained));
 
-                if let Some(index) = delete_index {
-                    values.remove(index);
-                }
+        ui.label("Equipped");
+        Self::show_section(ui, equipped_omamories, |ui, equipped| Self::show_equipped_omamori(ui, equipped, &obtained));
+    }
 
-                if values.len() < MAX_OMAMORI_SLOTS && ui.button("Add omamori slot").clicked() {
-                    values.push(PropertyValue::IntProperty(-1));
-                }
-            }
-            _ => {
-                ui.colored_label(egui::Color32::RED, "Error: missing or invalid equipped omamori inventory");
+    /// Shows the editor for a section of a record, or the error if it couldn't be read
+    fn show_section<T>(ui: &mut egui::Ui, section: &mut Section<T>, show: impl FnOnce(&mut egui::Ui, &mut T)) {
+        match section {
+            Ok(value) => show(ui, value),
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, format!("Error: {e}"));
             }
         }
     }
 
-    fn show_inventory(ui: &mut egui::Ui, inventory: &mut impl Indexable) {
-        Self::show_weapons(ui, inventory, "Fog");
+    fn show_inventory(ui: &mut egui::Ui, inventory: &mut InventoryRecord) {
+        ui.heading("Fog Weapons");
+        Self::show_section(ui, &mut inventory.fog_weapons, |ui, weapons| Self::show_weapons(ui, weapons, "Fog"));
         ui.separator();
-        Self::show_weapons(ui, inventory, "Dark");
+        ui.heading("Dark Weapons");
+        Self::show_section(ui, &mut inventory.dark_weapons, |ui, weapons| Self::show_weapons(ui, weapons, "Dark"));
         ui.separator();
-        Self::show_consumables(ui, inventory);
+        ui.heading("Consumables");
+        Self::show_section(ui, &mut inventory.consumables, Self::show_consumables);
         ui.separator();
-        Self::show_omamori(ui, inventory);
+        Self::show_omamori(ui, &mut inventory.omamories, &mut inventory.equipped_omamories);
         ui.separator();
-        Self::show_key_items(ui, inventory);
+        ui.heading("Key Items");
+        Self::show_section(ui, &mut inventory.key_items, |ui, key_items| Self::show_named_items(ui, key_items, &KEY_ITEM_NAMES));
         ui.separator();
-        Self::show_letters(ui, inventory);
+        ui.heading("Letters");
+        Self::show_section(ui, &mut inventory.letters, |ui, letters| Self::show_named_items(ui, letters, &LETTER_NAMES));
     }
 
-    fn show_difficulty<T: DifficultyLevel>(ui: &mut egui::Ui, name: &str, save: &mut impl Indexable, property_name: &str) -> Result<()> {
+    fn show_difficulty<T: DifficultyLevel>(ui: &mut egui::Ui, name: &str, save_data: &mut SaveGameData, property_name: &str, edits: &mut Vec<Edit>) -> Result<()> {
         ui.horizontal(|ui| {
             ui.label(name);
 
-            let level_property = save.get_key_mut(property_name);
+            let index = save_data.properties.iter().position(|property| property.name == property_name);
+            let level_property = index.and_then(|index| save_data.properties[index].body.as_mut()).map(|body| &mut body.value);
             let mut selected_level = match level_property {
-                Some(PropertyValue::EnumProperty(level)) => {
+                Some(PropertyValue::EnumProperty(level) | PropertyValue::ByteEnumProperty(level)) => {
                     let level = level.as_str();
                     match T::from_str(level) {
                         Ok(level) => level,
@@ -1087,6 +1716,7 @@ impl AppState {
                 }
                 _ => T::default(),
             };
+            let current_level = selected_level;
 
             egui::ComboBox::from_id_salt(name)
                 .selected_text(selected_level.name())
@@ -1096,33 +1726,53 @@ impl AppState {
                     }
                 });
 
-            match level_property {
-                Some(PropertyValue::EnumProperty(level)) => {
-                    let level = level.as_mut();
-                    level.clear();
-                    level.push_str(selected_level.as_str());
+            // we don't need to do anything if the level is unchanged, including a missing level left at the default value
+            if selected_level == current_level {
+                return Ok(());
+            }
+
+            match (index, level_property) {
+                (Some(index), Some(level_property)) => {
+                    let old = level_property.clone();
+                    match level_property {
+                        PropertyValue::EnumProperty(level) | PropertyValue::ByteEnumProperty(level) => {
+                            let level = level.as_mut();
+                            level.clear();
+                            level.push_str(selected_level.as_str());
+                        }
+                        // at this point we know level_property, if present, is a valid enum value, otherwise
+                        // we would have bailed above
+                        _ => unreachable!(),
+                    }
+                    edits.push(Edit::Value { path: vec![EditStep::Property(index)], old, new: level_property.clone() });
                 }
-                // at this point we know level_property, if present, is a valid EnumProperty, otherwise
-                // we would have bailed above
-                Some(_) => unreachable!(),
-                None if selected_level != T::default() => {
-                    save.add_property(
-                        Property::new_enum(property_name, T::namespace(), T::type_name(), selected_level.as_str())
-                    )?;
+                _ => {
+                    // like add_property, put the new property before the None property that ends the list
+                    let index = save_data.properties.iter().position(Property::is_none).unwrap_or(save_data.properties.len());
+                    let property = Property::new_enum(property_name, T::namespace(), T::type_name(), selected_level.as_str());
+                    save_data.insert_property(index, property.clone())?;
+                    edits.push(Edit::InsertProperty { path: Vec::new(), index, property });
                 }
-                // we don't need to do anything if the level is the default value
-                None => (),
             }
 
             Ok(())
         }).inner
     }
 
-    fn show_difficulties(ui: &mut egui::Ui, save: &mut impl Indexable) -> Result<()> {
+    fn show_difficulties(ui: &mut egui::Ui, save_data: &mut SaveGameData, edits: &mut Vec<Edit>) -> Result<()> {
         ui.heading("Difficulty");
 
-        Self::show_difficulty::<ActionLevel>(ui, "Action", save, "ActionLevel")?;
-        Self::show_difficulty::<RiddleLevel>(ui, "Puzzles", save, "RiddleLevel")
+        Self::show_difficulty::<ActionLevel>(ui, "Action", save_data, "ActionLevel", edits)?;
+        Self::show_difficulty::<RiddleLevel>(ui, "Puzzles", save_data, "RiddleLevel", edits)
+    }
+
+    /// Writes a record to the save, recording the edit to its properties
+    fn write_record<R: SaveRecord>(save: &mut SaveGame, record: &R, edits: &mut Vec<Edit>) -> Result<(), RecordError> {
+        let old = R::properties(save)?.clone();
+        // a failed write may have changed some fields already, so record the edit either way so it can be undone
+        let result = record.write_to_save(save);
+        edits.push(Edit::record::<R>(old, R::properties(save)?.clone()));
+        result
     }
 
     fn show_simple_view(&mut self, ui: &mut egui::Ui) {
@@ -1143,54 +1793,48 @@ impl AppState {
             );
         }
 
-        if let Err(e) = Self::show_difficulties(ui, &mut save.save_data) {
+        if let Err(e) = Self::show_difficulties(ui, &mut save.save_data, &mut self.edits) {
             self.error_message = Some(format!("Failed to set difficulty: {e}"));
         }
         ui.separator();
 
-        let mut health = match prop!(&save.save_data, ["HinakoRecord"]["Health"]) {
-            Some(PropertyValue::FloatProperty(health)) => *health,
-            _ => 0.0,
-        };
-
-        let Some(player_state_record) = save.save_data.get_key_mut("PlayerStateRecord") else {
-            ui.colored_label(egui::Color32::RED, "Error: missing PlayerStateRecord");
-            return;
-        };
+        let hinako_record = HinakoRecord::from_save(save);
+        match PlayerStats::from_save(save) {
+            Ok(player_stats) => {
+                let mut edited_stats = player_stats.clone();
+                let mut health = hinako_record.as_ref().map(|record| record.health);
+                Self::show_player_stats(ui, &mut edited_stats, health.as_mut().map_err(|e| *e));
 
-        match player_state_record.get_key_mut("Data") {
-            Some(data) => Self::show_player_stats(ui, data, &mut health),
-            None => {
-                ui.colored_label(egui::Color32::RED, "Error: missing Data property in PlayerStateRecord");
+                if edited_stats != player_stats && let Err(e) = Self::write_record(save, &edited_stats, &mut self.edits) {
+                    self.error_message = Some(format!("Failed to update player stats: {e}"));
+                }
+                // if the health was updated above, save it
+                if let (Ok(hinako_record), Ok(health)) = (&hinako_record, health)
+                    && health != hinako_record.health
+                    && let Err(e) = Self::write_record(save, &HinakoRecord { health }, &mut self.edits)
+                {
+                    self.error_message = Some(format!("Failed to update health: {e}"));
+                }
+            }
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, format!("Error: {e}"));
             }
         }
 
         ui.separator();
 
-        let Some(PropertyValue::ArrayProperty { values, .. }) = player_state_record.get_key_mut("ComponentRecords") else {
-            ui.colored_label(egui::Color32::RED, "Error: missing or invalid ComponentRecords property in PlayerStateRecord");
-            return;
-        };
+        match InventoryRecord::from_save(save) {
+            Ok(inventory) => {
+                let mut edited_inventory = inventory.clone();
+                Self::show_inventory(ui, &mut edited_inventory);
 
-        let mut found_inventory = false;
-        for component_record in values {
-            if let Some(class) = component_record.get_key_mut("Class")
-                && class == PLAYER_INVENTORY_COMPONENT_CLASS
-                && let Some(data) = component_record.get_key_mut("Data")
-            {
-                Self::show_inventory(ui, data);
-                found_inventory = true;
-                break;
+                if edited_inventory != inventory && let Err(e) = Self::write_record(save, &edited_inventory, &mut self.edits) {
+                    self.error_message = Some(format!("Failed to update inventory: {e}"));
+                }
+            }
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, format!("Error: {e}"));
             }
-        }
-
-        if !found_inventory {
-            ui.colored_label(egui::Color32::RED, "Error: missing inventory component record");
-        }
-
-        // if the health was updated above, save it
-        if let Some(PropertyValue::FloatProperty(health_property)) = prop_mut!(&mut save.save_data, ["HinakoRecord"]["Health"]) {
-            *health_property = health;
         }
     }
 }
@@ -1201,9 +1845,15 @@ impl eframe::App for AppState {
             self.default_pixels_per_point = Some(ctx.pixels_per_point());
         }
 
+        // closing the window or File > Exit asks about unsaved changes first
+        if ctx.input(|i| i.viewport().close_requested()) && !self.allow_close && self.is_modified() {
+            ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+            self.pending_action = Some(GuardedAction::Exit);
+        }
+
         let open_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
         if ctx.input_mut(|i| i.consume_shortcut(&open_shortcut)) {
-            self.open_save();
+            self.guard(ctx, GuardedAction::Open);
         }
 
         let save_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::S);
@@ -1211,6 +1861,19 @@ impl eframe::App for AppState {
             self.save();
         }
 
+        // text fields have their own undo, so leave the shortcuts to them while one is being edited
+        let undo_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+        let redo_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+        if !ctx.wants_keyboard_input() {
+            if ctx.input_mut(|i| i.consume_shortcut(&undo_shortcut)) {
+                self.undo();
+            }
+
+            if ctx.input_mut(|i| i.consume_shortcut(&redo_shortcut)) {
+                self.redo();
+            }
+        }
+
         // Menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
@@ -1220,7 +1883,7 @@ impl eframe::App for AppState {
                         .clicked()
                     {
                         ui.close();
-                        self.open_save();
+                        self.guard(ctx, GuardedAction::Open);
                     }
 
                     ui.separator();
@@ -1247,18 +1910,89 @@ impl eframe::App for AppState {
 
                     ui.separator();
 
+                    if ui.add_enabled(self.save_path.is_some(), egui::Button::new("Restore backup..."))
+                        .clicked()
+                    {
+                        ui.close();
+                        self.show_backup_browser();
+                    }
+
+                    if ui.add_enabled(can_save, egui::Button::new("Compare with..."))
+                        .clicked()
+                    {
+                        ui.close();
+                        self.compare_with();
+                    }
+
+                    ui.separator();
+
+                    if ui.button("Import JSON...").clicked() {
+                        ui.close();
+                        self.guard(ctx, GuardedAction::ImportJson);
+                    }
+
+                    if ui.add_enabled(can_save, egui::Button::new("Export JSON..."))
+                        .clicked()
+                    {
+                        ui.close();
+                        self.export_json();
+                    }
+
+                    ui.separator();
+
                     if ui.button("Exit").clicked() {
                         ui.close();
                         ctx.send_viewport_cmd(ViewportCommand::Close);
                     }
                 });
+
+                ui.menu_button("Edit", |ui| {
+                    if ui
+                        .add_enabled(
+                            self.history.can_undo(),
+                            egui::Button::new("Undo").shortcut_text(ctx.format_shortcut(&undo_shortcut)),
+                        )
+                        .clicked()
+                    {
+                        ui.close();
+                        self.undo();
+                    }
+
+                    if ui
+                        .add_enabled(
+                            self.history.can_redo(),
+                            egui::Button::new("Redo").shortcut_text(ctx.format_shortcut(&redo_shortcut)),
+                        )
+                        .clicked()
+                    {
+                        ui.close();
+                        self.redo();
+                    }
+                });
             });
         });
 
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
-            if ui.add(egui::Slider::new(&mut self.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE).text("UI Scale")).changed() {
-                ctx.set_pixels_per_point(self.ui_scale * self.default_pixels_per_point.unwrap());
-            }
+            ui.horizontal(|ui| {
+                if ui.add(egui::Slider::new(&mut self.ui_scale, MIN_UI_SCALE..=MAX_UI_SCALE).text("UI Scale")).changed() {
+                    ctx.set_pixels_per_point(self.ui_scale * self.default_pixels_per_point.unwrap());
+                }
+                ui.separator();
+                ui.add(egui::DragValue::new(&mut self.backup_generations).range(0..=MAX_BACKUP_GENERATIONS))
+                    .on_hover_text("Number of backups of each save to keep. Set to 0 to disable backups.");
+                ui.label("Backups kept");
+                ui.separator();
+                ui.checkbox(&mut self.detect_custom_structs, "Detect structs")
+                    .on_hover_text("Decode the data of objects of unknown classes when it can be read as a struct. Takes effect the next time a save is opened.");
+                if self.save.is_some() && !self.undecoded_classes.is_empty() {
+                    ui.separator();
+                    ui.label(format!("Undecoded classes: {}", self.undecoded_classes.len()))
+                        .on_hover_text(format!(
+                            "The data of objects of these classes is shown as raw bytes:\n\n{}",
+                            self.undecoded_classes.join("\n"),
+                        ));
+                }
+            });
         });
 
         if self.save.is_some() {
@@ -1299,6 +2033,11 @@ impl eframe::App for AppState {
             });
         }
 
+        self.record_edits(ctx);
+        self.update_title(ctx);
+        self.show_comparison(ctx);
+        self.backup_window(ctx);
+        self.unsaved_changes_modal(ctx);
         self.error_modal(ctx);
     }
 
@@ -1307,6 +2046,8 @@ impl eframe::App for AppState {
             default_pixels_per_point: self.default_pixels_per_point,
             ui_scale: self.ui_scale,
             last_directory: self.last_directory.clone(),
+            backup_generations: self.backup_generations,
+            detect_custom_structs: self.detect_custom_structs,
         };
         eframe::set_value(storage, SETTINGS_KEY, &settings);
     }
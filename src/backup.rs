@@ -0,0 +1,182 @@
+//! Backups of save files made before they're overwritten.
+//!
+//! Backups are kept in a folder next to the save, named after the save and the local time the backup was made, e.g.
+//! `backups/SaveData0-20251001-153012-345.sav`. If a backup with that name already exists, a number is added to the
+//! end, e.g. `SaveData0-20251001-153012-345-1.sav`. Only a limited number of the most recent backups of each save are
+//! kept.
+
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDateTime};
+
+/// Name of the folder backups are stored in, relative to the folder containing the save
+pub const BACKUP_DIR_NAME: &str = "backups";
+/// Default number of backups to keep for each save
+pub const DEFAULT_GENERATIONS: usize = 10;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+/// A backup of a save file
+#[derive(Debug, Clone)]
+pub struct Backup {
+    /// The path of the backup file
+    pub path: PathBuf,
+    /// The local time the backup was made
+    pub timestamp: NaiveDateTime,
+    /// The size of the backup in bytes
+    pub size: u64,
+}
+
+/// Returns the folder backups of the given save are stored in.
+pub fn backup_dir(save_path: &Path) -> PathBuf {
+    save_path.parent().unwrap_or(Path::new("")).join(BACKUP_DIR_NAME)
+}
+
+/// Splits a save path into the file stem and extension used to name its backups
+fn name_parts(save_path: &Path) -> Result<(String, String)> {
+    let stem = save_path.file_stem().ok_or_else(|| anyhow!("{} is not a file path", save_path.display()))?;
+    let extension = save_path.extension().map_or_else(String::new, |e| format!(".{}", e.to_string_lossy()));
+    Ok((stem.to_string_lossy().into_owned(), extension))
+}
+
+/// Parses the part of a backup's file name after the save's name, returning the timestamp and the number added to
+/// tell apart backups made at the same time
+fn parse_backup_name(name: &str) -> Option<(NaiveDateTime, u32)> {
+    if let Ok(timestamp) = NaiveDateTime::parse_from_str(name, TIMESTAMP_FORMAT) {
+        return Some((timestamp, 0));
+    }
+
+    let (timestamp, number) = name.rsplit_once('-')?;
+    Some((NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?, number.parse().ok()?))
+}
+
+/// Lists the backups of the given save, newest first.
+pub fn list_backups(save_path: &Path) -> Result<Vec<Backup>> {
+    let dir = backup_dir(save_path);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let (stem, extension) = name_parts(save_path)?;
+    let prefix = format!("{stem}-");
+    let mut backups = Vec::new();
+    for entry in dir.read_dir()? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        let Some(name) = file_name.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(&extension)) else {
+            continue;
+        };
+        // files for other saves whose names happen to start with this save's name won't parse as a timestamp
+        let Some((timestamp, number)) = parse_backup_name(name) else {
+            continue;
+        };
+
+        backups.push((number, Backup {
+            path: entry.path(),
+            timestamp,
+            size: entry.metadata()?.len(),
+        }));
+    }
+
+    backups.sort_by_key(|(number, b)| std::cmp::Reverse((b.timestamp, *number)));
+    Ok(backups.into_iter().map(|(_, backup)| backup).collect())
+}
+
+/// Deletes all but the given number of most recent backups of a save.
+pub fn prune_backups(save_path: &Path, generations: usize) -> Result<()> {
+    for backup in list_backups(save_path)?.into_iter().skip(generations) {
+        std::fs::remove_file(&backup.path)?;
+    }
+    Ok(())
+}
+
+/// Creates a new, empty backup file for the given time
+///
+/// An existing backup is never overwritten, e.g. one made by saving twice in the same millisecond. A number is added to
+/// the name instead.
+fn create_backup_file(dir: &Path, stem: &str, extension: &str, time: NaiveDateTime) -> Result<(PathBuf, File)> {
+    let timestamp = time.format(TIMESTAMP_FORMAT);
+    let mut number = 0u32;
+    loop {
+        let name = if number == 0 {
+            format!("{stem}-{timestamp}{extension}")
+        } else {
+            format!("{stem}-{timestamp}-{number}{extension}")
+        };
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => number += 1,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Copies a save into its backup folder before it's overwritten, keeping the given number of backups.
+///
+/// Returns the path of the new backup, or `None` if the save doesn't exist yet or backups are disabled by setting
+/// `generations` to 0.
+pub fn backup_before_overwrite(save_path: &Path, generations: usize) -> Result<Option<PathBuf>> {
+    if generations == 0 || !save_path.is_file() {
+        return Ok(None);
+    }
+
+    let (stem, extension) = name_parts(save_path)?;
+    let dir = backup_dir(save_path);
+    std::fs::create_dir_all(&dir)?;
+
+    let mut source = File::open(save_path)?;
+    let (backup_path, mut backup) = create_backup_file(&dir, &stem, &extension, Local::now().naive_local())?;
+    std::io::copy(&mut source, &mut backup)?;
+
+    prune_backups(save_path, generations)?;
+    Ok(Some(backup_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_generations() {
+        let dir = std::env::temp_dir().join(format!("shf-backup-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let save_path = dir.join("SaveData0.sav");
+        // a save whose name starts with the same characters shouldn't be mixed up with ours
+        let other_path = dir.join("SaveData0-old.sav");
+
+        assert!(backup_before_overwrite(&save_path, 2).unwrap().is_none());
+
+        std::fs::write(&other_path, b"other").unwrap();
+        backup_before_overwrite(&other_path, 2).unwrap().unwrap();
+        for contents in [b"one", b"two", b"thr"] {
+            std::fs::write(&save_path, contents).unwrap();
+            backup_before_overwrite(&save_path, 2).unwrap().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        let backups = list_backups(&save_path).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(std::fs::read(&backups[0].path).unwrap(), b"thr");
+        assert_eq!(std::fs::read(&backups[1].path).unwrap(), b"two");
+        assert_eq!(list_backups(&other_path).unwrap().len(), 1);
+
+        // backups made at the same time don't replace each other and are listed in the order they were made
+        let time = backups[0].timestamp + chrono::Duration::seconds(1);
+        let (first, _) = create_backup_file(&backup_dir(&save_path), "SaveData0", ".sav", time).unwrap();
+        std::fs::write(&first, b"fst").unwrap();
+        let (second, _) = create_backup_file(&backup_dir(&save_path), "SaveData0", ".sav", time).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read(&first).unwrap(), b"fst");
+        let backups = list_backups(&save_path).unwrap();
+        assert_eq!(backups.len(), 4);
+        assert_eq!(backups[0].path, second);
+        assert_eq!(backups[1].path, first);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
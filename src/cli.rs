@@ -0,0 +1,341 @@
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use shf_save_editor::diff;
+use shf_save_editor::json;
+use shf_save_editor::path::*;
+use shf_save_editor::save::*;
+use shf_save_editor::verify;
+
+const COMMANDS: [&str; 9] = ["dump", "get", "set", "validate", "roundtrip", "diff", "export", "import", "help"];
+
+const USAGE: &str = "\
+Usage: shf-save-editor [<save>]
+       shf-save-editor <command> [<args>]
+
+Commands:
+  dump <save>                               Print the full property tree
+  get <save> <path>                         Print the value at a property path
+  set <save> <path> <value> [-o <output>]   Set a scalar value and save the result
+  validate <save>                           Check that a save can be fully parsed
+  roundtrip <save> [-o <output>]            Check that a save re-serializes byte-for-byte
+  diff <old> <new>                          List the values that differ between two saves
+  export <save> [-o <output>]               Convert a save to JSON (printed if no output is given)
+  import <json> -o <output>                 Convert JSON produced by export back to a save
+  help                                      Show this message
+
+Property paths are property names separated by dots, with array indexes and map keys in
+brackets, e.g. PlayerStateRecord.ComponentRecords[3].Data.Omamories[2].IDIndex. A segment
+can also be * to match every element, or [Field=value] to match the elements whose Field
+has the given value, e.g. PlayerStateRecord.ComponentRecords[Class=<class>].Data.KeyItems[*].
+get prints every value the path matches, and set changes all of them.
+
+Values that fail to parse are kept as raw bytes so the rest of the save can still be used;
+a warning is printed for each one.
+
+Options:
+  -o, --output <output>   Write the result to the given file
+  --detect-structs        Decode the data of objects of unknown classes when it can be read
+                          as a struct
+
+With no command, the editor GUI is launched, optionally opening the given save.";
+
+fn count_properties(properties: &[Property]) -> usize {
+    properties.iter().filter(|p| !p.is_none()).count()
+}
+
+fn describe_body(label: &str, body: &PropertyBody) -> String {
+    let value = match body.value {
+        PropertyValue::BoolProperty(None) => (body.flags & BOOL_TRUE_FLAG != 0).to_string(),
+        ref value => value.summary(),
+    };
+    format!("{label}: {} = {}", body.property_type.describe(), value)
+}
+
+fn describe_property(property: &Property) -> Option<String> {
+    property.body.as_ref().map(|body| describe_body(property.name.as_str(), body))
+}
+
+fn dump_value(value: &PropertyValue, depth: usize) {
+    let indent = "  ".repeat(depth);
+    match value {
+        PropertyValue::StructProperty(props) => dump_properties(props, depth),
+        PropertyValue::CustomStructProperty(custom_struct) => {
+            dump_properties(&custom_struct.properties, depth);
+            if !custom_struct.extra.is_empty() {
+                println!("{indent}(extra: {} bytes)", custom_struct.extra.len());
+            }
+        }
+        PropertyValue::ArrayProperty { values } if !matches!(values.first(), Some(PropertyValue::UnknownProperty(_))) => {
+            for (i, value) in values.iter().enumerate() {
+                println!("{indent}[{i}] = {}", value.summary());
+                dump_value(value, depth + 1);
+            }
+        }
+        PropertyValue::SetProperty { values, .. } => {
+            for (i, value) in values.iter().enumerate() {
+                println!("{indent}[{i}] = {}", value.summary());
+                dump_value(value, depth + 1);
+            }
+        }
+        PropertyValue::MapProperty { values, .. } => {
+            for (key, value) in values {
+                println!("{indent}[{}] = {}", key.summary(), value.summary());
+                dump_value(value, depth + 1);
+            }
+        }
+        PropertyValue::GameplayTagContainerProperty(container) => {
+            for tag in &container.tags {
+                println!("{indent}{tag}");
+            }
+        }
+        _ => (),
+    }
+}
+
+fn dump_properties(properties: &[Property], depth: usize) {
+    let indent = "  ".repeat(depth);
+    for property in properties {
+        let Some(description) = describe_property(property) else {
+            continue;
+        };
+        println!("{indent}{description}");
+        dump_value(&property.body.as_ref().unwrap().value, depth + 1);
+    }
+}
+
+/// Options that can be given to any command
+#[derive(Debug, Default)]
+struct CommandOptions {
+    output: Option<PathBuf>,
+    detect_custom_structs: bool,
+}
+
+impl CommandOptions {
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions::lenient().with_custom_struct_detection(self.detect_custom_structs)
+    }
+}
+
+fn warn_quarantined(quarantined: &[ParseError]) {
+    for error in quarantined {
+        eprintln!("Warning: kept unparseable value as raw bytes: {error}");
+    }
+}
+
+/// Loads a save leniently, returning the errors for any values that had to be kept as raw bytes
+fn load_lenient(path: &Path, options: &CommandOptions) -> Result<(SaveGame, Vec<ParseError>)> {
+    let mut file = BufReader::new(File::open(path)?);
+    let parse_options = options.parse_options();
+    let save = SaveGame::read_with_options(&mut file, parse_options.clone())?;
+    Ok((save, parse_options.quarantined()))
+}
+
+fn load(path: &Path, options: &CommandOptions) -> Result<SaveGame> {
+    let (save, quarantined) = load_lenient(path, options)?;
+    warn_quarantined(&quarantined);
+    Ok(save)
+}
+
+fn write(path: &Path, save: &SaveGame, options: &CommandOptions) -> Result<()> {
+    verify::write_verified_with_options(save, path, options.parse_options())?;
+    Ok(())
+}
+
+/// Splits the options out of a list of arguments
+fn take_options(args: &[String]) -> Result<(Vec<&str>, CommandOptions)> {
+    let mut positional = Vec::new();
+    let mut options = CommandOptions::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-o" || arg == "--output" {
+            let Some(path) = iter.next() else {
+                bail!("{arg} requires a path");
+            };
+            options.output = Some(PathBuf::from(path));
+        } else if arg == "--detect-structs" {
+            options.detect_custom_structs = true;
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+
+    Ok((positional, options))
+}
+
+fn dump(save_path: &Path, options: &CommandOptions) -> Result<bool> {
+    let save = load(save_path, options)?;
+    println!("Type: {}", save.save_data.type_name);
+    dump_properties(&save.save_data.properties, 0);
+    Ok(true)
+}
+
+fn describe_target(label: &str, target: &PathTarget) -> String {
+    match target {
+        // paths never match None properties
+        PathTarget::Property(property) => describe_body(label, property.body.as_ref().unwrap()),
+        PathTarget::Value(value) => format!("{label} = {}", value.summary()),
+    }
+}
+
+fn get(save_path: &Path, path: &str, options: &CommandOptions) -> Result<bool> {
+    let path = PropertyPath::parse(path)?;
+    let save = load(save_path, options)?;
+    for path_match in path.select(&save.save_data.properties)? {
+        println!("{}", describe_target(&path_match.path, &path_match.target));
+    }
+    Ok(true)
+}
+
+fn set(save_path: &Path, path: &str, new_value: &str, options: &CommandOptions) -> Result<bool> {
+    let path = PropertyPath::parse(path)?;
+    let mut save = load(save_path, options)?;
+    for path_match in path.select_mut(&mut save.save_data.properties)? {
+        match path_match.target {
+            PathTargetMut::Property(Property { body: Some(body), .. }) if matches!(body.value, PropertyValue::BoolProperty(None)) => {
+                new_value.parse::<bool>().map(|value| if value {
+                    body.flags |= BOOL_TRUE_FLAG;
+                } else {
+                    body.flags &= !BOOL_TRUE_FLAG;
+                }).map_err(Into::into)
+            }
+            PathTargetMut::Property(Property { body: Some(body), .. }) => body.value.set_from_str(new_value),
+            PathTargetMut::Property(_) => unreachable!("paths never match None properties"),
+            PathTargetMut::Value(value) => value.set_from_str(new_value),
+        }.with_context(|| format!("Failed to set {}", path_match.path))?;
+    }
+
+    write(options.output.as_deref().unwrap_or(save_path), &save, options)?;
+    Ok(true)
+}
+
+fn count_unknown(value: &PropertyValue) -> usize {
+    match value {
+        PropertyValue::UnknownProperty(_) => 1,
+        PropertyValue::StructProperty(props) => props.iter().filter_map(|p| p.body.as_ref()).map(|b| count_unknown(&b.value)).sum(),
+        PropertyValue::CustomStructProperty(custom_struct) => {
+            custom_struct.properties.iter().filter_map(|p| p.body.as_ref()).map(|b| count_unknown(&b.value)).sum()
+        }
+        // single-element byte arrays are stored as an UnknownProperty but are fully understood
+        PropertyValue::ArrayProperty { values } if values.len() == 1 && matches!(values[0], PropertyValue::UnknownProperty(_)) => 0,
+        PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. } => values.iter().map(count_unknown).sum(),
+        PropertyValue::MapProperty { values, .. } => values.iter().map(|(k, v)| count_unknown(k) + count_unknown(v)).sum(),
+        _ => 0,
+    }
+}
+
+fn validate(save_path: &Path, options: &CommandOptions) -> Result<bool> {
+    let (save, quarantined) = load_lenient(save_path, options)?;
+    let num_properties = count_properties(&save.save_data.properties);
+    let num_unknown: usize = save.save_data.properties.iter()
+        .filter_map(|p| p.body.as_ref())
+        .map(|b| count_unknown(&b.value))
+        .sum();
+
+    println!("Type: {}", save.save_data.type_name);
+    println!("Top-level properties: {num_properties}");
+    println!("Undecoded values: {num_unknown}");
+    let undecoded_classes = save.undecoded_classes();
+    println!("Undecoded classes: {}", undecoded_classes.len());
+    for class in &undecoded_classes {
+        println!("  {class}");
+    }
+    println!("Unparseable values: {}", quarantined.len());
+    for error in &quarantined {
+        println!("  {error}");
+    }
+    Ok(quarantined.is_empty())
+}
+
+fn roundtrip(save_path: &Path, options: &CommandOptions) -> Result<bool> {
+    let original = std::fs::read(save_path)?;
+    let parse_options = options.parse_options();
+    let save = SaveGame::read_with_options(&mut Cursor::new(&original), parse_options.clone())?;
+    warn_quarantined(&parse_options.quarantined());
+
+    if let Some(ref output) = options.output {
+        std::fs::write(output, verify::serialize(&save)?)?;
+    }
+
+    match verify::verify_roundtrip(&original, &save) {
+        Ok(()) => {
+            println!("OK: {} bytes re-serialized identically", original.len());
+            Ok(true)
+        }
+        Err(err) if err.is::<verify::RoundTripMismatch>() => {
+            println!("Mismatch: {err}");
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn diff(old_path: &Path, new_path: &Path, options: &CommandOptions) -> Result<bool> {
+    let old = load(old_path, options)?;
+    let new = load(new_path, options)?;
+    let differences = diff::diff_saves(&old, &new);
+    for difference in &differences {
+        println!("{difference}");
+    }
+    Ok(differences.is_empty())
+}
+
+fn export(save_path: &Path, options: &CommandOptions) -> Result<bool> {
+    let save = load(save_path, options)?;
+    let json = json::to_json_string(&save)?;
+    match options.output {
+        Some(ref output) => std::fs::write(output, json)?,
+        None => println!("{json}"),
+    }
+    Ok(true)
+}
+
+fn import(json_path: &Path, options: &CommandOptions) -> Result<bool> {
+    let Some(ref output) = options.output else {
+        bail!("import requires an output path");
+    };
+
+    let json = std::fs::read_to_string(json_path)?;
+    let save = json::from_json_str(&json)?;
+    write(output, &save, options)?;
+    Ok(true)
+}
+
+/// Returns whether the given command-line argument is a CLI command rather than a save path
+pub fn is_command(arg: &str) -> bool {
+    COMMANDS.contains(&arg) || arg == "--help" || arg == "-h"
+}
+
+fn run_command(args: &[String]) -> Result<bool> {
+    let (positional, options) = take_options(&args[1..])?;
+    match (args[0].as_str(), positional.as_slice()) {
+        ("dump", [save]) => dump(Path::new(save), &options),
+        ("get", [save, path]) => get(Path::new(save), path, &options),
+        ("set", [save, path, value]) => set(Path::new(save), path, value, &options),
+        ("validate", [save]) => validate(Path::new(save), &options),
+        ("roundtrip", [save]) => roundtrip(Path::new(save), &options),
+        ("diff", [old, new]) => diff(Path::new(old), Path::new(new), &options),
+        ("export", [save]) => export(Path::new(save), &options),
+        ("import", [json]) => import(Path::new(json), &options),
+        ("help" | "--help" | "-h", []) => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+        (command, _) => bail!("Invalid arguments for {command}\n\n{USAGE}"),
+    }
+}
+
+/// Runs a CLI command and returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+    match run_command(args) {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            2
+        }
+    }
+}
@@ -0,0 +1,458 @@
+//! Structural comparison of two saves.
+//!
+//! Properties are matched by name, array elements by index, map entries by key, and set elements and gameplay tags by
+//! value, so the differences are reported in terms of the paths of the values that changed rather than byte offsets.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::Cursor;
+
+use binrw::BinWriterExt;
+
+use crate::save::*;
+use crate::verify::{element_label, join_path};
+
+/// The kind of change made to a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DifferenceKind {
+    /// The value only exists in the new save
+    Added,
+    /// The value only exists in the old save
+    Removed,
+    /// The value exists in both saves but is different
+    Changed,
+}
+
+/// A single difference between two saves
+#[derive(Debug, Clone)]
+pub struct Difference {
+    /// The kind of change
+    pub kind: DifferenceKind,
+    /// The path of the value that changed
+    pub path: String,
+    /// A description of the value in the old save, if it exists there
+    pub old: Option<String>,
+    /// A description of the value in the new save, if it exists there
+    pub new: Option<String>,
+}
+
+impl Difference {
+    fn added(path: String, new: String) -> Self {
+        Self { kind: DifferenceKind::Added, path, old: None, new: Some(new) }
+    }
+
+    fn removed(path: String, old: String) -> Self {
+        Self { kind: DifferenceKind::Removed, path, old: Some(old), new: None }
+    }
+
+    fn changed(path: String, old: String, new: String) -> Self {
+        Self { kind: DifferenceKind::Changed, path, old: Some(old), new: Some(new) }
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let old = self.old.as_deref().unwrap_or_default();
+        let new = self.new.as_deref().unwrap_or_default();
+        match self.kind {
+            DifferenceKind::Added => write!(f, "+ {} = {new}", self.path),
+            DifferenceKind::Removed => write!(f, "- {} = {old}", self.path),
+            DifferenceKind::Changed => write!(f, "~ {}: {old} -> {new}", self.path),
+        }
+    }
+}
+
+/// Describes a property's value, including the value of bools that are stored in the flags
+fn describe_body(body: &PropertyBody) -> String {
+    match body.value {
+        PropertyValue::BoolProperty(None) => (body.flags & BOOL_TRUE_FLAG != 0).to_string(),
+        ref value => value.summary(),
+    }
+}
+
+/// Describes a property type including all of its tags and inner types, which [`PropertyType::describe`] leaves out
+/// some of
+fn describe_type_exactly(property_type: &PropertyType) -> String {
+    let mut desc = property_type.name.to_string();
+    if !property_type.tags.is_empty() {
+        let tags: Vec<_> = property_type.tags.iter().map(|tag| format!("{}:{}", tag.kind, tag.value)).collect();
+        desc.push_str(&format!("[{}]", tags.join(", ")));
+    }
+    if !property_type.inner_types.is_empty() {
+        let inner_types: Vec<_> = property_type.inner_types.iter().map(describe_type_exactly).collect();
+        desc.push_str(&format!("({})", inner_types.join(", ")));
+    }
+    desc
+}
+
+fn diff_removed_count(old: u32, new: u32, path: &str, differences: &mut Vec<Difference>) {
+    if old != new {
+        differences.push(Difference::changed(join_path(path, "(removed count)"), old.to_string(), new.to_string()));
+    }
+}
+
+fn value_bytes(value: &PropertyValue) -> Vec<u8> {
+    let mut data = Vec::with_capacity(value.size());
+    // writing to memory can't fail for any value that was read successfully
+    let _ = Cursor::new(&mut data).write_le(value);
+    data
+}
+
+/// Returns whether the value is a byte array, which we store as a single UnknownProperty
+fn is_byte_array(values: &[PropertyValue]) -> bool {
+    values.len() == 1 && matches!(values[0], PropertyValue::UnknownProperty(_))
+}
+
+fn diff_values(old: &PropertyValue, new: &PropertyValue, path: &str, differences: &mut Vec<Difference>) {
+    match (old, new) {
+        (PropertyValue::StructProperty(old_props), PropertyValue::StructProperty(new_props)) => {
+            diff_properties(old_props, new_props, path, differences);
+        }
+        (PropertyValue::CustomStructProperty(old_struct), PropertyValue::CustomStructProperty(new_struct)) => {
+            if old_struct.flags != new_struct.flags {
+                differences.push(Difference::changed(
+                    join_path(path, "(flags)"),
+                    old_struct.flags.to_string(),
+                    new_struct.flags.to_string(),
+                ));
+            }
+            diff_properties(&old_struct.properties, &new_struct.properties, path, differences);
+            if old_struct.extra != new_struct.extra {
+                differences.push(Difference::changed(
+                    join_path(path, "(extra)"),
+                    format!("{:02x?}", old_struct.extra),
+                    format!("{:02x?}", new_struct.extra),
+                ));
+            }
+        }
+        (PropertyValue::ArrayProperty { values: old_values }, PropertyValue::ArrayProperty { values: new_values })
+            if !is_byte_array(old_values) && !is_byte_array(new_values) =>
+        {
+            for (i, (old_value, new_value)) in old_values.iter().zip(new_values).enumerate() {
+                diff_values(old_value, new_value, &join_path(path, &element_label(i, None)), differences);
+            }
+            for (i, old_value) in old_values.iter().enumerate().skip(new_values.len()) {
+                differences.push(Difference::removed(join_path(path, &element_label(i, None)), old_value.summary()));
+            }
+            for (i, new_value) in new_values.iter().enumerate().skip(old_values.len()) {
+                differences.push(Difference::added(join_path(path, &element_label(i, None)), new_value.summary()));
+            }
+        }
+        (
+            PropertyValue::MapProperty { removed_count: old_removed, values: old_values },
+            PropertyValue::MapProperty { removed_count: new_removed, values: new_values },
+        ) => {
+            diff_removed_count(*old_removed, *new_removed, path, differences);
+            diff_maps(old_values, new_values, path, differences);
+        }
+        (
+            PropertyValue::SetProperty { removed_count: old_removed, values: old_values },
+            PropertyValue::SetProperty { removed_count: new_removed, values: new_values },
+        ) => {
+            diff_removed_count(*old_removed, *new_removed, path, differences);
+            diff_sets(old_values, new_values, path, differences);
+        }
+        (PropertyValue::GameplayTagContainerProperty(old_container), PropertyValue::GameplayTagContainerProperty(new_container)) => {
+            // tags are unordered, so only tags that were added or removed matter
+            for (i, tag) in old_container.tags.iter().enumerate().filter(|(_, tag)| !new_container.contains(tag.as_str())) {
+                differences.push(Difference::removed(join_path(path, &element_label(i, None)), tag.to_string()));
+            }
+            for (i, tag) in new_container.tags.iter().enumerate().filter(|(_, tag)| !old_container.contains(tag.as_str())) {
+                differences.push(Difference::added(join_path(path, &element_label(i, None)), tag.to_string()));
+            }
+        }
+        _ => {
+            if old.type_name() != new.type_name() || value_bytes(old) != value_bytes(new) {
+                differences.push(Difference::changed(path.to_string(), old.summary(), new.summary()));
+            }
+        }
+    }
+}
+
+fn diff_maps(old_values: &[(PropertyValue, PropertyValue)], new_values: &[(PropertyValue, PropertyValue)], path: &str, differences: &mut Vec<Difference>) {
+    // keys that aren't scalars can't be matched up by value, so they're matched by index instead
+    let key_of = |i: usize, key: &PropertyValue| key.scalar_string().ok_or(i);
+    let new_index: HashMap<_, _> = new_values.iter().enumerate().map(|(i, (k, _))| (key_of(i, k), i)).collect();
+    let old_keys: Vec<_> = old_values.iter().enumerate().map(|(i, (k, _))| key_of(i, k)).collect();
+
+    for (i, (old_key, old_value)) in old_values.iter().enumerate() {
+        let label = join_path(path, &element_label(i, Some(old_key)));
+        match new_index.get(&old_keys[i]) {
+            Some(&j) => {
+                let (new_key, new_value) = &new_values[j];
+                if old_key.scalar_string().is_none() {
+                    diff_values(old_key, new_key, &format!("{label}(key)"), differences);
+                }
+                diff_values(old_value, new_value, &label, differences);
+            }
+            None => differences.push(Difference::removed(label, old_value.summary())),
+        }
+    }
+
+    for (j, (new_key, new_value)) in new_values.iter().enumerate() {
+        if !old_keys.contains(&key_of(j, new_key)) {
+            differences.push(Difference::added(join_path(path, &element_label(j, Some(new_key))), new_value.summary()));
+        }
+    }
+}
+
+fn diff_sets(old_values: &[PropertyValue], new_values: &[PropertyValue], path: &str, differences: &mut Vec<Difference>) {
+    // sets are unordered, so scalar elements are matched by value and everything else by index
+    let key_of = |i: usize, value: &PropertyValue| value.scalar_string().ok_or(i);
+    let new_index: HashMap<_, _> = new_values.iter().enumerate().map(|(i, v)| (key_of(i, v), i)).collect();
+    let old_keys: Vec<_> = old_values.iter().enumerate().map(|(i, v)| key_of(i, v)).collect();
+
+    for (i, old_value) in old_values.iter().enumerate() {
+        let label = join_path(path, &element_label(i, None));
+        match new_index.get(&old_keys[i]) {
+            Some(&j) => diff_values(old_value, &new_values[j], &label, differences),
+            None => differences.push(Difference::removed(label, old_value.summary())),
+        }
+    }
+
+    for (j, new_value) in new_values.iter().enumerate() {
+        if !old_keys.contains(&key_of(j, new_value)) {
+            differences.push(Difference::added(join_path(path, &element_label(j, None)), new_value.summary()));
+        }
+    }
+}
+
+fn diff_properties(old_props: &[Property], new_props: &[Property], parent: &str, differences: &mut Vec<Difference>) {
+    // a struct may contain several properties with the same name, so match them up by name and occurrence
+    fn keyed(props: &[Property]) -> Vec<((&str, usize), &Property)> {
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        props.iter().filter(|p| !p.is_none()).map(|p| {
+            let count = seen.entry(p.name.as_str()).or_default();
+            *count += 1;
+            ((p.name.as_str(), *count - 1), p)
+        }).collect()
+    }
+
+    let label = |name: &str, occurrence: usize| if occurrence == 0 {
+        join_path(parent, name)
+    } else {
+        join_path(parent, &format!("{name}#{occurrence}"))
+    };
+
+    let old_keyed = keyed(old_props);
+    let new_keyed = keyed(new_props);
+    let new_lookup: HashMap<_, _> = new_keyed.iter().copied().collect();
+
+    for &(key, old_prop) in &old_keyed {
+        let path = label(key.0, key.1);
+        // is_none() excludes properties without a body
+        let old_body = old_prop.body.as_ref().unwrap();
+        let Some(new_prop) = new_lookup.get(&key) else {
+            differences.push(Difference::removed(path, describe_body(old_body)));
+            continue;
+        };
+        let new_body = new_prop.body.as_ref().unwrap();
+
+        let old_type = old_body.property_type.describe();
+        let new_type = new_body.property_type.describe();
+        if old_type != new_type {
+            differences.push(Difference::changed(path, format!("{old_type} = {}", describe_body(old_body)), format!("{new_type} = {}", describe_body(new_body))));
+            continue;
+        }
+
+        // the description leaves out some tags, such as the enum of a ByteProperty and the GUID of a blueprint type
+        if old_body.property_type != new_body.property_type {
+            differences.push(Difference::changed(
+                join_path(&path, "(type)"),
+                describe_type_exactly(&old_body.property_type),
+                describe_type_exactly(&new_body.property_type),
+            ));
+        }
+
+        let is_bool_in_flags = matches!((&old_body.value, &new_body.value), (PropertyValue::BoolProperty(None), PropertyValue::BoolProperty(None)));
+        // the value of a bool stored in the flags is reported as the value rather than as a change to the flags
+        let flags_mask = if is_bool_in_flags { !BOOL_TRUE_FLAG } else { u8::MAX };
+        if old_body.flags & flags_mask != new_body.flags & flags_mask {
+            differences.push(Difference::changed(
+                join_path(&path, "(flags)"),
+                (old_body.flags & flags_mask).to_string(),
+                (new_body.flags & flags_mask).to_string(),
+            ));
+        }
+
+        if is_bool_in_flags {
+            if old_body.flags & BOOL_TRUE_FLAG != new_body.flags & BOOL_TRUE_FLAG {
+                differences.push(Difference::changed(path, describe_body(old_body), describe_body(new_body)));
+            }
+        } else {
+            diff_values(&old_body.value, &new_body.value, &path, differences);
+        }
+    }
+
+    let old_lookup: HashMap<_, _> = old_keyed.into_iter().collect();
+    for (key, new_prop) in new_keyed {
+        if !old_lookup.contains_key(&key) {
+            differences.push(Difference::added(label(key.0, key.1), describe_body(new_prop.body.as_ref().unwrap())));
+        }
+    }
+}
+
+/// Compares two saves and returns the differences between them, in the order they appear in the saves.
+pub fn diff_saves(old: &SaveGame, new: &SaveGame) -> Vec<Difference> {
+    let mut differences = Vec::new();
+
+    let old_header = format!("{:?}", old.header);
+    let new_header = format!("{:?}", new.header);
+    if old_header != new_header {
+        differences.push(Difference::changed(String::from("(header)"), old_header, new_header));
+    }
+
+    let old_custom_format = format!("{:?}", old.custom_format_data);
+    let new_custom_format = format!("{:?}", new.custom_format_data);
+    if old_custom_format != new_custom_format {
+        differences.push(Difference::changed(String::from("(custom format data)"), old_custom_format, new_custom_format));
+    }
+
+    if old.save_data.type_name != new.save_data.type_name {
+        differences.push(Difference::changed(
+            String::from("(save type)"),
+            old.save_data.type_name.to_string(),
+            new.save_data.type_name.to_string(),
+        ));
+    }
+
+    if old.save_data.flags != new.save_data.flags {
+        differences.push(Difference::changed(
+            String::from("(save flags)"),
+            old.save_data.flags.to_string(),
+            new.save_data.flags.to_string(),
+        ));
+    }
+
+    diff_properties(&old.save_data.properties, &new.save_data.properties, "", &mut differences);
+
+    if old.save_data.extra != new.save_data.extra {
+        differences.push(Difference::changed(
+            String::from("(save extra)"),
+            old.save_data.extra.to_string(),
+            new.save_data.extra.to_string(),
+        ));
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_saves() {
+        let old = SaveGame::new_for_test(vec![
+            Property::new_scalar("Same", PropertyValue::IntProperty(1)),
+            Property::new_scalar("Struct", PropertyValue::StructProperty(vec![
+                Property::new_scalar("Health", PropertyValue::FloatProperty(100.0)),
+                Property::new_scalar("Gone", PropertyValue::IntProperty(5)),
+                Property::new_none(),
+            ])),
+            Property::new_scalar("List", PropertyValue::ArrayProperty { values: vec![
+                PropertyValue::IntProperty(1),
+                PropertyValue::IntProperty(2),
+            ] }),
+            Property::new_scalar("Custom", PropertyValue::CustomStructProperty(CustomStruct {
+                flags: 0,
+                properties: vec![Property::new_scalar("Count", PropertyValue::IntProperty(5)), Property::new_none()],
+                extra: vec![1, 2],
+            })),
+            Property::new_none(),
+        ]);
+        let mut new = SaveGame::new_for_test(vec![
+            Property::new_scalar("Same", PropertyValue::IntProperty(1)),
+            Property::new_scalar("Struct", PropertyValue::StructProperty(vec![
+                Property::new_scalar("Health", PropertyValue::FloatProperty(50.0)),
+                Property::new_none(),
+            ])),
+            Property::new_scalar("List", PropertyValue::ArrayProperty { values: vec![
+                PropertyValue::IntProperty(1),
+                PropertyValue::IntProperty(3),
+                PropertyValue::IntProperty(4),
+            ] }),
+            Property::new_scalar("Custom", PropertyValue::CustomStructProperty(CustomStruct {
+                flags: 1,
+                properties: vec![Property::new_scalar("Count", PropertyValue::IntProperty(5)), Property::new_none()],
+                extra: vec![1, 2],
+            })),
+            Property::new_scalar("New", PropertyValue::StrProperty(FString::from_str("hi"))),
+            Property::new_none(),
+        ]);
+        new.save_data.flags = 2;
+        new.save_data.extra = 7;
+
+        let differences: Vec<_> = diff_saves(&old, &new).iter().map(Difference::to_string).collect();
+        assert_eq!(differences, vec![
+            "~ (save flags): 0 -> 2",
+            "~ Struct.Health: 100 -> 50",
+            "- Struct.Gone = 5",
+            "~ List[1]: 2 -> 3",
+            "+ List[2] = 4",
+            "~ Custom.(flags): 0 -> 1",
+            "+ New = hi",
+            "~ (save extra): 0 -> 7",
+        ]);
+
+        assert!(diff_saves(&old, &old).is_empty());
+    }
+
+    fn typed(name: &str, property_type: PropertyType, flags: u8, value: PropertyValue) -> Property {
+        Property { name: FString::from_str(name), body: Some(PropertyBody { property_type, flags, value }) }
+    }
+
+    fn record_type(guid: &str) -> PropertyType {
+        PropertyType {
+            name: FString::from_str("StructProperty"),
+            tags: vec![TypeTag::new(2, "Record"), TypeTag::new(1, "/Game/Blueprint/Record"), TypeTag::new(1, guid)],
+            inner_types: Vec::new(),
+        }
+    }
+
+    fn enum_type(enum_name: &str) -> PropertyType {
+        PropertyType {
+            name: FString::from_str("ByteProperty"),
+            tags: vec![TypeTag::new(2, enum_name), TypeTag::new(1, "/Script/GameNoce")],
+            inner_types: Vec::new(),
+        }
+    }
+
+    fn make_save(removed_count: u32, flags: u8, enum_name: &str, guid: &str) -> SaveGame {
+        let elements = vec![PropertyValue::IntProperty(1)];
+        SaveGame::new_for_test(vec![
+            Property::new_scalar("Map", PropertyValue::MapProperty {
+                removed_count,
+                values: vec![(PropertyValue::IntProperty(1), PropertyValue::IntProperty(2))],
+            }),
+            Property::new_scalar("Set", PropertyValue::SetProperty { removed_count, values: elements }),
+            typed("Flagged", PropertyType::new_scalar("IntProperty"), flags, PropertyValue::IntProperty(3)),
+            typed("Bool", PropertyType::new_scalar("BoolProperty"), flags | BOOL_TRUE_FLAG, PropertyValue::BoolProperty(None)),
+            typed("Facing", enum_type(enum_name), 0, PropertyValue::ByteEnumProperty(FString::from_str("Front"))),
+            typed("Record", record_type(guid), 0, PropertyValue::StructProperty(vec![Property::new_none()])),
+            Property::new_none(),
+        ])
+    }
+
+    #[test]
+    fn test_diff_hidden_details() {
+        let old = make_save(0, 0, "ENoceFacing", "00000000-0000-0000-0000-000000000000");
+        let new = make_save(2, 1, "ENoceDirection", "11111111-1111-1111-1111-111111111111");
+
+        let differences: Vec<_> = diff_saves(&old, &new).iter().map(Difference::to_string).collect();
+        assert_eq!(differences, vec![
+            "~ Map.(removed count): 0 -> 2",
+            "~ Set.(removed count): 0 -> 2",
+            "~ Flagged.(flags): 0 -> 1",
+            "~ Bool.(flags): 0 -> 1",
+            "~ Facing.(type): ByteProperty[2:ENoceFacing, 1:/Script/GameNoce] -> ByteProperty[2:ENoceDirection, 1:/Script/GameNoce]",
+            "~ Record.(type): StructProperty[2:Record, 1:/Game/Blueprint/Record, 1:00000000-0000-0000-0000-000000000000] -> StructProperty[2:Record, 1:/Game/Blueprint/Record, 1:11111111-1111-1111-1111-111111111111]",
+        ]);
+
+        // a bool stored in the flags is reported as its value, not as a change to the flags
+        let mut new = make_save(0, 0, "ENoceFacing", "00000000-0000-0000-0000-000000000000");
+        let body = new.save_data.properties[3].body.as_mut().unwrap();
+        body.flags = 0;
+        let differences: Vec<_> = diff_saves(&old, &new).iter().map(Difference::to_string).collect();
+        assert_eq!(differences, vec!["~ Bool: true -> false"]);
+    }
+}
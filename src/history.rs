@@ -0,0 +1,498 @@
+//! Undo and redo history for edits to a save.
+//!
+//! Each edit is recorded as the change it made rather than a copy of the save, so undoing it applies the change in
+//! reverse.
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::records::{RecordError, SaveRecord};
+use crate::save::{CustomFormatData, FString, Property, PropertyType, PropertyValue, SaveGame, SaveGameData, SaveGameHeader};
+
+/// Maximum number of edits that can be undone
+const MAX_UNDO: usize = 100;
+
+/// One step from a list of properties to a property, or from a value to one of its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditStep {
+    /// The property at this position in a list of properties
+    Property(usize),
+    /// The element at this index of an array or set
+    Element(usize),
+    /// The key of the entry at this index of a map
+    MapKey(usize),
+    /// The value of the entry at this index of a map
+    MapValue(usize),
+}
+
+/// Finds the properties of a record in a save
+type RecordProperties = fn(&mut SaveGame) -> Result<&mut Vec<Property>, RecordError>;
+
+/// The parts of a property other than its value
+#[derive(Debug, Clone)]
+pub struct PropertyInfo {
+    name: FString,
+    body: Option<(PropertyType, u8)>,
+}
+
+impl PropertyInfo {
+    pub fn of(property: &Property) -> Self {
+        Self {
+            name: property.name.clone(),
+            body: property.body.as_ref().map(|body| (body.property_type.clone(), body.flags)),
+        }
+    }
+
+    fn apply_to(&self, property: &mut Property) {
+        property.name = self.name.clone();
+        if let (Some(body), Some((property_type, flags))) = (&mut property.body, &self.body) {
+            body.property_type = property_type.clone();
+            body.flags = *flags;
+        }
+    }
+}
+
+/// The parts of the save data other than its properties
+#[derive(Debug, Clone)]
+pub struct SaveDataInfo {
+    type_name: FString,
+    flags: u8,
+    extra: u32,
+}
+
+impl SaveDataInfo {
+    pub fn of(save_data: &SaveGameData) -> Self {
+        Self { type_name: save_data.type_name.clone(), flags: save_data.flags, extra: save_data.extra }
+    }
+
+    fn apply_to(&self, save_data: &mut SaveGameData) {
+        save_data.type_name = self.type_name.clone();
+        save_data.flags = self.flags;
+        save_data.extra = self.extra;
+    }
+}
+
+/// A change to one part of a save, recorded where the change is made
+///
+/// Paths are lists of [`EditStep`]s from the save's properties. An empty path to a list of properties is the save's own
+/// properties.
+#[derive(Debug, Clone)]
+pub enum Edit {
+    Header {
+        old: SaveGameHeader,
+        new: SaveGameHeader,
+    },
+    CustomFormat {
+        old: CustomFormatData,
+        new: CustomFormatData,
+    },
+    /// The type, flags or extra data of the save data
+    SaveData {
+        old: SaveDataInfo,
+        new: SaveDataInfo,
+    },
+    /// A property's name, type or flags
+    Property {
+        path: Vec<EditStep>,
+        old: PropertyInfo,
+        new: PropertyInfo,
+    },
+    /// A value, including an array, set or map whose elements were inserted or deleted
+    Value {
+        path: Vec<EditStep>,
+        old: PropertyValue,
+        new: PropertyValue,
+    },
+    /// A property inserted into the list of properties at `path`
+    InsertProperty {
+        path: Vec<EditStep>,
+        index: usize,
+        property: Property,
+    },
+    /// A property deleted from the list of properties at `path`
+    DeleteProperty {
+        path: Vec<EditStep>,
+        index: usize,
+        property: Property,
+    },
+    /// The properties of a record edited in the Simple view
+    Record {
+        /// The record's [`SaveRecord::PATH`]
+        path: &'static str,
+        find_properties: RecordProperties,
+        old: Vec<Property>,
+        new: Vec<Property>,
+    },
+}
+
+impl Edit {
+    /// Creates an edit to the properties of a record
+    pub fn record<R: SaveRecord>(old: Vec<Property>, new: Vec<Property>) -> Self {
+        Self::Record { path: R::PATH, find_properties: R::properties_mut, old, new }
+    }
+
+    /// Merges a following edit of the same part of the save into this one, or returns it if it's of a different part
+    fn merge(&mut self, next: Self) -> Option<Self> {
+        match (self, next) {
+            (Self::Header { new, .. }, Self::Header { new: next_new, .. }) => *new = next_new,
+            (Self::CustomFormat { new, .. }, Self::CustomFormat { new: next_new, .. }) => *new = next_new,
+            (Self::SaveData { new, .. }, Self::SaveData { new: next_new, .. }) => *new = next_new,
+            (Self::Property { path, new, .. }, Self::Property { path: next_path, new: next_new, .. }) if *path == next_path => {
+                *new = next_new;
+            }
+            (Self::Value { path, new, .. }, Self::Value { path: next_path, new: next_new, .. }) if *path == next_path => {
+                *new = next_new;
+            }
+            (Self::Record { path, new, .. }, Self::Record { path: next_path, new: next_new, .. }) if *path == next_path => {
+                *new = next_new;
+            }
+            (_, next) => return Some(next),
+        }
+        None
+    }
+
+    /// Changes the save to how it was before the edit if `undo` is true, or after the edit otherwise
+    fn apply(&self, save: &mut SaveGame, undo: bool) -> Result<()> {
+        fn pick<'a, T>(undo: bool, old: &'a T, new: &'a T) -> &'a T {
+            if undo { old } else { new }
+        }
+
+        match self {
+            Self::Header { old, new } => save.header = pick(undo, old, new).clone(),
+            Self::CustomFormat { old, new } => save.custom_format_data = pick(undo, old, new).clone(),
+            Self::SaveData { old, new } => pick(undo, old, new).apply_to(&mut save.save_data),
+            Self::Property { path, old, new } => pick(undo, old, new).apply_to(property_mut(&mut save.save_data.properties, path)?),
+            Self::Value { path, old, new } => *value_mut(&mut save.save_data.properties, path)? = pick(undo, old, new).clone(),
+            Self::InsertProperty { path, index, property } | Self::DeleteProperty { path, index, property } => {
+                let properties = properties_mut(&mut save.save_data.properties, path)?;
+                // deleting is the reverse of inserting
+                if undo == matches!(self, Self::InsertProperty { .. }) {
+                    if *index >= properties.len() {
+                        bail!("There is no property {index} at {path:?} to delete");
+                    }
+                    properties.remove(*index);
+                } else {
+                    if *index > properties.len() {
+                        bail!("Can't insert property {index} into the {} properties at {path:?}", properties.len());
+                    }
+                    properties.insert(*index, property.clone());
+                }
+            }
+            Self::Record { path, find_properties, old, new } => {
+                *find_properties(save).map_err(|e| anyhow!("Can't find {path}: {e}"))? = pick(undo, old, new).clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn missing(path: &[EditStep]) -> anyhow::Error {
+    anyhow!("The edited value at {path:?} no longer exists")
+}
+
+/// Finds the list of properties at a path, which is either the save's own properties or those of a struct
+fn properties_mut<'a>(properties: &'a mut Vec<Property>, path: &[EditStep]) -> Result<&'a mut Vec<Property>> {
+    if path.is_empty() {
+        return Ok(properties);
+    }
+
+    match value_mut(properties, path)? {
+        PropertyValue::StructProperty(properties) => Ok(properties),
+        PropertyValue::CustomStructProperty(custom_struct) => Ok(&mut custom_struct.properties),
+        _ => bail!("The edited value at {path:?} is not a struct"),
+    }
+}
+
+fn property_mut<'a>(properties: &'a mut Vec<Property>, path: &[EditStep]) -> Result<&'a mut Property> {
+    let Some((EditStep::Property(index), parent)) = path.split_last() else {
+        bail!("The edited path {path:?} is not a property");
+    };
+    properties_mut(properties, parent)?.get_mut(*index).ok_or_else(|| missing(path))
+}
+
+fn value_mut<'a>(properties: &'a mut Vec<Property>, path: &[EditStep]) -> Result<&'a mut PropertyValue> {
+    let Some((step, parent)) = path.split_last() else {
+        bail!("The edited path is empty");
+    };
+
+    let value = match *step {
+        EditStep::Property(_) => property_mut(properties, path)?.body.as_mut().map(|body| &mut body.value),
+        EditStep::Element(index) => match value_mut(properties, parent)? {
+            PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. } => values.get_mut(index),
+            _ => None,
+        },
+        EditStep::MapKey(index) => match value_mut(properties, parent)? {
+            PropertyValue::MapProperty { values, .. } => values.get_mut(index).map(|(key, _)| key),
+            _ => None,
+        },
+        EditStep::MapValue(index) => match value_mut(properties, parent)? {
+            PropertyValue::MapProperty { values, .. } => values.get_mut(index).map(|(_, value)| value),
+            _ => None,
+        },
+    };
+    value.ok_or_else(|| missing(path))
+}
+
+/// The edits undone or redone together, identified so the history can tell whether the save is back to a saved state
+#[derive(Debug)]
+struct Entry {
+    id: u64,
+    edits: Vec<Edit>,
+}
+
+impl Entry {
+    /// Undoes or redoes all the edits, leaving the save unchanged if any of them fails
+    fn apply(&self, save: &mut SaveGame, undo: bool) -> Result<()> {
+        let edits: Vec<_> = if undo { self.edits.iter().rev().collect() } else { self.edits.iter().collect() };
+        for (i, edit) in edits.iter().enumerate() {
+            if let Err(err) = edit.apply(save, undo) {
+                for applied in edits[..i].iter().rev() {
+                    applied.apply(save, !undo).map_err(|restore_err| anyhow!("{err}, and restoring the save failed: {restore_err}"))?;
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Undo/redo history of edits to a save
+///
+/// Each widget that changes the save describes its change as an [`Edit`], which is applied in place to undo or redo
+/// it. Consecutive edits in the same group, e.g. keystrokes in one text field or one slider drag, are merged into a
+/// single entry.
+#[derive(Debug)]
+pub struct History<G> {
+    undo_stack: Vec<Entry>,
+    redo_stack: Vec<Entry>,
+    current_group: Option<G>,
+    /// The ID of the state before the oldest entry in the undo stack
+    base_id: u64,
+    next_id: u64,
+    /// The ID of the state that was last loaded or saved
+    saved_id: Option<u64>,
+}
+
+impl<G> Default for History<G> {
+    fn default() -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            current_group: None,
+            base_id: 0,
+            next_id: 1,
+            saved_id: None,
+        }
+    }
+}
+
+impl<G: PartialEq> History<G> {
+    fn new_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Returns the ID of the current state of the save
+    fn current_id(&self) -> u64 {
+        self.undo_stack.last().map_or(self.base_id, |entry| entry.id)
+    }
+
+    /// Clears the history to start editing a different save
+    ///
+    /// The save is considered modified until [`Self::mark_saved`] is called.
+    pub fn reset(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.current_group = None;
+        self.base_id = self.new_id();
+        self.saved_id = None;
+    }
+
+    /// Records that the save in its current state was loaded or written to disk
+    pub fn mark_saved(&mut self) {
+        self.saved_id = Some(self.current_id());
+    }
+
+    /// Returns whether the save has changed since it was last loaded or saved
+    ///
+    /// Undoing back to the saved state makes the save unmodified again.
+    pub fn is_modified(&self) -> bool {
+        self.saved_id != Some(self.current_id())
+    }
+
+    /// Records edits that were made to the save
+    ///
+    /// If the edits are in the same group as the previous ones, they're merged into the previous entry. Pass `None` for
+    /// edits that should never be merged. Returns whether there were any edits.
+    pub fn record(&mut self, edits: Vec<Edit>, group: Option<G>) -> bool {
+        if edits.is_empty() {
+            if group.is_none() {
+                self.current_group = None;
+            }
+            return false;
+        }
+
+        let id = self.new_id();
+        match self.undo_stack.last_mut() {
+            Some(entry) if group.is_some() && group == self.current_group => {
+                for edit in edits {
+                    let unmerged = match entry.edits.last_mut() {
+                        Some(last) => last.merge(edit),
+                        None => Some(edit),
+                    };
+                    entry.edits.extend(unmerged);
+                }
+                entry.id = id;
+            }
+            _ => {
+                self.undo_stack.push(Entry { id, edits });
+                if self.undo_stack.len() > MAX_UNDO {
+                    self.base_id = self.undo_stack.remove(0).id;
+                }
+            }
+        }
+        self.redo_stack.clear();
+        self.current_group = group;
+        true
+    }
+
+    /// Returns whether there is an edit to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Returns whether there is an undone edit to redo
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Reverts the most recent edit to the save
+    ///
+    /// If the edit can't be reverted, the save and the history are left unchanged.
+    pub fn undo(&mut self, save: &mut SaveGame) -> Option<Result<()>> {
+        if let Err(err) = self.undo_stack.last()?.apply(save, true) {
+            return Some(Err(err));
+        }
+
+        let entry = self.undo_stack.pop().unwrap();
+        self.redo_stack.push(entry);
+        self.current_group = None;
+        Some(Ok(()))
+    }
+
+    /// Re-applies the most recently undone edit to the save
+    ///
+    /// If the edit can't be re-applied, the save and the history are left unchanged.
+    pub fn redo(&mut self, save: &mut SaveGame) -> Option<Result<()>> {
+        if let Err(err) = self.redo_stack.last()?.apply(save, false) {
+            return Some(Err(err));
+        }
+
+        let entry = self.redo_stack.pop().unwrap();
+        self.undo_stack.push(entry);
+        self.current_group = None;
+        Some(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::save::*;
+
+    fn make_save() -> SaveGame {
+        SaveGame::new_for_test(vec![
+            Property::new_scalar("Value", PropertyValue::IntProperty(0)),
+            Property::new_scalar("Values", PropertyValue::ArrayProperty { values: vec![PropertyValue::IntProperty(0)] }),
+            Property::new_none(),
+        ])
+    }
+
+    fn value(save: &SaveGame) -> i32 {
+        match save.save_data.get_key("Value") {
+            Some(PropertyValue::IntProperty(i)) => *i,
+            _ => panic!("missing value"),
+        }
+    }
+
+    /// Sets the value as the UI would, returning the edit
+    fn set_value(save: &mut SaveGame, new: i32) -> Vec<Edit> {
+        let path = vec![EditStep::Property(0)];
+        let old = value_mut(&mut save.save_data.properties, &path).unwrap().clone();
+        *value_mut(&mut save.save_data.properties, &path).unwrap() = PropertyValue::IntProperty(new);
+        vec![Edit::Value { path, old, new: PropertyValue::IntProperty(new) }]
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = History::default();
+        let mut save = make_save();
+        history.reset();
+        assert!(history.is_modified());
+        history.mark_saved();
+        assert!(!history.is_modified());
+        assert!(!history.can_undo());
+
+        // two changes in the same group are merged into one edit
+        let edits = set_value(&mut save, 1);
+        assert!(history.record(edits, Some(1)));
+        let edits = set_value(&mut save, 2);
+        assert!(history.record(edits, Some(1)));
+        assert!(!history.record(Vec::new(), None));
+        let edits = set_value(&mut save, 3);
+        assert!(history.record(edits, None));
+
+        history.undo(&mut save).unwrap().unwrap();
+        assert_eq!(value(&save), 2);
+        assert!(history.is_modified());
+        history.undo(&mut save).unwrap().unwrap();
+        assert_eq!(value(&save), 0);
+        assert!(!history.is_modified());
+        assert!(history.undo(&mut save).is_none());
+        history.redo(&mut save).unwrap().unwrap();
+        assert_eq!(value(&save), 2);
+
+        // a new edit discards the redo history
+        let edits = set_value(&mut save, 4);
+        assert!(history.record(edits, None));
+        assert!(!history.can_redo());
+        history.undo(&mut save).unwrap().unwrap();
+        assert_eq!(value(&save), 2);
+    }
+
+    #[test]
+    fn test_undo_structure() {
+        let mut history: History<i32> = History::default();
+        let mut save = make_save();
+        history.reset();
+
+        // deleting an array element and the property before it
+        let element_path = vec![EditStep::Property(1)];
+        let old = value_mut(&mut save.save_data.properties, &element_path).unwrap().clone();
+        let new = PropertyValue::ArrayProperty { values: Vec::new() };
+        *value_mut(&mut save.save_data.properties, &element_path).unwrap() = new.clone();
+        let property = save.save_data.properties.remove(0);
+        history.record(vec![
+            Edit::Value { path: element_path, old, new },
+            Edit::DeleteProperty { path: Vec::new(), index: 0, property },
+        ], None);
+        assert!(save.save_data.get_key("Value").is_none());
+
+        history.undo(&mut save).unwrap().unwrap();
+        assert_eq!(value(&save), 0);
+        assert!(matches!(save.save_data.get_key("Values"), Some(PropertyValue::ArrayProperty { values }) if values.len() == 1));
+
+        history.redo(&mut save).unwrap().unwrap();
+        assert!(save.save_data.get_key("Value").is_none());
+        assert!(matches!(save.save_data.get_key("Values"), Some(PropertyValue::ArrayProperty { values }) if values.is_empty()));
+
+        // an edit that no longer applies is reported and leaves everything as it was
+        history.undo(&mut save).unwrap().unwrap();
+        save.save_data.properties.truncate(1);
+        assert!(history.redo(&mut save).unwrap().is_err());
+        assert_eq!(save.save_data.properties.len(), 1);
+        assert_eq!(value(&save), 0);
+        assert!(history.can_redo());
+    }
+}
@@ -0,0 +1,868 @@
+//! Conversion of save games to and from JSON.
+//!
+//! Every [`PropertyValue`] is written as a single-key object naming its variant, e.g.
+//! `{"IntProperty": 5}`, so a document can be converted back to a byte-identical save without
+//! having to guess how a value was originally parsed. Raw bytes are written as hex strings.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Map, Value};
+
+use crate::save::*;
+use crate::uobject::{make_default_uobject, Stringable};
+
+/// Encodes a byte slice as a lowercase hex string.
+pub fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Decodes a hex string into bytes.
+pub fn from_hex(s: &str) -> Result<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        bail!("Invalid hex string");
+    }
+
+    (0..s.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|e| anyhow!("Invalid hex at index {i}: {e}")))
+        .collect()
+}
+
+fn float_to_json(value: f64, bits: String) -> Value {
+    // JSON can't represent infinities or NaN, so we store the raw bits of those as a string
+    if value.is_finite() {
+        json!(value)
+    } else {
+        json!(bits)
+    }
+}
+
+fn f32_to_json(value: f32) -> Value {
+    float_to_json(value as f64, format!("{:#010x}", value.to_bits()))
+}
+
+fn f64_to_json(value: f64) -> Value {
+    float_to_json(value, format!("{:#018x}", value.to_bits()))
+}
+
+fn get<'a>(object: &'a Value, key: &str) -> Result<&'a Value> {
+    object.get(key).ok_or_else(|| anyhow!("Missing field {key}"))
+}
+
+fn as_str(value: &Value) -> Result<&str> {
+    value.as_str().ok_or_else(|| anyhow!("Expected a string, got {value}"))
+}
+
+fn as_bool(value: &Value) -> Result<bool> {
+    value.as_bool().ok_or_else(|| anyhow!("Expected a boolean, got {value}"))
+}
+
+fn as_array(value: &Value) -> Result<&Vec<Value>> {
+    value.as_array().ok_or_else(|| anyhow!("Expected an array, got {value}"))
+}
+
+fn as_fstring(value: &Value) -> Result<FString> {
+    match value {
+        Value::Null => Ok(FString::new_unterminated()),
+        _ => Ok(FString::from_str(as_str(value)?)),
+    }
+}
+
+/// Converts a string to JSON, using null for empty strings that are stored without a null terminator
+fn fstring_to_json(s: &FString) -> Value {
+    if s.is_unterminated() {
+        Value::Null
+    } else {
+        json!(s.as_str())
+    }
+}
+
+fn as_int<T: TryFrom<i64> + TryFrom<u64>>(value: &Value) -> Result<T> {
+    let int = if let Some(i) = value.as_i64() {
+        T::try_from(i).ok()
+    } else if let Some(u) = value.as_u64() {
+        T::try_from(u).ok()
+    } else {
+        bail!("Expected an integer, got {value}");
+    };
+
+    int.ok_or_else(|| anyhow!("Integer {value} out of range"))
+}
+
+fn as_float_bits(value: &Value) -> Result<u64> {
+    let bits = as_str(value)?;
+    let hex = bits.strip_prefix("0x").ok_or_else(|| anyhow!("Expected a number, got {value}"))?;
+    Ok(u64::from_str_radix(hex, 16)?)
+}
+
+fn as_f32(value: &Value) -> Result<f32> {
+    match value.as_f64() {
+        Some(f) => Ok(f as f32),
+        None => Ok(f32::from_bits(as_float_bits(value)? as u32)),
+    }
+}
+
+fn as_f64(value: &Value) -> Result<f64> {
+    match value.as_f64() {
+        Some(f) => Ok(f),
+        None => Ok(f64::from_bits(as_float_bits(value)?)),
+    }
+}
+
+/// Converts a field of a CoreUObject to JSON, storing floats like float properties so NaN payloads aren't lost
+fn uobject_field_to_json(field: &dyn Stringable) -> Value {
+    let any = field.as_any();
+    if let Some(f) = any.downcast_ref::<f32>() {
+        f32_to_json(*f)
+    } else if let Some(d) = any.downcast_ref::<f64>() {
+        f64_to_json(*d)
+    } else {
+        json!(field.to_string())
+    }
+}
+
+fn set_uobject_field_from_json(field: &mut dyn Stringable, value: &Value) -> Result<()> {
+    if let Some(f) = field.as_any_mut().downcast_mut::<f32>() {
+        *f = as_f32(value)?;
+    } else if let Some(d) = field.as_any_mut().downcast_mut::<f64>() {
+        *d = as_f64(value)?;
+    } else {
+        let s = as_str(value)?;
+        if !field.try_set_from_str(s) {
+            bail!("Invalid value {s}");
+        }
+    }
+    Ok(())
+}
+
+fn header_to_json(header: &SaveGameHeader) -> Value {
+    let engine_version = &header.engine_version;
+    json!({
+        "save_game_version": header.save_game_version,
+        "package_version": [header.package_version.0, header.package_version.1],
+        "engine_version": {
+            "major": engine_version.major,
+            "minor": engine_version.minor,
+            "patch": engine_version.patch,
+            "build": engine_version.build,
+            "build_id": fstring_to_json(&engine_version.build_id),
+        },
+    })
+}
+
+fn header_from_json(value: &Value) -> Result<SaveGameHeader> {
+    let package_version = as_array(get(value, "package_version")?)?;
+    let [ue4_version, ue5_version] = package_version.as_slice() else {
+        bail!("package_version should have two elements");
+    };
+    let engine_version = get(value, "engine_version")?;
+
+    Ok(SaveGameHeader {
+        save_game_version: as_int(get(value, "save_game_version")?)?,
+        package_version: (as_int(ue4_version)?, as_int(ue5_version)?),
+        engine_version: EngineVersion {
+            major: as_int(get(engine_version, "major")?)?,
+            minor: as_int(get(engine_version, "minor")?)?,
+            patch: as_int(get(engine_version, "patch")?)?,
+            build: as_int(get(engine_version, "build")?)?,
+            build_id: as_fstring(get(engine_version, "build_id")?)?,
+        },
+    })
+}
+
+fn custom_format_to_json(custom_format_data: &CustomFormatData) -> Value {
+    json!({
+        "version": custom_format_data.version,
+        "entries": custom_format_data.entries.iter().map(|entry| json!({
+            "guid": entry.guid.to_string(),
+            "value": entry.value,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn custom_format_entry_from_json(value: &Value) -> Result<CustomFormatEntry> {
+    Ok(CustomFormatEntry {
+        guid: Guid::from_str(as_str(get(value, "guid")?)?)?,
+        value: as_int(get(value, "value")?)?,
+    })
+}
+
+fn custom_format_from_json(value: &Value) -> Result<CustomFormatData> {
+    let entries = as_array(get(value, "entries")?)?
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| custom_format_entry_from_json(entry).with_context(|| format!("in custom format entry {i}")))
+        .collect::<Result<_>>()?;
+
+    Ok(CustomFormatData {
+        version: as_int(get(value, "version")?)?,
+        entries,
+    })
+}
+
+fn type_to_json(property_type: &PropertyType) -> Value {
+    json!({
+        "name": fstring_to_json(&property_type.name),
+        "tags": property_type.tags.iter().map(|tag| json!({
+            "kind": tag.kind,
+            "value": fstring_to_json(&tag.value),
+        })).collect::<Vec<_>>(),
+        "inner_types": property_type.inner_types.iter().map(type_to_json).collect::<Vec<_>>(),
+    })
+}
+
+fn type_from_json(value: &Value) -> Result<PropertyType> {
+    let tags = as_array(get(value, "tags")?)?
+        .iter()
+        .map(|tag| Ok(TypeTag { kind: as_int(get(tag, "kind")?)?, value: as_fstring(get(tag, "value")?)? }))
+        .collect::<Result<_>>()?;
+    let inner_types = as_array(get(value, "inner_types")?)?
+        .iter()
+        .map(type_from_json)
+        .collect::<Result<_>>()?;
+
+    Ok(PropertyType {
+        name: as_fstring(get(value, "name")?)?,
+        tags,
+        inner_types,
+    })
+}
+
+fn ftext_to_json(text: &FText) -> Value {
+    json!({
+        "flags": text.flags.bits(),
+        "data": text_to_json(&text.data),
+    })
+}
+
+fn ftext_from_json(value: &Value) -> Result<FText> {
+    Ok(FText {
+        flags: TextFlags::from_bits_retain(as_int(get(value, "flags")?)?),
+        data: Box::new(text_from_json(get(value, "data")?)?),
+    })
+}
+
+fn format_argument_to_json(value: &FormatArgumentValue) -> Value {
+    match value {
+        FormatArgumentValue::Int(i) => json!({ "Int": i }),
+        FormatArgumentValue::UInt(u) => json!({ "UInt": u }),
+        FormatArgumentValue::Float(f) => json!({ "Float": f32_to_json(*f) }),
+        FormatArgumentValue::Double(d) => json!({ "Double": f64_to_json(*d) }),
+        FormatArgumentValue::Text(text) => json!({ "Text": ftext_to_json(text) }),
+        FormatArgumentValue::Gender(g) => json!({ "Gender": g }),
+    }
+}
+
+fn format_argument_from_json(value: &Value) -> Result<FormatArgumentValue> {
+    let (name, inner) = variant(value)?;
+    Ok(match name {
+        "Int" => FormatArgumentValue::Int(as_int(inner)?),
+        "UInt" => FormatArgumentValue::UInt(as_int(inner)?),
+        "Float" => FormatArgumentValue::Float(as_f32(inner)?),
+        "Double" => FormatArgumentValue::Double(as_f64(inner)?),
+        "Text" => FormatArgumentValue::Text(ftext_from_json(inner)?),
+        "Gender" => FormatArgumentValue::Gender(as_int(inner)?),
+        _ => bail!("Unknown format argument type {name}"),
+    })
+}
+
+fn format_argument_data_to_json(argument: &FormatArgumentData) -> Value {
+    let value = match &argument.value {
+        FormatArgumentDataValue::Int(i) => json!({ "Int": i }),
+        FormatArgumentDataValue::Float(f) => json!({ "Float": f32_to_json(*f) }),
+        FormatArgumentDataValue::Double(d) => json!({ "Double": f64_to_json(*d) }),
+        FormatArgumentDataValue::Text(text) => json!({ "Text": ftext_to_json(text) }),
+        FormatArgumentDataValue::Gender(g) => json!({ "Gender": g }),
+    };
+    json!([fstring_to_json(&argument.name), value])
+}
+
+fn format_argument_data_from_json(value: &Value) -> Result<FormatArgumentData> {
+    let [name, value] = as_array(value)?.as_slice() else {
+        bail!("Format arguments should have two elements");
+    };
+    let (type_name, inner) = variant(value)?;
+    Ok(FormatArgumentData {
+        name: as_fstring(name)?,
+        value: match type_name {
+            "Int" => FormatArgumentDataValue::Int(as_int(inner)?),
+            "Float" => FormatArgumentDataValue::Float(as_f32(inner)?),
+            "Double" => FormatArgumentDataValue::Double(as_f64(inner)?),
+            "Text" => FormatArgumentDataValue::Text(ftext_from_json(inner)?),
+            "Gender" => FormatArgumentDataValue::Gender(as_int(inner)?),
+            _ => bail!("Unknown format argument type {type_name}"),
+        },
+    })
+}
+
+fn format_number_to_json(number: &FormatNumber) -> Value {
+    let options = number.options.as_ref().map(|options| json!({
+        "always_sign": options.always_sign,
+        "use_grouping": options.use_grouping,
+        "rounding_mode": options.rounding_mode,
+        "minimum_integral_digits": options.minimum_integral_digits,
+        "maximum_integral_digits": options.maximum_integral_digits,
+        "minimum_fractional_digits": options.minimum_fractional_digits,
+        "maximum_fractional_digits": options.maximum_fractional_digits,
+    }));
+    json!({
+        "source_value": format_argument_to_json(&number.source_value),
+        "options": options,
+        "culture_name": fstring_to_json(&number.culture_name),
+    })
+}
+
+fn format_number_from_json(value: &Value) -> Result<FormatNumber> {
+    let options = match get(value, "options")? {
+        Value::Null => None,
+        options => Some(NumberFormattingOptions {
+            always_sign: as_bool(get(options, "always_sign")?)?,
+            use_grouping: as_bool(get(options, "use_grouping")?)?,
+            rounding_mode: as_int(get(options, "rounding_mode")?)?,
+            minimum_integral_digits: as_int(get(options, "minimum_integral_digits")?)?,
+            maximum_integral_digits: as_int(get(options, "maximum_integral_digits")?)?,
+            minimum_fractional_digits: as_int(get(options, "minimum_fractional_digits")?)?,
+            maximum_fractional_digits: as_int(get(options, "maximum_fractional_digits")?)?,
+        }),
+    };
+    Ok(FormatNumber {
+        source_value: format_argument_from_json(get(value, "source_value")?)?,
+        options,
+        culture_name: as_fstring(get(value, "culture_name")?)?,
+    })
+}
+
+fn text_to_json(data: &TextData) -> Value {
+    match data {
+        TextData::None { values } => json!({
+            "None": {
+                "values": values.iter().map(fstring_to_json).collect::<Vec<_>>(),
+            },
+        }),
+        TextData::Base { namespace, key, source_string } => json!({
+            "Base": {
+                "namespace": fstring_to_json(namespace),
+                "key": fstring_to_json(key),
+                "source_string": fstring_to_json(source_string),
+            },
+        }),
+        TextData::NamedFormat { source_format, arguments } => json!({
+            "NamedFormat": {
+                "source_format": ftext_to_json(source_format),
+                "arguments": arguments.iter()
+                    .map(|(name, value)| json!([fstring_to_json(name), format_argument_to_json(value)]))
+                    .collect::<Vec<_>>(),
+            },
+        }),
+        TextData::OrderedFormat { source_format, arguments } => json!({
+            "OrderedFormat": {
+                "source_format": ftext_to_json(source_format),
+                "arguments": arguments.iter().map(format_argument_to_json).collect::<Vec<_>>(),
+            },
+        }),
+        TextData::ArgumentFormat { source_format, arguments } => json!({
+            "ArgumentFormat": {
+                "source_format": ftext_to_json(source_format),
+                "arguments": arguments.iter().map(format_argument_data_to_json).collect::<Vec<_>>(),
+            },
+        }),
+        TextData::AsNumber { number } => json!({
+            "AsNumber": format_number_to_json(number),
+        }),
+        TextData::AsPercent { number } => json!({
+            "AsPercent": format_number_to_json(number),
+        }),
+        TextData::AsCurrency { currency_code, number } => json!({
+            "AsCurrency": {
+                "currency_code": fstring_to_json(currency_code),
+                "number": format_number_to_json(number),
+            },
+        }),
+        TextData::AsDate { ticks, date_style, time_zone, culture_name } => json!({
+            "AsDate": {
+                "ticks": ticks,
+                "date_style": date_style,
+                "time_zone": fstring_to_json(time_zone),
+                "culture_name": fstring_to_json(culture_name),
+            },
+        }),
+        TextData::AsTime { ticks, time_style, time_zone, culture_name } => json!({
+            "AsTime": {
+                "ticks": ticks,
+                "time_style": time_style,
+                "time_zone": fstring_to_json(time_zone),
+                "culture_name": fstring_to_json(culture_name),
+            },
+        }),
+        TextData::AsDateTime { ticks, date_style, time_style, time_zone, culture_name } => json!({
+            "AsDateTime": {
+                "ticks": ticks,
+                "date_style": date_style,
+                "time_style": time_style,
+                "time_zone": fstring_to_json(time_zone),
+                "culture_name": fstring_to_json(culture_name),
+            },
+        }),
+        TextData::Transform { source_text, transform_type } => json!({
+            "Transform": {
+                "source_text": ftext_to_json(source_text),
+                "transform_type": transform_type,
+            },
+        }),
+        TextData::StringTableEntry { table, key } => json!({
+            "StringTableEntry": {
+                "table": fstring_to_json(table),
+                "key": fstring_to_json(key),
+            },
+        }),
+    }
+}
+
+/// Splits a single-key object like `{"Variant": value}` into its key and value.
+fn variant(value: &Value) -> Result<(&str, &Value)> {
+    match value.as_object() {
+        Some(object) if object.len() == 1 => {
+            let (name, inner) = object.iter().next().unwrap();
+            Ok((name.as_str(), inner))
+        }
+        _ => bail!("Expected an object with a single variant key, got {value}"),
+    }
+}
+
+fn text_from_json(value: &Value) -> Result<TextData> {
+    let (name, fields) = variant(value)?;
+    Ok(match name {
+        "None" => TextData::None {
+            values: as_array(get(fields, "values")?)?.iter().map(as_fstring).collect::<Result<_>>()?,
+        },
+        "Base" => TextData::Base {
+            namespace: as_fstring(get(fields, "namespace")?)?,
+            key: as_fstring(get(fields, "key")?)?,
+            source_string: as_fstring(get(fields, "source_string")?)?,
+        },
+        "NamedFormat" => TextData::NamedFormat {
+            source_format: ftext_from_json(get(fields, "source_format")?)?,
+            arguments: as_array(get(fields, "arguments")?)?
+                .iter()
+                .map(|argument| {
+                    let [name, value] = as_array(argument)?.as_slice() else {
+                        bail!("Format arguments should have two elements");
+                    };
+                    Ok((as_fstring(name)?, format_argument_from_json(value)?))
+                })
+                .collect::<Result<_>>()?,
+        },
+        "OrderedFormat" => TextData::OrderedFormat {
+            source_format: ftext_from_json(get(fields, "source_format")?)?,
+            arguments: as_array(get(fields, "arguments")?)?.iter().map(format_argument_from_json).collect::<Result<_>>()?,
+        },
+        "ArgumentFormat" => TextData::ArgumentFormat {
+            source_format: ftext_from_json(get(fields, "source_format")?)?,
+            arguments: as_array(get(fields, "arguments")?)?.iter().map(format_argument_data_from_json).collect::<Result<_>>()?,
+        },
+        "AsNumber" => TextData::AsNumber {
+            number: format_number_from_json(fields)?,
+        },
+        "AsPercent" => TextData::AsPercent {
+            number: format_number_from_json(fields)?,
+        },
+        "AsCurrency" => TextData::AsCurrency {
+            currency_code: as_fstring(get(fields, "currency_code")?)?,
+            number: format_number_from_json(get(fields, "number")?)?,
+        },
+        "AsDate" => TextData::AsDate {
+            ticks: as_int(get(fields, "ticks")?)?,
+            date_style: as_int(get(fields, "date_style")?)?,
+            time_zone: as_fstring(get(fields, "time_zone")?)?,
+            culture_name: as_fstring(get(fields, "culture_name")?)?,
+        },
+        "AsTime" => TextData::AsTime {
+            ticks: as_int(get(fields, "ticks")?)?,
+            time_style: as_int(get(fields, "time_style")?)?,
+            time_zone: as_fstring(get(fields, "time_zone")?)?,
+            culture_name: as_fstring(get(fields, "culture_name")?)?,
+        },
+        "AsDateTime" => TextData::AsDateTime {
+            ticks: as_int(get(fields, "ticks")?)?,
+            date_style: as_int(get(fields, "date_style")?)?,
+            time_style: as_int(get(fields, "time_style")?)?,
+            time_zone: as_fstring(get(fields, "time_zone")?)?,
+            culture_name: as_fstring(get(fields, "culture_name")?)?,
+        },
+        "Transform" => TextData::Transform {
+            source_text: ftext_from_json(get(fields, "source_text")?)?,
+            transform_type: as_int(get(fields, "transform_type")?)?,
+        },
+        "StringTableEntry" => TextData::StringTableEntry {
+            table: as_fstring(get(fields, "table")?)?,
+            key: as_fstring(get(fields, "key")?)?,
+        },
+        _ => bail!("Unknown text history type {name}"),
+    })
+}
+
+fn soft_object_path_from_json(value: &Value) -> Result<SoftObjectPath> {
+    Ok(SoftObjectPath {
+        package_name: as_fstring(get(value, "package_name")?)?,
+        asset_name: as_fstring(get(value, "asset_name")?)?,
+        sub_path: as_fstring(get(value, "sub_path")?)?,
+    })
+}
+
+/// Converts a property value to JSON.
+pub fn value_to_json(value: &PropertyValue) -> Value {
+    let inner = match value {
+        PropertyValue::StrProperty(s) | PropertyValue::EnumProperty(s) | PropertyValue::ByteEnumProperty(s) | PropertyValue::NameProperty(s) | PropertyValue::ObjectProperty(s) => fstring_to_json(s),
+        PropertyValue::BoolProperty(b) => json!(b),
+        PropertyValue::ByteProperty(b) => json!(b),
+        PropertyValue::IntProperty(i) => json!(i),
+        PropertyValue::Int8Property(i) => json!(i),
+        PropertyValue::Int16Property(i) => json!(i),
+        PropertyValue::Int64Property(i) => json!(i),
+        PropertyValue::UInt16Property(i) => json!(i),
+        PropertyValue::UInt32Property(i) => json!(i),
+        PropertyValue::UInt64Property(i) => json!(i),
+        PropertyValue::FloatProperty(f) => f32_to_json(*f),
+        PropertyValue::DoubleProperty(d) => f64_to_json(*d),
+        PropertyValue::TextProperty { flags, data } => json!({
+            "flags": flags.bits(),
+            "data": text_to_json(data),
+        }),
+        PropertyValue::SoftObjectProperty(path) | PropertyValue::SoftClassProperty(path) => json!({
+            "package_name": fstring_to_json(&path.package_name),
+            "asset_name": fstring_to_json(&path.asset_name),
+            "sub_path": fstring_to_json(&path.sub_path),
+        }),
+        PropertyValue::StructProperty(props) => properties_to_json(props),
+        PropertyValue::CustomStructProperty(custom_struct) => json!({
+            "flags": custom_struct.flags,
+            "properties": properties_to_json(&custom_struct.properties),
+            "extra": to_hex(&custom_struct.extra),
+        }),
+        PropertyValue::CoreUObjectStructProperty(object) => {
+            let fields: Map<_, _> = object.fields()
+                .into_iter()
+                .map(|(name, field)| (name.to_string(), uobject_field_to_json(field)))
+                .collect();
+            json!({
+                "type": object.type_name(),
+                "fields": fields,
+            })
+        }
+        PropertyValue::GameplayTagContainerProperty(container) => Value::Array(container.tags.iter().map(fstring_to_json).collect()),
+        PropertyValue::ArrayProperty { values } => Value::Array(values.iter().map(value_to_json).collect()),
+        PropertyValue::MapProperty { removed_count, values } => json!({
+            "removed_count": removed_count,
+            "entries": values.iter().map(|(k, v)| json!([value_to_json(k), value_to_json(v)])).collect::<Vec<_>>(),
+        }),
+        PropertyValue::SetProperty { removed_count, values } => json!({
+            "removed_count": removed_count,
+            "values": values.iter().map(value_to_json).collect::<Vec<_>>(),
+        }),
+        PropertyValue::UnknownProperty(data) => json!(to_hex(data)),
+    };
+
+    let name = match value {
+        PropertyValue::ByteEnumProperty(_) => "ByteEnumProperty",
+        PropertyValue::CustomStructProperty(_) => "CustomStructProperty",
+        PropertyValue::CoreUObjectStructProperty(_) => "CoreUObjectStructProperty",
+        PropertyValue::GameplayTagContainerProperty(_) => "GameplayTagContainerProperty",
+        PropertyValue::UnknownProperty(_) => "UnknownProperty",
+        _ => value.type_name(),
+    };
+
+    let mut object = Map::new();
+    object.insert(name.to_string(), inner);
+    Value::Object(object)
+}
+
+fn map_entry_from_json(value: &Value) -> Result<(PropertyValue, PropertyValue)> {
+    match as_array(value)?.as_slice() {
+        [k, v] => Ok((value_from_json(k)?, value_from_json(v)?)),
+        _ => bail!("Map entries should be [key, value] pairs"),
+    }
+}
+
+/// Converts JSON produced by [`value_to_json`] back to a property value.
+pub fn value_from_json(value: &Value) -> Result<PropertyValue> {
+    let (name, inner) = variant(value)?;
+    Ok(match name {
+        "StrProperty" => PropertyValue::StrProperty(as_fstring(inner)?),
+        "EnumProperty" => PropertyValue::EnumProperty(as_fstring(inner)?),
+        "NameProperty" => PropertyValue::NameProperty(as_fstring(inner)?),
+        "ObjectProperty" => PropertyValue::ObjectProperty(as_fstring(inner)?),
+        "BoolProperty" => PropertyValue::BoolProperty(match inner {
+            Value::Null => None,
+            Value::Bool(b) => Some(*b),
+            _ => bail!("Expected a boolean or null, got {inner}"),
+        }),
+        "ByteProperty" => PropertyValue::ByteProperty(as_int(inner)?),
+        "ByteEnumProperty" => PropertyValue::ByteEnumProperty(as_fstring(inner)?),
+        "IntProperty" => PropertyValue::IntProperty(as_int(inner)?),
+        "Int8Property" => PropertyValue::Int8Property(as_int(inner)?),
+        "Int16Property" => PropertyValue::Int16Property(as_int(inner)?),
+        "Int64Property" => PropertyValue::Int64Property(as_int(inner)?),
+        "UInt16Property" => PropertyValue::UInt16Property(as_int(inner)?),
+        "UInt32Property" => PropertyValue::UInt32Property(as_int(inner)?),
+        "UInt64Property" => PropertyValue::UInt64Property(as_int(inner)?),
+        "FloatProperty" => PropertyValue::FloatProperty(as_f32(inner)?),
+        "DoubleProperty" => PropertyValue::DoubleProperty(as_f64(inner)?),
+        "TextProperty" => PropertyValue::TextProperty {
+            // unwrap is safe because we used the unnamed field trick to make all bits legal
+            flags: TextFlags::from_bits(as_int(get(inner, "flags")?)?).unwrap(),
+            data: text_from_json(get(inner, "data")?)?,
+        },
+        "SoftObjectProperty" => PropertyValue::SoftObjectProperty(soft_object_path_from_json(inner)?),
+        "SoftClassProperty" => PropertyValue::SoftClassProperty(soft_object_path_from_json(inner)?),
+        "StructProperty" => PropertyValue::StructProperty(properties_from_json(inner)?),
+        "CustomStructProperty" => PropertyValue::CustomStructProperty(CustomStruct {
+            flags: as_int(get(inner, "flags")?)?,
+            properties: properties_from_json(get(inner, "properties")?)?,
+            extra: from_hex(as_str(get(inner, "extra")?)?)?,
+        }),
+        "CoreUObjectStructProperty" => {
+            let type_name = as_str(get(inner, "type")?)?;
+            let mut object = make_default_uobject(type_name).ok_or_else(|| anyhow!("Unknown CoreUObject type {type_name}"))?;
+            let fields = get(inner, "fields")?;
+            for (field_name, field) in object.fields_mut() {
+                set_uobject_field_from_json(field, get(fields, field_name)?).with_context(|| format!("in field {field_name}"))?;
+            }
+            PropertyValue::CoreUObjectStructProperty(object)
+        }
+        "GameplayTagContainerProperty" => PropertyValue::GameplayTagContainerProperty(GameplayTagContainer {
+            tags: as_array(inner)?.iter().map(as_fstring).collect::<Result<_>>()?,
+        }),
+        "ArrayProperty" => PropertyValue::ArrayProperty {
+            values: as_array(inner)?
+                .iter()
+                .enumerate()
+                .map(|(i, v)| value_from_json(v).with_context(|| format!("in element {i}")))
+                .collect::<Result<_>>()?,
+        },
+        "MapProperty" => PropertyValue::MapProperty {
+            removed_count: as_int(get(inner, "removed_count")?)?,
+            values: as_array(get(inner, "entries")?)?
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| map_entry_from_json(entry).with_context(|| format!("in entry {i}")))
+                .collect::<Result<_>>()?,
+        },
+        "SetProperty" => PropertyValue::SetProperty {
+            removed_count: as_int(get(inner, "removed_count")?)?,
+            values: as_array(get(inner, "values")?)?
+                .iter()
+                .enumerate()
+                .map(|(i, v)| value_from_json(v).with_context(|| format!("in element {i}")))
+                .collect::<Result<_>>()?,
+        },
+        "UnknownProperty" => PropertyValue::UnknownProperty(from_hex(as_str(inner)?)?),
+        _ => bail!("Unknown property value type {name}"),
+    })
+}
+
+/// Converts a property to JSON.
+pub fn property_to_json(property: &Property) -> Value {
+    let mut object = Map::new();
+    object.insert(String::from("name"), fstring_to_json(&property.name));
+    if let Some(body) = &property.body {
+        object.insert(String::from("type"), type_to_json(&body.property_type));
+        object.insert(String::from("flags"), json!(body.flags));
+        object.insert(String::from("value"), value_to_json(&body.value));
+    }
+    Value::Object(object)
+}
+
+/// Converts JSON produced by [`property_to_json`] back to a property.
+pub fn property_from_json(value: &Value) -> Result<Property> {
+    let name = as_fstring(get(value, "name")?)?;
+    let body = match value.get("value") {
+        Some(property_value) => Some(PropertyBody {
+            property_type: type_from_json(get(value, "type")?)?,
+            flags: as_int(get(value, "flags")?)?,
+            value: value_from_json(property_value)?,
+        }),
+        None => None,
+    };
+
+    Ok(Property { name, body })
+}
+
+fn properties_to_json(properties: &[Property]) -> Value {
+    Value::Array(properties.iter().map(property_to_json).collect())
+}
+
+fn properties_from_json(value: &Value) -> Result<Vec<Property>> {
+    as_array(value)?
+        .iter()
+        .enumerate()
+        .map(|(i, property)| {
+            property_from_json(property).with_context(|| match property.get("name").and_then(Value::as_str) {
+                Some(name) => format!("in property {i} ({name})"),
+                None => format!("in property {i}"),
+            })
+        })
+        .collect()
+}
+
+/// Converts a save game to a JSON document.
+pub fn to_json(save: &SaveGame) -> Value {
+    json!({
+        "header": header_to_json(&save.header),
+        "custom_format_data": custom_format_to_json(&save.custom_format_data),
+        "save_data": {
+            "type_name": fstring_to_json(&save.save_data.type_name),
+            "flags": save.save_data.flags,
+            "properties": properties_to_json(&save.save_data.properties),
+            "extra": save.save_data.extra,
+        },
+    })
+}
+
+/// Converts a JSON document produced by [`to_json`] back to a save game.
+pub fn from_json(value: &Value) -> Result<SaveGame> {
+    let save_data = get(value, "save_data")?;
+    Ok(SaveGame {
+        header: header_from_json(get(value, "header")?).context("in header")?,
+        custom_format_data: custom_format_from_json(get(value, "custom_format_data")?).context("in custom_format_data")?,
+        save_data: SaveGameData {
+            type_name: as_fstring(get(save_data, "type_name")?)?,
+            flags: as_int(get(save_data, "flags")?)?,
+            properties: properties_from_json(get(save_data, "properties")?)?,
+            extra: as_int(get(save_data, "extra")?)?,
+        },
+    })
+}
+
+/// Converts a save game to a pretty-printed JSON string.
+pub fn to_json_string(save: &SaveGame) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&to_json(save))?)
+}
+
+/// Parses a save game from a JSON string produced by [`to_json_string`].
+pub fn from_json_str(s: &str) -> Result<SaveGame> {
+    from_json(&serde_json::from_str(s)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+    use binrw::BinWriterExt;
+
+    fn to_bytes(save: &SaveGame) -> Vec<u8> {
+        let mut data = Vec::new();
+        Cursor::new(&mut data).write_le(save).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(to_hex(&[0x00, 0xAB, 0x10]), "00ab10");
+        assert_eq!(from_hex("00ab10").unwrap(), vec![0x00, 0xAB, 0x10]);
+        assert!(from_hex("abc").is_err());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut location = make_default_uobject("Vector").unwrap();
+        for ((_, field), value) in location.fields_mut().into_iter().zip(["1.5", "-2", "3"]) {
+            assert!(field.try_set_from_str(value));
+        }
+        // floats that don't survive being formatted as a string
+        let mut odd_location = make_default_uobject("Vector").unwrap();
+        for ((_, field), value) in odd_location.fields_mut().into_iter().zip([f64::from_bits(0xfff8_0000_0000_1234), -0.0, f64::INFINITY]) {
+            *field.as_any_mut().downcast_mut::<f64>().unwrap() = value;
+        }
+        let vector_type = PropertyType {
+            name: FString::from_str("StructProperty"),
+            tags: vec![TypeTag::new(2, "Vector"), TypeTag::new(1, "/Script/CoreUObject")],
+            inner_types: Vec::new(),
+        };
+
+        let mut save = SaveGame::new_for_test(vec![
+            Property::new_scalar("Float", PropertyValue::FloatProperty(0.1)),
+            Property::new_scalar("NaN", PropertyValue::DoubleProperty(f64::NAN)),
+            Property::new_scalar("Int64", PropertyValue::Int64Property(i64::MIN)),
+            Property::new_scalar("UInt64", PropertyValue::UInt64Property(u64::MAX)),
+            Property::new_enum("Enum", "/Script/GameNoce", "ENoceActionLevel", "ENoceActionLevel::Hard"),
+            Property::new_scalar("Text", PropertyValue::TextProperty {
+                flags: TextFlags::CULTURE_INVARIANT,
+                data: TextData::Base {
+                    namespace: FString::new(),
+                    key: FString::from_str("key"),
+                    source_string: FString::from_str("source"),
+                },
+            }),
+            Property::new_scalar("Level", PropertyValue::SoftObjectProperty(SoftObjectPath {
+                package_name: FString::from_str("/Game/Maps/Village"),
+                asset_name: FString::from_str("Village"),
+                sub_path: FString::new_unterminated(),
+            })),
+            Property::new_scalar("Percent", PropertyValue::TextProperty {
+                flags: TextFlags::empty(),
+                data: TextData::AsPercent {
+                    number: FormatNumber {
+                        source_value: FormatArgumentValue::Double(0.5),
+                        options: None,
+                        culture_name: FString::from_str("en"),
+                    },
+                },
+            }),
+            Property::new_scalar("Tags", PropertyValue::GameplayTagContainerProperty(GameplayTagContainer {
+                tags: vec![FString::from_str("Noce.Event.A"), FString::from_str("Noce.Event.B")],
+            })),
+            Property {
+                name: FString::from_str("Facing"),
+                body: Some(PropertyBody {
+                    property_type: PropertyType {
+                        name: FString::from_str("ByteProperty"),
+                        tags: vec![TypeTag::new(2, "ENoceFacing"), TypeTag::new(1, "/Script/GameNoce")],
+                        inner_types: Vec::new(),
+                    },
+                    flags: 0,
+                    value: PropertyValue::ByteEnumProperty(FString::from_str("ENoceFacing::Left")),
+                }),
+            },
+            Property {
+                name: FString::from_str("Location"),
+                body: Some(PropertyBody {
+                    property_type: vector_type.clone(),
+                    flags: 0,
+                    value: PropertyValue::CoreUObjectStructProperty(location),
+                }),
+            },
+            Property {
+                name: FString::from_str("OddLocation"),
+                body: Some(PropertyBody {
+                    property_type: vector_type,
+                    flags: 0,
+                    value: PropertyValue::CoreUObjectStructProperty(odd_location),
+                }),
+            },
+            Property {
+                name: FString::from_str("Data"),
+                body: Some(PropertyBody {
+                    property_type: PropertyType {
+                        name: FString::from_str("StructProperty"),
+                        tags: vec![TypeTag::new(2, "NoceData"), TypeTag { kind: 1, value: FString::new_unterminated() }],
+                        inner_types: Vec::new(),
+                    },
+                    flags: 0,
+                    value: PropertyValue::StructProperty(vec![
+                        Property::new_scalar("Inner", PropertyValue::IntProperty(1)),
+                        Property::new_none(),
+                    ]),
+                }),
+            },
+            Property::new_scalar("Unknown", PropertyValue::UnknownProperty(vec![1, 2, 3])),
+            Property::new_none(),
+        ]);
+        save.header.engine_version.build_id = FString::from_str("UE5");
+        save.custom_format_data.entries = vec![CustomFormatEntry { guid: Guid::from_str("01020304-0506-0708-090a-0b0c0d0e0f10").unwrap(), value: 7 }];
+
+        let json = to_json_string(&save).unwrap();
+        let parsed = from_json_str(&json).unwrap();
+        assert_eq!(to_bytes(&parsed), to_bytes(&save));
+    }
+}
@@ -0,0 +1,22 @@
+//! Parsing and editing of Silent Hill f save files.
+//!
+//! Saves are Unreal Engine 5 GVAS files. [`save::SaveGame`] is the entry point for reading and writing them with
+//! [`binrw`], and [`save::Indexable`] provides access to the property tree by name or index and edits its structure.
+//! [`game`] contains game-specific constants and item catalogs, [`path`] parses property paths at runtime, and [`json`]
+//! converts saves to and from a human-readable JSON document. [`verify`] checks that a save can be written and read
+//! back without changes, and [`diff`] compares two saves. [`backup`] keeps copies of saves before they're overwritten,
+//! and [`history`] records edits so they can be undone.
+//! [`registry`] lists the struct types the parser can decode and can be extended with new ones. [`records`] provides
+//! typed views of the inventory and player stats.
+
+pub mod backup;
+pub mod diff;
+pub mod game;
+pub mod history;
+pub mod json;
+pub mod path;
+pub mod records;
+pub mod registry;
+pub mod save;
+pub mod uobject;
+pub mod verify;
@@ -1,22 +1,64 @@
 #![cfg_attr(windows, windows_subsystem = "windows")]
 
+#[cfg(feature = "gui")]
 use std::path::PathBuf;
 
+#[cfg(feature = "gui")]
 use eframe::NativeOptions;
 
-mod app;
-mod game;
-mod save;
-mod uobject;
+use shf_save_editor::registry;
 
-fn main() -> eframe::Result<()> {
-    let initial_path = std::env::args().nth(1).map(PathBuf::from);
+#[cfg(feature = "gui")]
+mod app;
+mod cli;
 
+#[cfg(feature = "gui")]
+fn run_gui(initial_path: Option<PathBuf>, classes_error: Option<String>) -> eframe::Result<()> {
     let options = NativeOptions::default();
     eframe::run_native(
-        "Silent Hill f Save Editor",
+        app::APP_TITLE,
         options,
-        Box::new(|cc| Ok(Box::new(app::AppState::load_app(cc, initial_path)))),
+        Box::new(|cc| Ok(Box::new(app::AppState::load_app(cc, initial_path, classes_error)))),
     )
 }
 
+/// Loads the custom struct class overrides from the editor's folder, if there are any
+fn load_custom_struct_classes() -> anyhow::Result<()> {
+    let Some(path) = std::env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(registry::CUSTOM_STRUCT_CLASSES_FILE_NAME)))
+    else {
+        return Ok(());
+    };
+
+    if path.is_file() {
+        registry::registry_mut().load_custom_struct_classes_file(&path)?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let load_result = load_custom_struct_classes();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = args.first() && cli::is_command(command) {
+        if let Err(err) = &load_result {
+            eprintln!("Warning: {err:#}");
+        }
+        std::process::exit(cli::run(&args));
+    }
+
+    #[cfg(feature = "gui")]
+    if let Err(err) = run_gui(args.into_iter().next().map(PathBuf::from), load_result.err().map(|err| format!("{err:#}"))) {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    }
+
+    #[cfg(not(feature = "gui"))]
+    {
+        if let Err(err) = &load_result {
+            eprintln!("Warning: {err:#}");
+        }
+        eprintln!("This build does not include the GUI; only commands are available.\n");
+        std::process::exit(cli::run(&[String::from("help")]));
+    }
+}
@@ -0,0 +1,486 @@
+//! Property paths parsed at runtime.
+//!
+//! A path is a list of segments separated by dots, with array indexes, map keys and filters in brackets, e.g.
+//! `PlayerStateRecord.ComponentRecords[Class=/Script/GameNoce.NocePlayerInventoryComponent].Data.KeyItems[5]`. Each
+//! segment selects children of the values matched so far:
+//!
+//! - `Name` or `[Name]` selects the property with that name, or the map value with that key
+//! - `[3]` selects the element at that index, or the map value with that key
+//! - `*` or `[*]` selects every child
+//! - `[Field=value]` selects every child whose `Field` property has the given scalar value
+//!
+//! Within brackets, a backslash escapes the next character, e.g. `[a\]b]` is the key `a]b`, `[a\=b]` is the key `a=b`
+//! and `[\*]` is the key `*`.
+//!
+//! Unlike [`prop!`](crate::save::prop), a path can match any number of values, and all of them are returned with the
+//! concrete path of each one.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::save::{Indexable, Property, PropertyValue};
+use crate::verify::{element_label, join_path};
+
+/// An error parsing or resolving a property path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// The path isn't valid
+    Syntax {
+        path: String,
+        message: &'static str,
+    },
+    /// Nothing matched one of the segments
+    NoMatch {
+        /// The segments before the one that failed
+        parent: String,
+        /// The segment that failed
+        segment: String,
+    },
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Syntax { path, message } => write!(f, "Invalid path {path}: {message}"),
+            Self::NoMatch { parent, segment } if parent.is_empty() => write!(f, "No property matches {segment}"),
+            Self::NoMatch { parent, segment } => write!(f, "Nothing in {parent} matches {segment}"),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// A single step in a property path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A property name or map key
+    Key(String),
+    /// An array index or map key
+    Index(usize),
+    /// Every child
+    Wildcard,
+    /// Every child with a property `field` whose scalar value is `value`
+    Filter {
+        field: String,
+        value: String,
+    },
+}
+
+impl PathSegment {
+    /// Parses the contents of a bracketed segment, which may contain escaped characters
+    fn parse_bracketed(contents: &str) -> Self {
+        if contents == "*" {
+            return Self::Wildcard;
+        }
+        if let Some(i) = find_unescaped(contents, '=') && i > 0 {
+            return Self::Filter { field: unescape(&contents[..i]), value: unescape(&contents[i + 1..]) };
+        }
+        match contents.parse::<usize>() {
+            Ok(index) => Self::Index(index),
+            Err(_) => Self::Key(unescape(contents)),
+        }
+    }
+
+    fn matches(&self, label: &str, key: &ChildKey, value: Option<&PropertyValue>) -> bool {
+        match (self, key) {
+            (Self::Wildcard, _) => true,
+            (Self::Key(name), ChildKey::Name) => name == label,
+            (Self::Key(name), ChildKey::MapKey(map_key)) => *map_key == name,
+            (Self::Index(index), ChildKey::Index(child_index)) => index == child_index,
+            (Self::Index(index), ChildKey::MapKey(map_key)) => *map_key == index,
+            (Self::Filter { field, value: expected }, _) => value
+                .and_then(|value| value.get_key(field))
+                .and_then(PropertyValue::scalar_string)
+                .is_some_and(|actual| actual == *expected),
+            _ => false,
+        }
+    }
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // keys that would otherwise be split into several segments or read as a wildcard have to be bracketed, with
+            // the characters that mean something within brackets escaped
+            Self::Key(key) if key.is_empty() || key == "*" || key.contains(['.', '[', ']', '=', '\\']) => {
+                write!(f, "[{}]", escape(key, &['\\', ']', '=', '*']))
+            }
+            Self::Key(key) => write!(f, "{key}"),
+            Self::Index(index) => write!(f, "[{index}]"),
+            Self::Wildcard => write!(f, "*"),
+            Self::Filter { field, value } => {
+                write!(f, "[{}={}]", escape(field, &['\\', ']', '=']), escape(value, &['\\', ']']))
+            }
+        }
+    }
+}
+
+/// Returns the byte offset of the first occurrence of `c` that isn't escaped with a backslash
+fn find_unescaped(s: &str, c: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if ch == c => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn escape(s: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        if special.contains(&ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(ch),
+        }
+    }
+    unescaped
+}
+
+/// A parsed property path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyPath {
+    segments: Vec<PathSegment>,
+}
+
+impl PropertyPath {
+    /// Parses a path
+    pub fn parse(path: &str) -> Result<Self, PathError> {
+        let syntax_error = |message| PathError::Syntax { path: path.to_string(), message };
+        let mut segments = Vec::new();
+        let mut rest = path;
+
+        while !rest.is_empty() {
+            if let Some(bracketed) = rest.strip_prefix('[') {
+                let end = find_unescaped(bracketed, ']').ok_or_else(|| syntax_error("unterminated '['"))?;
+                segments.push(PathSegment::parse_bracketed(&bracketed[..end]));
+                rest = &bracketed[end + 1..];
+                if !rest.is_empty() && !rest.starts_with(['.', '[']) {
+                    return Err(syntax_error("expected '.' or '[' after ']'"));
+                }
+            } else {
+                let rest_without_dot = rest.strip_prefix('.').unwrap_or(rest);
+                let end = rest_without_dot.find(['.', '[']).unwrap_or(rest_without_dot.len());
+                let name = &rest_without_dot[..end];
+                segments.push(match name {
+                    "" => return Err(syntax_error("empty property name")),
+                    "*" => PathSegment::Wildcard,
+                    _ => PathSegment::Key(name.to_string()),
+                });
+                rest = &rest_without_dot[end..];
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(syntax_error("empty path"));
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    fn no_match(&self, index: usize) -> PathError {
+        PathError::NoMatch {
+            parent: Self { segments: self.segments[..index].to_vec() }.to_string(),
+            segment: self.segments[index].to_string(),
+        }
+    }
+
+    /// Finds all values matching the path in a list of properties, such as those of a save or a struct
+    ///
+    /// Fails if any segment matches nothing.
+    pub fn select<'a>(&self, properties: &'a [Property]) -> Result<Vec<PathMatch<'a>>, PathError> {
+        let mut parents = vec![(String::new(), property_children(properties))];
+        for (i, segment) in self.segments.iter().enumerate() {
+            let mut matches = Vec::new();
+            for (parent, children) in parents {
+                for (label, key, target) in children {
+                    if segment.matches(&label, &key, target.value()) {
+                        matches.push(PathMatch { path: join_path(&parent, &label), target });
+                    }
+                }
+            }
+
+            if matches.is_empty() {
+                return Err(self.no_match(i));
+            }
+            if i + 1 == self.segments.len() {
+                return Ok(matches);
+            }
+            parents = matches.into_iter().map(|m| (m.path, m.target.children())).collect();
+        }
+        unreachable!("parsed paths always have at least one segment")
+    }
+
+    /// Mutable version of [`Self::select`]
+    pub fn select_mut<'a>(&self, properties: &'a mut [Property]) -> Result<Vec<PathMatchMut<'a>>, PathError> {
+        let mut parents = vec![(String::new(), property_children_mut(properties))];
+        for (i, segment) in self.segments.iter().enumerate() {
+            let mut matches = Vec::new();
+            for (parent, children) in parents {
+                for (label, key, target) in children {
+                    if segment.matches(&label, &key, target.value()) {
+                        matches.push(PathMatchMut { path: join_path(&parent, &label), target });
+                    }
+                }
+            }
+
+            if matches.is_empty() {
+                return Err(self.no_match(i));
+            }
+            if i + 1 == self.segments.len() {
+                return Ok(matches);
+            }
+            parents = matches.into_iter().map(|m| (m.path, m.target.children())).collect();
+        }
+        unreachable!("parsed paths always have at least one segment")
+    }
+}
+
+impl FromStr for PropertyPath {
+    type Err = PathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for PropertyPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let path = self.segments.iter().fold(String::new(), |path, segment| join_path(&path, &segment.to_string()));
+        write!(f, "{path}")
+    }
+}
+
+/// How a child is identified within its parent
+enum ChildKey<'a> {
+    /// A property, identified by its name, which is also its label
+    Name,
+    Index(usize),
+    MapKey(&'a PropertyValue),
+}
+
+/// A value matched by a path
+#[derive(Debug, Clone, Copy)]
+pub enum PathTarget<'a> {
+    /// A named property, which gives access to the property flags as well as the value
+    Property(&'a Property),
+    /// An array, set or map element
+    Value(&'a PropertyValue),
+}
+
+impl<'a> PathTarget<'a> {
+    pub fn value(&self) -> Option<&'a PropertyValue> {
+        match self {
+            Self::Property(property) => property.body.as_ref().map(|body| &body.value),
+            Self::Value(value) => Some(value),
+        }
+    }
+
+    fn children(self) -> Vec<(String, ChildKey<'a>, Self)> {
+        match self.value() {
+            Some(PropertyValue::StructProperty(properties)) => property_children(properties),
+            Some(PropertyValue::CustomStructProperty(custom_struct)) => property_children(&custom_struct.properties),
+            Some(PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. }) => values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| (element_label(i, None), ChildKey::Index(i), Self::Value(value)))
+                .collect(),
+            Some(PropertyValue::MapProperty { values, .. }) => values
+                .iter()
+                .enumerate()
+                .map(|(i, (key, value))| (element_label(i, Some(key)), ChildKey::MapKey(key), Self::Value(value)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn property_children(properties: &[Property]) -> Vec<(String, ChildKey<'_>, PathTarget<'_>)> {
+    properties
+        .iter()
+        .filter(|property| !property.is_none())
+        .map(|property| (property.name.to_string(), ChildKey::Name, PathTarget::Property(property)))
+        .collect()
+}
+
+/// Mutable version of [`PathTarget`]
+#[derive(Debug)]
+pub enum PathTargetMut<'a> {
+    Property(&'a mut Property),
+    Value(&'a mut PropertyValue),
+}
+
+impl<'a> PathTargetMut<'a> {
+    pub fn value(&self) -> Option<&PropertyValue> {
+        match self {
+            Self::Property(property) => property.body.as_ref().map(|body| &body.value),
+            Self::Value(value) => Some(value),
+        }
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut PropertyValue> {
+        match self {
+            Self::Property(property) => property.body.as_mut().map(|body| &mut body.value),
+            Self::Value(value) => Some(value),
+        }
+    }
+
+    fn children(self) -> Vec<(String, ChildKey<'a>, Self)> {
+        let value = match self {
+            Self::Property(property) => match property.body.as_mut() {
+                Some(body) => &mut body.value,
+                None => return Vec::new(),
+            },
+            Self::Value(value) => value,
+        };
+
+        match value {
+            PropertyValue::StructProperty(properties) => property_children_mut(properties),
+            PropertyValue::CustomStructProperty(custom_struct) => property_children_mut(&mut custom_struct.properties),
+            PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. } => values
+                .iter_mut()
+                .enumerate()
+                .map(|(i, value)| (element_label(i, None), ChildKey::Index(i), Self::Value(value)))
+                .collect(),
+            PropertyValue::MapProperty { values, .. } => values
+                .iter_mut()
+                .enumerate()
+                .map(|(i, (key, value))| {
+                    let key: &'a PropertyValue = key;
+                    (element_label(i, Some(key)), ChildKey::MapKey(key), Self::Value(value))
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn property_children_mut(properties: &mut [Property]) -> Vec<(String, ChildKey<'_>, PathTargetMut<'_>)> {
+    properties
+        .iter_mut()
+        .filter(|property| !property.is_none())
+        .map(|property| (property.name.to_string(), ChildKey::Name, PathTargetMut::Property(property)))
+        .collect()
+}
+
+/// A value matched by a path, along with its concrete path
+#[derive(Debug, Clone)]
+pub struct PathMatch<'a> {
+    pub path: String,
+    pub target: PathTarget<'a>,
+}
+
+/// Mutable version of [`PathMatch`]
+#[derive(Debug)]
+pub struct PathMatchMut<'a> {
+    pub path: String,
+    pub target: PathTargetMut<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::save::{PropertyBody, SaveGame};
+
+    fn component(class: &str, items: &[bool]) -> PropertyValue {
+        SaveGame::component_record_for_test(class, PropertyBody::new_scalar(PropertyValue::StructProperty(vec![
+            Property::new_scalar("KeyItems", PropertyValue::ArrayProperty {
+                values: items.iter().map(|item| PropertyValue::BoolProperty(Some(*item))).collect(),
+            }),
+            Property::new_none(),
+        ])))
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let path = PropertyPath::parse("PlayerStateRecord.ComponentRecords[3].Data[Key].*[Class=/Script/Test.Foo][*]").unwrap();
+        assert_eq!(path.segments(), [
+            PathSegment::Key(String::from("PlayerStateRecord")),
+            PathSegment::Key(String::from("ComponentRecords")),
+            PathSegment::Index(3),
+            PathSegment::Key(String::from("Data")),
+            PathSegment::Key(String::from("Key")),
+            PathSegment::Wildcard,
+            PathSegment::Filter { field: String::from("Class"), value: String::from("/Script/Test.Foo") },
+            PathSegment::Wildcard,
+        ]);
+        assert_eq!(path.to_string(), "PlayerStateRecord.ComponentRecords[3].Data.Key.*[Class=/Script/Test.Foo].*");
+
+        assert!(PropertyPath::parse("").is_err());
+        assert!(PropertyPath::parse("Foo[1").is_err());
+        assert!(PropertyPath::parse("Foo..Bar").is_err());
+        assert_eq!(PropertyPath::parse("Foo[1]Bar").unwrap_err().to_string(), "Invalid path Foo[1]Bar: expected '.' or '[' after ']'");
+
+        // keys containing path syntax are bracketed and escaped so they parse back to the same key
+        for (key, expected) in [
+            ("a.b", "Map[a.b]"),
+            ("a[b", "Map[a[b]"),
+            ("a]b", "Map[a\\]b]"),
+            ("a=b", "Map[a\\=b]"),
+            ("=b", "Map[\\=b]"),
+            ("*", "Map[\\*]"),
+            ("a\\b", "Map[a\\\\b]"),
+            ("a*", "Map.a*"),
+        ] {
+            let path = PropertyPath { segments: vec![PathSegment::Key(String::from("Map")), PathSegment::Key(String::from(key))] };
+            assert_eq!(path.to_string(), expected);
+            assert_eq!(PropertyPath::parse(&path.to_string()).unwrap(), path);
+        }
+        let filter = PathSegment::Filter { field: String::from("a=]"), value: String::from("b=]") };
+        let path = PropertyPath { segments: vec![PathSegment::Key(String::from("Map")), filter] };
+        assert_eq!(path.to_string(), "Map[a\\=\\]=b=\\]]");
+        assert_eq!(PropertyPath::parse(&path.to_string()).unwrap(), path);
+        assert!(PropertyPath::parse("Map[a\\]").is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let mut properties = vec![
+            Property::new_scalar("ComponentRecords", PropertyValue::ArrayProperty {
+                values: vec![
+                    component("/Script/Test.Other", &[true]),
+                    component("/Script/Test.Inventory", &[false, true]),
+                ],
+            }),
+            Property::new_none(),
+        ];
+
+        let path = PropertyPath::parse("ComponentRecords[Class=/Script/Test.Inventory].Data.KeyItems[*]").unwrap();
+        let matches = path.select(&properties).unwrap();
+        let paths: Vec<_> = matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["ComponentRecords[1].Data.KeyItems[0]", "ComponentRecords[1].Data.KeyItems[1]"]);
+        assert!(matches!(matches[1].target.value(), Some(PropertyValue::BoolProperty(Some(true)))));
+
+        for mut path_match in path.select_mut(&mut properties).unwrap() {
+            *path_match.target.value_mut().unwrap() = PropertyValue::BoolProperty(Some(true));
+        }
+        let all_items = PropertyPath::parse("ComponentRecords[*].Data.KeyItems.*").unwrap();
+        assert_eq!(all_items.select(&properties).unwrap().len(), 3);
+        assert!(all_items.select(&properties).unwrap().iter().all(|m| matches!(m.target.value(), Some(PropertyValue::BoolProperty(Some(true))))));
+
+        let path = PropertyPath::parse("ComponentRecords[Class=/Script/Test.Missing].Data").unwrap();
+        assert_eq!(path.select(&properties).unwrap_err().to_string(), "Nothing in ComponentRecords matches [Class=/Script/Test.Missing]");
+        let path = PropertyPath::parse("Components").unwrap();
+        assert_eq!(path.select(&properties).unwrap_err().to_string(), "No property matches Components");
+    }
+}
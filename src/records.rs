@@ -0,0 +1,856 @@
+//! Typed views of the parts of a save the Simple view edits.
+//!
+//! Each record reads its fields out of a list of properties and writes them back in place, so properties the record
+//! doesn't know about are preserved. A field that's missing or has an unexpected type is reported as a
+//! [`RecordError`] with the path of the field. Larger records are split into [`Section`]s that are read separately, so
+//! one bad field only affects its own section.
+
+use std::fmt::{Display, Formatter};
+
+use crate::game::PLAYER_INVENTORY_COMPONENT_CLASS;
+use crate::save::*;
+use crate::verify::{element_label, join_path};
+
+/// What was wrong with a record field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordErrorKind {
+    /// The field doesn't exist
+    Missing,
+    /// The field exists but has a different type than expected
+    WrongType {
+        expected: &'static str,
+    },
+}
+
+/// An error reading a record from, or writing it to, a list of properties
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError {
+    /// The path of the field, relative to the record
+    pub path: String,
+    pub kind: RecordErrorKind,
+}
+
+impl RecordError {
+    fn missing(path: &str) -> Self {
+        Self { path: path.to_string(), kind: RecordErrorKind::Missing }
+    }
+
+    fn wrong_type(path: &str, expected: &'static str) -> Self {
+        Self { path: path.to_string(), kind: RecordErrorKind::WrongType { expected } }
+    }
+
+    /// Adds a parent name or index to the front of the path
+    pub fn prefixed(mut self, parent: &str) -> Self {
+        self.path = join_path(parent, &self.path);
+        self
+    }
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            RecordErrorKind::Missing => write!(f, "{} is missing", self.path),
+            RecordErrorKind::WrongType { expected } => write!(f, "{} has the wrong type, expected {expected}", self.path),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+/// A part of a record that's read separately from the rest, holding the error if it couldn't be read
+pub type Section<T> = Result<T, RecordError>;
+
+fn prefix_section<T>(section: &mut Section<T>, parent: &str) {
+    if let Err(e) = section {
+        *e = e.clone().prefixed(parent);
+    }
+}
+
+/// A scalar type that record fields can have
+trait FieldValue: Sized + Copy {
+    const TYPE_NAME: &'static str;
+
+    fn from_value(value: &PropertyValue) -> Option<Self>;
+
+    fn to_value(self) -> PropertyValue;
+}
+
+impl FieldValue for i32 {
+    const TYPE_NAME: &'static str = "IntProperty";
+
+    fn from_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::IntProperty(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn to_value(self) -> PropertyValue {
+        PropertyValue::IntProperty(self)
+    }
+}
+
+impl FieldValue for f32 {
+    const TYPE_NAME: &'static str = "FloatProperty";
+
+    fn from_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::FloatProperty(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    fn to_value(self) -> PropertyValue {
+        PropertyValue::FloatProperty(self)
+    }
+}
+
+impl FieldValue for bool {
+    const TYPE_NAME: &'static str = "BoolProperty";
+
+    fn from_value(value: &PropertyValue) -> Option<Self> {
+        match value {
+            PropertyValue::BoolProperty(Some(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn to_value(self) -> PropertyValue {
+        PropertyValue::BoolProperty(Some(self))
+    }
+}
+
+fn find_value<'a>(properties: &'a [Property], name: &str) -> Option<&'a PropertyValue> {
+    properties.iter().find(|p| p.name == name).and_then(|p| p.body.as_ref()).map(|b| &b.value)
+}
+
+fn find_value_mut<'a>(properties: &'a mut [Property], name: &str) -> Option<&'a mut PropertyValue> {
+    properties.iter_mut().find(|p| p.name == name).and_then(|p| p.body.as_mut()).map(|b| &mut b.value)
+}
+
+fn struct_properties<'a>(value: &'a PropertyValue, path: &str) -> Result<&'a Vec<Property>, RecordError> {
+    match value {
+        PropertyValue::StructProperty(properties) => Ok(properties),
+        PropertyValue::CustomStructProperty(custom_struct) => Ok(&custom_struct.properties),
+        _ => Err(RecordError::wrong_type(path, "StructProperty")),
+    }
+}
+
+fn struct_properties_mut<'a>(value: &'a mut PropertyValue, path: &str) -> Result<&'a mut Vec<Property>, RecordError> {
+    match value {
+        PropertyValue::StructProperty(properties) => Ok(properties),
+        PropertyValue::CustomStructProperty(custom_struct) => Ok(&mut custom_struct.properties),
+        _ => Err(RecordError::wrong_type(path, "StructProperty")),
+    }
+}
+
+fn get_field<T: FieldValue>(properties: &[Property], name: &str) -> Result<T, RecordError> {
+    let value = find_value(properties, name).ok_or_else(|| RecordError::missing(name))?;
+    T::from_value(value).ok_or_else(|| RecordError::wrong_type(name, T::TYPE_NAME))
+}
+
+fn get_optional_field<T: FieldValue>(properties: &[Property], name: &str) -> Result<Option<T>, RecordError> {
+    match find_value(properties, name) {
+        Some(value) => T::from_value(value).map(Some).ok_or_else(|| RecordError::wrong_type(name, T::TYPE_NAME)),
+        None => Ok(None),
+    }
+}
+
+/// Sets the value of a field, adding it before the terminating `None` property if it doesn't exist
+fn set_field<T: FieldValue>(properties: &mut Vec<Property>, name: &str, value: T) {
+    if let Some(existing) = find_value_mut(properties, name) {
+        *existing = value.to_value();
+        return;
+    }
+
+    let property = Property::new_scalar(name, value.to_value());
+    match properties.last() {
+        Some(last) if last.is_none() => properties.insert(properties.len() - 1, property),
+        _ => properties.push(property),
+    }
+}
+
+fn get_array<'a>(properties: &'a [Property], name: &str) -> Result<&'a [PropertyValue], RecordError> {
+    match find_value(properties, name) {
+        Some(PropertyValue::ArrayProperty { values }) => Ok(values),
+        Some(_) => Err(RecordError::wrong_type(name, "ArrayProperty")),
+        None => Err(RecordError::missing(name)),
+    }
+}
+
+/// Gets an array field for writing
+///
+/// Arrays can't be created because the type of their elements isn't known, so this fails if the array is missing.
+fn get_array_mut<'a>(properties: &'a mut [Property], name: &str) -> Result<&'a mut Vec<PropertyValue>, RecordError> {
+    match find_value_mut(properties, name) {
+        Some(PropertyValue::ArrayProperty { values }) => Ok(values),
+        Some(_) => Err(RecordError::wrong_type(name, "ArrayProperty")),
+        None => Err(RecordError::missing(name)),
+    }
+}
+
+fn get_scalar_array<T: FieldValue>(properties: &[Property], name: &str) -> Result<Vec<T>, RecordError> {
+    get_array(properties, name)?
+        .iter()
+        .enumerate()
+        .map(|(i, value)| T::from_value(value).ok_or_else(|| RecordError::wrong_type(&join_path(name, &element_label(i, None)), T::TYPE_NAME)))
+        .collect()
+}
+
+fn set_scalar_array<T: FieldValue>(properties: &mut [Property], name: &str, values: &[T]) -> Result<(), RecordError> {
+    *get_array_mut(properties, name)? = values.iter().map(|value| value.to_value()).collect();
+    Ok(())
+}
+
+fn get_record_array<R: ElementRecord>(properties: &[Property], name: &str) -> Result<Vec<R>, RecordError> {
+    get_array(properties, name)?
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let path = join_path(name, &element_label(i, None));
+            let mut record = R::from_properties(struct_properties(value, &path)?).map_err(|e| e.prefixed(&path))?;
+            record.set_source(i);
+            Ok(record)
+        })
+        .collect()
+}
+
+/// Writes a list of records to an array of structs
+///
+/// Each record updates the element it was read from, so any properties the record doesn't know about are kept, even
+/// if records were removed or reordered. Elements with no record are removed, and records with no element get a new
+/// one. The array is left unchanged if any record can't be written.
+fn update_record_array<R: ElementRecord>(properties: &mut [Property], name: &str, records: &[R]) -> Result<(), RecordError> {
+    let values = get_array_mut(properties, name)?;
+    let mut old_values: Vec<_> = values.iter().cloned().map(Some).collect();
+    let mut new_values = Vec::with_capacity(records.len());
+    for (i, record) in records.iter().enumerate() {
+        let path = join_path(name, &element_label(i, None));
+        let value = match record.source().and_then(|source| old_values.get_mut(source)).and_then(Option::take) {
+            Some(mut value) => record.update_properties(struct_properties_mut(&mut value, &path)?).map(|_| value),
+            None => record.to_properties().map(PropertyValue::StructProperty),
+        }.map_err(|e| e.prefixed(&path))?;
+        new_values.push(value);
+    }
+    *values = new_values;
+    Ok(())
+}
+
+/// A typed view of a list of properties
+pub trait Record: Sized {
+    /// Reads the record from a list of properties
+    fn from_properties(properties: &[Property]) -> Result<Self, RecordError>;
+
+    /// Writes the record's fields to a list of properties, leaving any other properties unchanged
+    ///
+    /// Missing scalar fields are added. Missing arrays are an error, since the type of their elements isn't known.
+    fn update_properties(&self, properties: &mut Vec<Property>) -> Result<(), RecordError>;
+
+    /// Creates a new list of properties containing the record's fields
+    fn to_properties(&self) -> Result<Vec<Property>, RecordError> {
+        let mut properties = vec![Property::new_none()];
+        self.update_properties(&mut properties)?;
+        Ok(properties)
+    }
+
+    /// Adds a parent path to the errors of any [`Section`]s that couldn't be read
+    fn prefix_section_errors(&mut self, _parent: &str) {}
+}
+
+/// A record stored as an element of an array of structs
+///
+/// The record remembers which element it was read from so it can be written back to the same element.
+trait ElementRecord: Record {
+    /// The index of the element the record was read from, or `None` if it's new
+    fn source(&self) -> Option<usize>;
+
+    fn set_source(&mut self, index: usize);
+}
+
+/// A record found at a fixed location in a save
+pub trait SaveRecord: Record {
+    /// The path of the record in the save, which errors in its fields are reported relative to
+    const PATH: &'static str;
+
+    /// Finds the properties of this record in a save
+    fn properties(save: &SaveGame) -> Result<&Vec<Property>, RecordError>;
+
+    /// Finds the properties of this record in a save for writing
+    fn properties_mut(save: &mut SaveGame) -> Result<&mut Vec<Property>, RecordError>;
+
+    /// Reads the record from a save
+    fn from_save(save: &SaveGame) -> Result<Self, RecordError> {
+        let mut record = Self::from_properties(Self::properties(save)?).map_err(|e| e.prefixed(Self::PATH))?;
+        record.prefix_section_errors(Self::PATH);
+        Ok(record)
+    }
+
+    /// Writes the record to a save
+    fn write_to_save(&self, save: &mut SaveGame) -> Result<(), RecordError> {
+        self.update_properties(Self::properties_mut(save)?).map_err(|e| e.prefixed(Self::PATH))
+    }
+}
+
+/// A weapon in the player's inventory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeaponSlot {
+    pub id_index: i32,
+    pub durability: f32,
+    /// The index in the weapon array this slot was read from, or `None` for a new slot
+    pub source: Option<usize>,
+}
+
+impl WeaponSlot {
+    /// Creates a new weapon slot that isn't in the save yet
+    pub const fn new(id_index: i32, durability: f32) -> Self {
+        Self { id_index, durability, source: None }
+    }
+}
+
+impl Record for WeaponSlot {
+    fn from_properties(properties: &[Property]) -> Result<Self, RecordError> {
+        Ok(Self::new(get_field(properties, "IDIndex")?, get_field(properties, "Durability")?))
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>) -> Result<(), RecordError> {
+        set_field(properties, "Durability", self.durability);
+        set_field(properties, "IDIndex", self.id_index);
+        Ok(())
+    }
+}
+
+impl ElementRecord for WeaponSlot {
+    fn source(&self) -> Option<usize> {
+        self.source
+    }
+
+    fn set_source(&mut self, index: usize) {
+        self.source = Some(index);
+    }
+}
+
+/// A stack of consumable items in the player's inventory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsumableSlot {
+    pub id_index: i32,
+    pub quantity: i32,
+    /// The index in the consumable array this slot was read from, or `None` for a new slot
+    pub source: Option<usize>,
+}
+
+impl ConsumableSlot {
+    /// Creates a new consumable slot that isn't in the save yet
+    pub const fn new(id_index: i32, quantity: i32) -> Self {
+        Self { id_index, quantity, source: None }
+    }
+}
+
+impl Record for ConsumableSlot {
+    fn from_properties(properties: &[Property]) -> Result<Self, RecordError> {
+        Ok(Self::new(get_field(properties, "IDIndex")?, get_field(properties, "Quantity")?))
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>) -> Result<(), RecordError> {
+        set_field(properties, "Quantity", self.quantity);
+        set_field(properties, "IDIndex", self.id_index);
+        Ok(())
+    }
+}
+
+impl ElementRecord for ConsumableSlot {
+    fn source(&self) -> Option<usize> {
+        self.source
+    }
+
+    fn set_source(&mut self, index: usize) {
+        self.source = Some(index);
+    }
+}
+
+/// An omamori the player has obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OmamoriSlot {
+    pub id_index: i32,
+    /// The index in the omamori array this slot was read from, or `None` for a new slot
+    pub source: Option<usize>,
+}
+
+impl OmamoriSlot {
+    /// Creates a new omamori slot that isn't in the save yet
+    pub const fn new(id_index: i32) -> Self {
+        Self { id_index, source: None }
+    }
+}
+
+impl Record for OmamoriSlot {
+    fn from_properties(properties: &[Property]) -> Result<Self, RecordError> {
+        Ok(Self::new(get_field(properties, "IDIndex")?))
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>) -> Result<(), RecordError> {
+        set_field(properties, "IDIndex", self.id_index);
+        Ok(())
+    }
+}
+
+impl ElementRecord for OmamoriSlot {
+    fn source(&self) -> Option<usize> {
+        self.source
+    }
+
+    fn set_source(&mut self, index: usize) {
+        self.source = Some(index);
+    }
+}
+
+/// The weapons the player has in one of the two worlds
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaponInventory {
+    pub weapons: Vec<WeaponSlot>,
+    /// The index of the equipped weapon in `weapons`, or -1 if none is equipped
+    pub equipped_index: i32,
+    /// The index of the weapon the player will switch to, which is normally the same as `equipped_index`
+    pub target_index: i32,
+}
+
+impl WeaponInventory {
+    /// Reads the weapons for the given world, `Fog` or `Dark`
+    fn from_properties(properties: &[Property], world: &str) -> Result<Self, RecordError> {
+        Ok(Self {
+            weapons: get_record_array(properties, &format!("{world}Weapons"))?,
+            equipped_index: get_field(properties, &format!("{world}EquippedWeaponIndex"))?,
+            target_index: get_field(properties, &format!("{world}TargetWeaponIndex"))?,
+        })
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>, world: &str) -> Result<(), RecordError> {
+        update_record_array(properties, &format!("{world}Weapons"), &self.weapons)?;
+        set_field(properties, &format!("{world}EquippedWeaponIndex"), self.equipped_index);
+        set_field(properties, &format!("{world}TargetWeaponIndex"), self.target_index);
+        Ok(())
+    }
+}
+
+/// The player's inventory, stored in the data of the player's inventory component
+///
+/// Each part of the inventory is a separate [`Section`]. Sections that couldn't be read are left unchanged when the
+/// record is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryRecord {
+    pub fog_weapons: Section<WeaponInventory>,
+    pub dark_weapons: Section<WeaponInventory>,
+    pub consumables: Section<Vec<ConsumableSlot>>,
+    pub omamories: Section<Vec<OmamoriSlot>>,
+    /// The ID indexes of the equipped omamori, with -1 for empty slots
+    pub equipped_omamories: Section<Vec<i32>>,
+    /// Whether the player has each key item, in the order of [`KEY_ITEM_NAMES`](crate::game::KEY_ITEM_NAMES)
+    pub key_items: Section<Vec<bool>>,
+    /// Whether the player has each letter, in the order of [`LETTER_NAMES`](crate::game::LETTER_NAMES)
+    pub letters: Section<Vec<bool>>,
+}
+
+impl Record for InventoryRecord {
+    fn from_properties(properties: &[Property]) -> Result<Self, RecordError> {
+        Ok(Self {
+            fog_weapons: WeaponInventory::from_properties(properties, "Fog"),
+            dark_weapons: WeaponInventory::from_properties(properties, "Dark"),
+            consumables: get_record_array(properties, "Consumables"),
+            omamories: get_record_array(properties, "Omamories"),
+            equipped_omamories: get_scalar_array(properties, "EquippedOmamories"),
+            key_items: get_scalar_array(properties, "KeyItems"),
+            letters: get_scalar_array(properties, "Letters"),
+        })
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>) -> Result<(), RecordError> {
+        if let Ok(fog_weapons) = &self.fog_weapons {
+            fog_weapons.update_properties(properties, "Fog")?;
+        }
+        if let Ok(dark_weapons) = &self.dark_weapons {
+            dark_weapons.update_properties(properties, "Dark")?;
+        }
+        if let Ok(consumables) = &self.consumables {
+            update_record_array(properties, "Consumables", consumables)?;
+        }
+        if let Ok(omamories) = &self.omamories {
+            update_record_array(properties, "Omamories", omamories)?;
+        }
+        if let Ok(equipped_omamories) = &self.equipped_omamories {
+            set_scalar_array(properties, "EquippedOmamories", equipped_omamories)?;
+        }
+        if let Ok(key_items) = &self.key_items {
+            set_scalar_array(properties, "KeyItems", key_items)?;
+        }
+        if let Ok(letters) = &self.letters {
+            set_scalar_array(properties, "Letters", letters)?;
+        }
+        Ok(())
+    }
+
+    fn prefix_section_errors(&mut self, parent: &str) {
+        prefix_section(&mut self.fog_weapons, parent);
+        prefix_section(&mut self.dark_weapons, parent);
+        prefix_section(&mut self.consumables, parent);
+        prefix_section(&mut self.omamories, parent);
+        prefix_section(&mut self.equipped_omamories, parent);
+        prefix_section(&mut self.key_items, parent);
+        prefix_section(&mut self.letters, parent);
+    }
+}
+
+/// Finds the component records of the player state
+fn component_records(save: &SaveGame) -> Result<&[PropertyValue], RecordError> {
+    let player_state = struct_properties(player_state_record(save)?, "PlayerStateRecord")?;
+    get_array(player_state, "ComponentRecords").map_err(|e| e.prefixed("PlayerStateRecord"))
+}
+
+fn component_records_mut(save: &mut SaveGame) -> Result<&mut Vec<PropertyValue>, RecordError> {
+    let player_state = struct_properties_mut(player_state_record_mut(save)?, "PlayerStateRecord")?;
+    get_array_mut(player_state, "ComponentRecords").map_err(|e| e.prefixed("PlayerStateRecord"))
+}
+
+const INVENTORY_PATH: &str = "PlayerStateRecord.ComponentRecords.Data";
+
+impl SaveRecord for InventoryRecord {
+    const PATH: &'static str = INVENTORY_PATH;
+
+    fn properties(save: &SaveGame) -> Result<&Vec<Property>, RecordError> {
+        let inventory = component_records(save)?
+            .iter()
+            .find(|record| record.get_key("Class").is_some_and(|class| class == PLAYER_INVENTORY_COMPONENT_CLASS))
+            .and_then(|record| record.get_key("Data"))
+            .ok_or_else(|| RecordError::missing(INVENTORY_PATH))?;
+        struct_properties(inventory, INVENTORY_PATH)
+    }
+
+    fn properties_mut(save: &mut SaveGame) -> Result<&mut Vec<Property>, RecordError> {
+        let inventory = component_records_mut(save)?
+            .iter_mut()
+            .find(|record| record.get_key("Class").is_some_and(|class| class == PLAYER_INVENTORY_COMPONENT_CLASS))
+            .and_then(|record| record.get_key_mut("Data"))
+            .ok_or_else(|| RecordError::missing(INVENTORY_PATH))?;
+        struct_properties_mut(inventory, INVENTORY_PATH)
+    }
+}
+
+/// One of the player's upgradable stats
+///
+/// The buy level isn't in every save, and is only written if it was read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayerStat {
+    /// The current value as a ratio of the maximum
+    pub ratio: f32,
+    pub max_level: i32,
+    pub buy_level: Option<i32>,
+}
+
+impl PlayerStat {
+    /// Reads the stat with the given name, e.g. `Health`
+    fn from_properties(properties: &[Property], stat: &str) -> Result<Self, RecordError> {
+        Ok(Self {
+            ratio: get_field(properties, &format!("{stat}Ratio"))?,
+            max_level: get_field(properties, &format!("Max{stat}Level"))?,
+            buy_level: get_optional_field(properties, &format!("Buy{stat}Level"))?,
+        })
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>, stat: &str) {
+        set_field(properties, &format!("{stat}Ratio"), self.ratio);
+        set_field(properties, &format!("Max{stat}Level"), self.max_level);
+        if let Some(buy_level) = self.buy_level {
+            set_field(properties, &format!("Buy{stat}Level"), buy_level);
+        }
+    }
+}
+
+/// The player's stats, stored in the data of the player state record
+///
+/// Each stat is a separate [`Section`]. Sections that couldn't be read are left unchanged when the record is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerStats {
+    pub health: Section<PlayerStat>,
+    pub stamina: Section<PlayerStat>,
+    pub sanity: Section<PlayerStat>,
+    pub current_max_sanity_ratio: Section<f32>,
+    pub faith_value: Section<i32>,
+}
+
+impl Record for PlayerStats {
+    fn from_properties(properties: &[Property]) -> Result<Self, RecordError> {
+        Ok(Self {
+            health: PlayerStat::from_properties(properties, "Health"),
+            stamina: PlayerStat::from_properties(properties, "Stamina"),
+            sanity: PlayerStat::from_properties(properties, "Sanity"),
+            current_max_sanity_ratio: get_field(properties, "CurrentMaxSanityRatio"),
+            faith_value: get_field(properties, "FaithValue"),
+        })
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>) -> Result<(), RecordError> {
+        for (stat, section) in [("Health", &self.health), ("Stamina", &self.stamina), ("Sanity", &self.sanity)] {
+            if let Ok(stat_value) = section {
+                stat_value.update_properties(properties, stat);
+            }
+        }
+        if let Ok(current_max_sanity_ratio) = self.current_max_sanity_ratio {
+            set_field(properties, "CurrentMaxSanityRatio", current_max_sanity_ratio);
+        }
+        if let Ok(faith_value) = self.faith_value {
+            set_field(properties, "FaithValue", faith_value);
+        }
+        Ok(())
+    }
+
+    fn prefix_section_errors(&mut self, parent: &str) {
+        prefix_section(&mut self.health, parent);
+        prefix_section(&mut self.stamina, parent);
+        prefix_section(&mut self.sanity, parent);
+        prefix_section(&mut self.current_max_sanity_ratio, parent);
+        prefix_section(&mut self.faith_value, parent);
+    }
+}
+
+fn player_state_record(save: &SaveGame) -> Result<&PropertyValue, RecordError> {
+    save.save_data.get_key("PlayerStateRecord").ok_or_else(|| RecordError::missing("PlayerStateRecord"))
+}
+
+fn player_state_record_mut(save: &mut SaveGame) -> Result<&mut PropertyValue, RecordError> {
+    save.save_data.get_key_mut("PlayerStateRecord").ok_or_else(|| RecordError::missing("PlayerStateRecord"))
+}
+
+const PLAYER_STATS_PATH: &str = "PlayerStateRecord.Data";
+
+impl SaveRecord for PlayerStats {
+    const PATH: &'static str = PLAYER_STATS_PATH;
+
+    fn properties(save: &SaveGame) -> Result<&Vec<Property>, RecordError> {
+        let data = player_state_record(save)?.get_key("Data").ok_or_else(|| RecordError::missing(PLAYER_STATS_PATH))?;
+        struct_properties(data, PLAYER_STATS_PATH)
+    }
+
+    fn properties_mut(save: &mut SaveGame) -> Result<&mut Vec<Property>, RecordError> {
+        let data = player_state_record_mut(save)?.get_key_mut("Data").ok_or_else(|| RecordError::missing(PLAYER_STATS_PATH))?;
+        struct_properties_mut(data, PLAYER_STATS_PATH)
+    }
+}
+
+/// The state of Hinako herself, as opposed to the player state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HinakoRecord {
+    /// The current amount of health, as opposed to the ratio in [`PlayerStats::health`]
+    pub health: f32,
+}
+
+impl Record for HinakoRecord {
+    fn from_properties(properties: &[Property]) -> Result<Self, RecordError> {
+        Ok(Self { health: get_field(properties, "Health")? })
+    }
+
+    fn update_properties(&self, properties: &mut Vec<Property>) -> Result<(), RecordError> {
+        set_field(properties, "Health", self.health);
+        Ok(())
+    }
+}
+
+const HINAKO_RECORD_PATH: &str = "HinakoRecord";
+
+impl SaveRecord for HinakoRecord {
+    const PATH: &'static str = HINAKO_RECORD_PATH;
+
+    fn properties(save: &SaveGame) -> Result<&Vec<Property>, RecordError> {
+        let record = save.save_data.get_key(HINAKO_RECORD_PATH).ok_or_else(|| RecordError::missing(HINAKO_RECORD_PATH))?;
+        struct_properties(record, HINAKO_RECORD_PATH)
+    }
+
+    fn properties_mut(save: &mut SaveGame) -> Result<&mut Vec<Property>, RecordError> {
+        let record = save.save_data.get_key_mut(HINAKO_RECORD_PATH).ok_or_else(|| RecordError::missing(HINAKO_RECORD_PATH))?;
+        struct_properties_mut(record, HINAKO_RECORD_PATH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weapon(id_index: i32, durability: f32) -> PropertyValue {
+        PropertyValue::StructProperty(vec![
+            Property::new_scalar("Durability", PropertyValue::FloatProperty(durability)),
+            Property::new_scalar("IDIndex", PropertyValue::IntProperty(id_index)),
+            Property::new_scalar("Unknown", PropertyValue::IntProperty(7)),
+            Property::new_none(),
+        ])
+    }
+
+    fn component_record(class: &str, data: Vec<Property>) -> PropertyValue {
+        SaveGame::component_record_for_test(class, PropertyBody::new_scalar(PropertyValue::StructProperty(data)))
+    }
+
+    fn bool_array(name: &str, values: &[bool]) -> Property {
+        Property::new_scalar(name, PropertyValue::ArrayProperty {
+            values: values.iter().map(|b| PropertyValue::BoolProperty(Some(*b))).collect(),
+        })
+    }
+
+    #[test]
+    fn test_player_stats() {
+        let mut save = SaveGame::new_for_test(vec![
+            Property::new_scalar("PlayerStateRecord", PropertyValue::StructProperty(vec![
+                Property::new_scalar("Data", PropertyValue::StructProperty(vec![
+                    Property::new_scalar("HealthRatio", PropertyValue::FloatProperty(1.0)),
+                    Property::new_scalar("MaxHealthLevel", PropertyValue::IntProperty(2)),
+                    Property::new_scalar("BuyHealthLevel", PropertyValue::IntProperty(2)),
+                    Property::new_scalar("StaminaRatio", PropertyValue::FloatProperty(0.5)),
+                    Property::new_scalar("MaxStaminaLevel", PropertyValue::IntProperty(1)),
+                    Property::new_scalar("SanityRatio", PropertyValue::IntProperty(1)),
+                    Property::new_scalar("MaxSanityLevel", PropertyValue::IntProperty(0)),
+                    Property::new_scalar("CurrentMaxSanityRatio", PropertyValue::FloatProperty(1.0)),
+                    Property::new_none(),
+                ])),
+                Property::new_none(),
+            ])),
+            Property::new_none(),
+        ]);
+
+        // sections that can't be read don't prevent the others from being edited
+        let mut stats = PlayerStats::from_save(&save).unwrap();
+        assert_eq!(stats.health, Ok(PlayerStat { ratio: 1.0, max_level: 2, buy_level: Some(2) }));
+        assert_eq!(stats.stamina, Ok(PlayerStat { ratio: 0.5, max_level: 1, buy_level: None }));
+        assert_eq!(stats.sanity.as_ref().unwrap_err().to_string(), "PlayerStateRecord.Data.SanityRatio has the wrong type, expected FloatProperty");
+        assert_eq!(stats.faith_value.as_ref().unwrap_err().to_string(), "PlayerStateRecord.Data.FaithValue is missing");
+
+        stats.stamina.as_mut().unwrap().max_level = 3;
+        stats.write_to_save(&mut save).unwrap();
+        let data = PlayerStats::properties(&save).unwrap();
+        assert!(matches!(find_value(data, "MaxStaminaLevel"), Some(PropertyValue::IntProperty(3))));
+        // optional fields that weren't read stay absent, and sections that weren't read are left alone
+        assert!(find_value(data, "BuyStaminaLevel").is_none());
+        assert!(find_value(data, "FaithValue").is_none());
+        assert!(matches!(find_value(data, "SanityRatio"), Some(PropertyValue::IntProperty(1))));
+        assert_eq!(PlayerStats::from_save(&save).unwrap(), stats);
+
+        assert_eq!(HinakoRecord::from_save(&save).unwrap_err().to_string(), "HinakoRecord is missing");
+    }
+
+    #[test]
+    fn test_inventory_record() {
+        let other_component = component_record("/Script/GameNoce.NoceOtherComponent", vec![
+            bool_array("KeyItems", &[false]),
+            Property::new_none(),
+        ]);
+        let inventory_component = component_record(PLAYER_INVENTORY_COMPONENT_CLASS, vec![
+            Property::new_scalar("FogWeapons", PropertyValue::ArrayProperty { values: vec![weapon(1, 500.0)] }),
+            Property::new_scalar("FogEquippedWeaponIndex", PropertyValue::IntProperty(0)),
+            Property::new_scalar("FogTargetWeaponIndex", PropertyValue::IntProperty(0)),
+            bool_array("KeyItems", &[true, false]),
+            Property::new_none(),
+        ]);
+        let mut save = SaveGame::new_for_test(vec![
+            Property::new_scalar("PlayerStateRecord", PropertyValue::StructProperty(vec![
+                Property::new_scalar("ComponentRecords", PropertyValue::ArrayProperty {
+                    values: vec![other_component, inventory_component],
+                }),
+                Property::new_none(),
+            ])),
+            Property::new_none(),
+        ]);
+
+        // the inventory is found by the class of its component record
+        let mut inventory = InventoryRecord::from_save(&save).unwrap();
+        assert_eq!(inventory.key_items, Ok(vec![true, false]));
+        assert_eq!(inventory.fog_weapons.as_ref().unwrap().weapons, [WeaponSlot { id_index: 1, durability: 500.0, source: Some(0) }]);
+        assert_eq!(inventory.letters.as_ref().unwrap_err().to_string(), "PlayerStateRecord.ComponentRecords.Data.Letters is missing");
+        assert!(inventory.dark_weapons.is_err());
+
+        inventory.key_items.as_mut().unwrap()[1] = true;
+        inventory.write_to_save(&mut save).unwrap();
+        assert_eq!(InventoryRecord::from_save(&save).unwrap(), inventory);
+        let Some(PropertyValue::ArrayProperty { values }) = save.save_data.get_key("PlayerStateRecord").and_then(|r| r.get_key("ComponentRecords")) else {
+            panic!("ComponentRecords should be an array");
+        };
+        assert!(matches!(values[0].get_key("Data").and_then(|d| d.get_key("KeyItems")).and_then(|k| k.get_index(0)), Some(PropertyValue::BoolProperty(Some(false)))));
+
+        let mut save = SaveGame::new_for_test(vec![Property::new_none()]);
+        assert_eq!(InventoryRecord::from_save(&save).unwrap_err().to_string(), "PlayerStateRecord is missing");
+        assert_eq!(inventory.write_to_save(&mut save).unwrap_err().to_string(), "PlayerStateRecord is missing");
+    }
+
+    #[test]
+    fn test_weapon_inventory() {
+        let mut properties = vec![
+            Property::new_scalar("FogWeapons", PropertyValue::ArrayProperty { values: vec![weapon(1, 500.0), weapon(2, 250.0)] }),
+            Property::new_scalar("FogEquippedWeaponIndex", PropertyValue::IntProperty(0)),
+            Property::new_none(),
+        ];
+
+        assert_eq!(
+            WeaponInventory::from_properties(&properties, "Fog").unwrap_err().to_string(),
+            "FogTargetWeaponIndex is missing",
+        );
+        assert_eq!(
+            WeaponInventory::from_properties(&properties, "Dark").unwrap_err().to_string(),
+            "DarkWeapons is missing",
+        );
+
+        set_field(&mut properties, "FogTargetWeaponIndex", 0);
+        assert!(properties.last().unwrap().is_none());
+        let mut weapons = WeaponInventory::from_properties(&properties, "Fog").unwrap();
+        assert_eq!(weapons.weapons, [
+            WeaponSlot { id_index: 1, durability: 500.0, source: Some(0) },
+            WeaponSlot { id_index: 2, durability: 250.0, source: Some(1) },
+        ]);
+
+        weapons.weapons[1].durability = 100.0;
+        weapons.weapons.push(WeaponSlot::new(3, 0.0));
+        weapons.equipped_index = 2;
+        weapons.update_properties(&mut properties, "Fog").unwrap();
+        // the new weapon is read back from the element that was added for it
+        weapons.weapons[2].source = Some(2);
+        assert_eq!(WeaponInventory::from_properties(&properties, "Fog").unwrap(), weapons);
+
+        // properties the record doesn't know about are kept
+        let Some(PropertyValue::ArrayProperty { values }) = find_value(&properties, "FogWeapons") else {
+            panic!("FogWeapons should be an array");
+        };
+        assert!(matches!(values[1].get_key("Unknown"), Some(PropertyValue::IntProperty(7))));
+        assert!(matches!(values[1].get_key("Durability"), Some(PropertyValue::FloatProperty(100.0))));
+        assert!(matches!(values[2].get_key("IDIndex"), Some(PropertyValue::IntProperty(3))));
+
+        let Some(PropertyValue::ArrayProperty { values }) = find_value_mut(&mut properties, "FogWeapons") else {
+            panic!("FogWeapons should be an array");
+        };
+        values[0] = PropertyValue::StructProperty(vec![Property::new_scalar("IDIndex", PropertyValue::FloatProperty(1.0))]);
+        assert_eq!(
+            WeaponInventory::from_properties(&properties, "Fog").unwrap_err().to_string(),
+            "FogWeapons[0].IDIndex has the wrong type, expected IntProperty",
+        );
+    }
+
+    #[test]
+    fn test_remove_weapon() {
+        let mut values: Vec<_> = (0..3).map(|i| weapon(i, 100.0 * i as f32)).collect();
+        for (i, value) in values.iter_mut().enumerate() {
+            *value.get_key_mut("Unknown").unwrap() = PropertyValue::IntProperty(10 + i as i32);
+        }
+        let mut properties = vec![
+            Property::new_scalar("FogWeapons", PropertyValue::ArrayProperty { values }),
+            Property::new_scalar("FogEquippedWeaponIndex", PropertyValue::IntProperty(0)),
+            Property::new_scalar("FogTargetWeaponIndex", PropertyValue::IntProperty(0)),
+            Property::new_none(),
+        ];
+
+        let mut weapons = WeaponInventory::from_properties(&properties, "Fog").unwrap();
+        weapons.weapons.remove(1);
+        weapons.weapons[1].durability = 50.0;
+        weapons.update_properties(&mut properties, "Fog").unwrap();
+
+        // the removed weapon's element is removed along with it, and the others keep their unknown properties
+        let Some(PropertyValue::ArrayProperty { values }) = find_value(&properties, "FogWeapons") else {
+            panic!("FogWeapons should be an array");
+        };
+        assert_eq!(values.len(), 2);
+        assert!(matches!(values[0].get_key("Unknown"), Some(PropertyValue::IntProperty(10))));
+        assert!(matches!(values[1].get_key("IDIndex"), Some(PropertyValue::IntProperty(2))));
+        assert!(matches!(values[1].get_key("Durability"), Some(PropertyValue::FloatProperty(50.0))));
+        assert!(matches!(values[1].get_key("Unknown"), Some(PropertyValue::IntProperty(12))));
+    }
+}
@@ -0,0 +1,205 @@
+//! Registry of struct types the parser knows how to decode.
+//!
+//! Struct properties whose fields aren't described in the save, like `/Script/CoreUObject.Vector`, can only be decoded
+//! if the type is registered here. Likewise, game objects whose state is stored as a custom struct in a byte array are
+//! recognized by their class. The registry starts out with the engine and Silent Hill f types, and more can be added
+//! at startup with [`registry_mut`], before any saves are read. The built-in custom struct classes are defined in
+//! `data/custom_struct_classes.json`.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::path::Path;
+use std::sync::{LazyLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use anyhow::{anyhow, Context, Result};
+use binrw::{BinRead, BinResult, Endian};
+use serde_json::Value;
+
+use crate::save::Guid;
+use crate::uobject::*;
+
+/// Name of the file custom struct classes are loaded from
+///
+/// The editor reads this file from its own folder, if it exists, to add classes or override the built-in ones.
+pub const CUSTOM_STRUCT_CLASSES_FILE_NAME: &str = "custom_struct_classes.json";
+
+/// Classes of game objects whose state is stored as a custom struct, mapped to the size of the footer after the
+/// struct's properties
+const BUILTIN_CUSTOM_STRUCT_CLASSES: &str = include_str!("../data/custom_struct_classes.json");
+
+/// A reader that can be used by a [`UObjectReader`]
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+/// Reads a CoreUObject struct from a reader
+pub type UObjectReader = fn(&mut dyn ReadSeek, Endian) -> BinResult<Box<dyn CoreUObject>>;
+
+/// Creates a default value of a CoreUObject struct
+pub type UObjectFactory = fn() -> Box<dyn CoreUObject>;
+
+/// How to decode and create a CoreUObject struct type
+///
+/// Objects are encoded by their own [`CoreUObject::to_bytes`].
+#[derive(Debug, Clone, Copy)]
+pub struct UObjectType {
+    pub read: UObjectReader,
+    pub make_default: UObjectFactory,
+}
+
+fn read_boxed<T>(mut reader: &mut dyn ReadSeek, endian: Endian) -> BinResult<Box<dyn CoreUObject>>
+where
+    T: CoreUObject + for<'a> BinRead<Args<'a> = ()> + 'static,
+{
+    Ok(Box::new(T::read_options(&mut reader, endian, ())?))
+}
+
+fn default_boxed<T: CoreUObject + Default + 'static>() -> Box<dyn CoreUObject> {
+    Box::new(T::default())
+}
+
+/// The struct types known to the parser
+///
+/// The registry only covers decoding and default values. Encoding always goes through the object's own
+/// [`CoreUObject::to_bytes`], so a registered type must implement it to be written back.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    uobjects: HashMap<String, UObjectType>,
+    custom_struct_classes: HashMap<String, usize>,
+}
+
+impl Registry {
+    /// Creates a registry containing the engine and game types supported out of the box
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::default();
+        registry.register_uobject::<FDateTime>("DateTime");
+        registry.register_uobject::<FTimespan>("Timespan");
+        registry.register_uobject::<Vector>("Vector");
+        registry.register_uobject::<Quat>("Quat");
+        registry.register_uobject::<LinearColor>("LinearColor");
+        registry.register_uobject::<Vector2D>("Vector2D");
+        registry.register_uobject::<Vector4>("Vector4");
+        registry.register_uobject::<Rotator>("Rotator");
+        registry.register_uobject::<IntPoint>("IntPoint");
+        registry.register_uobject::<Color>("Color");
+        registry.register_uobject::<BoundingBox>("Box");
+        registry.register_uobject::<Transform>("Transform");
+        registry.register_uobject::<Guid>("Guid");
+
+        registry.load_custom_struct_classes(BUILTIN_CUSTOM_STRUCT_CLASSES).expect("built-in custom struct classes should be valid");
+        registry
+    }
+
+    /// Registers a CoreUObject struct type that's read with its [`BinRead`] implementation
+    ///
+    /// `type_name` is the name of the struct without the `/Script/CoreUObject.` namespace, e.g. `Vector`.
+    pub fn register_uobject<T>(&mut self, type_name: &str)
+    where
+        T: CoreUObject + Default + for<'a> BinRead<Args<'a> = ()> + 'static,
+    {
+        self.register_uobject_type(type_name, UObjectType { read: read_boxed::<T>, make_default: default_boxed::<T> });
+    }
+
+    /// Registers a CoreUObject struct type with custom decoding, replacing any existing registration for the name
+    pub fn register_uobject_type(&mut self, type_name: &str, uobject_type: UObjectType) {
+        self.uobjects.insert(type_name.to_string(), uobject_type);
+    }
+
+    /// Registers a class of game object whose state is stored as a custom struct
+    ///
+    /// `footer_size` is the number of bytes following the struct's properties.
+    pub fn register_custom_struct_class(&mut self, class: &str, footer_size: usize) {
+        self.custom_struct_classes.insert(class.to_string(), footer_size);
+    }
+
+    /// Registers the custom struct classes in a JSON object mapping class names to footer sizes
+    ///
+    /// Classes that are already registered are overridden. If any entry is invalid, nothing is registered. Returns the
+    /// number of classes loaded.
+    pub fn load_custom_struct_classes(&mut self, json: &str) -> Result<usize> {
+        let classes: Value = serde_json::from_str(json)?;
+        let classes = classes.as_object().ok_or_else(|| anyhow!("Expected an object mapping class names to footer sizes"))?;
+        let classes = classes
+            .iter()
+            .map(|(class, footer_size)| {
+                let footer_size = footer_size.as_u64().ok_or_else(|| anyhow!("Invalid footer size {footer_size} for class {class}"))?;
+                Ok((class, footer_size as usize))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for (class, footer_size) in &classes {
+            self.register_custom_struct_class(class, *footer_size);
+        }
+        Ok(classes.len())
+    }
+
+    /// Registers the custom struct classes in a JSON file in the format of [`Self::load_custom_struct_classes`]
+    pub fn load_custom_struct_classes_file(&mut self, path: &Path) -> Result<usize> {
+        let json = std::fs::read_to_string(path)?;
+        self.load_custom_struct_classes(&json).with_context(|| format!("Invalid custom struct classes file {}", path.display()))
+    }
+
+    /// Returns how to decode the CoreUObject struct type with the given name, if it's registered
+    pub fn uobject_type(&self, type_name: &str) -> Option<UObjectType> {
+        self.uobjects.get(type_name).copied()
+    }
+
+    /// Returns the footer size of the custom struct for the given class, if it's registered
+    pub fn custom_struct_footer_size(&self, class: &str) -> Option<usize> {
+        self.custom_struct_classes.get(class).copied()
+    }
+}
+
+static REGISTRY: LazyLock<RwLock<Registry>> = LazyLock::new(|| RwLock::new(Registry::with_builtins()));
+
+/// Returns the global registry used by the parser.
+pub fn registry() -> RwLockReadGuard<'static, Registry> {
+    REGISTRY.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the global registry for adding types.
+///
+/// The guard must be dropped before reading any saves.
+pub fn registry_mut() -> RwLockWriteGuard<'static, Registry> {
+    REGISTRY.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    #[test]
+    fn test_register_uobject() {
+        let mut registry = Registry::with_builtins();
+        assert!(registry.uobject_type("TestVector").is_none());
+        registry.register_uobject::<Vector>("TestVector");
+
+        let uobject_type = registry.uobject_type("TestVector").unwrap();
+        let object = (uobject_type.make_default)();
+        let bytes = object.to_bytes(Endian::Little).unwrap();
+        let read = (uobject_type.read)(&mut Cursor::new(&bytes), Endian::Little).unwrap();
+        assert_eq!(read.size(), 24);
+
+        assert_eq!(registry.custom_struct_footer_size("/Script/Engine.ActorComponent"), Some(8));
+        assert_eq!(registry.custom_struct_footer_size("/Script/Test.TestComponent"), None);
+    }
+
+    #[test]
+    fn test_load_custom_struct_classes() {
+        let mut registry = Registry::with_builtins();
+        assert_eq!(registry.custom_struct_footer_size("/Script/GameNoce.NocePickupsSubsystem"), Some(4));
+
+        let count = registry.load_custom_struct_classes(r#"{"/Script/GameNoce.NocePickupsSubsystem": 8, "/Script/Test.TestComponent": 4}"#).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(registry.custom_struct_footer_size("/Script/GameNoce.NocePickupsSubsystem"), Some(8));
+        assert_eq!(registry.custom_struct_footer_size("/Script/Test.TestComponent"), Some(4));
+
+        // an invalid entry leaves the registry unchanged, even if the entries before it are valid
+        let json = r#"{"/Script/Test.AnotherComponent": 2, "/Script/Test.TestComponent": "big"}"#;
+        assert!(registry.load_custom_struct_classes(json).is_err());
+        assert_eq!(registry.custom_struct_footer_size("/Script/Test.AnotherComponent"), None);
+        assert_eq!(registry.custom_struct_footer_size("/Script/Test.TestComponent"), Some(4));
+        assert!(registry.load_custom_struct_classes("[]").is_err());
+    }
+}
@@ -1,55 +1,54 @@
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::cmp::PartialEq;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::rc::Rc;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
 use binrw::{binrw, binwrite, BinRead, BinReaderExt, BinResult, BinWrite, Endian, NullString};
 use bitflags::bitflags;
 
+use crate::registry::registry;
 use crate::uobject::*;
 
-const CUSTOM_STRUCT_CLASSES: [(&str, usize); 28] = [
-    ("/Script/GameNoce.NocePlayerInventoryComponent", 8),
-    ("/Script/GameNoce.NoceInteractableBase", 8),
-    ("/Script/GameNoce.NocePlayerTriggerBase", 8),
-    ("/Script/GameNoce.NocePlayerCharacter", 8),
-    ("/Script/GameNoce.NocePlayerState", 8),
-    ("/Script/GameNoce.NoceBodyPartGroupComponent", 8),
-    ("/Script/GameNoce.NoceEnemyCharacter", 8),
-    ("/Script/GameNoce.NoceMapIconComponent", 8),
-    ("/Script/Engine.ActorComponent", 8),
-    ("/Script/GameNoce.NoceEnvironmentSubsystem", 4),
-    ("/Script/GameNoce.NoceWorldManagerSubsystem", 4),
-    ("/Script/GameNoce.MucusSubsystem", 4),
-    ("/Script/GameNoce.NoceAchievementSubsystem", 4),
-    ("/Script/GameNoce.NoceActivitySubsystem", 4),
-    ("/Script/GameNoce.NoceItemSubsystem", 4),
-    ("/Script/GameNoce.NoceOmamoriDrawingSubsystem", 4),
-    ("/Script/GameNoce.NocePickupsHelperSubsystem", 4),
-    ("/Script/GameNoce.NoceTutorialSubsystem", 4),
-    ("/Script/GameNoce.NoceAISystem", 4),
-    ("/Script/GameNoce.NoceDialogSubsystem", 4),
-    ("/Script/GameNoce.NoceGameClockSubsystem", 4),
-    ("/Script/GameNoce.NoceBinkSubsystem", 4),
-    ("/Script/GameNoce.NoceHitPerformDataSubsystem", 4),
-    ("/Script/GameNoce.NocePlayerLookAtSubsystem", 4),
-    ("/Script/GameNoce.NoceTentacleSubsystem", 4),
-    ("/Script/GameNoce.NoceUIMissionSubsystem", 4),
-    ("/Script/GameNoce.NoceBattlePositionSubsystem", 4),
-    ("/Script/GameNoce.NocePickupsSubsystem", 4),
-];
 const GAMEPLAY_TAG_CONTAINER_TYPE: &str = "StructProperty</Script/GameplayTags.GameplayTagContainer>";
 const CORE_UOBJECT_TYPE_PREFIX: &str = "StructProperty</Script/CoreUObject.";
 const BLUEPRINT_NAMESPACE: &str = "/Blueprint/";
+/// Property flag that holds the value of a BoolProperty which has no value data
+pub const BOOL_TRUE_FLAG: u8 = 0x10;
 // includes null byte
 const GUID_STRING_LENGTH: u32 = 37;
 
+/// How a child of an [`Indexable`] object is identified
+#[derive(Debug, Clone, Copy)]
+pub enum ChildKey<'a> {
+    /// The name of a property
+    Name(&'a str),
+    /// The index of an array or set element
+    Index(usize),
+    /// The key of a map entry
+    MapKey(&'a PropertyValue),
+}
+
 /// A save object containing other objects which can be accessed by name or numeric index
+///
+/// Objects made of properties always keep their terminating None property last: it isn't counted as a child, can't be
+/// removed, moved or renamed, and properties are never inserted after it.
+///
+/// Arrays of bytes are stored as a single [`PropertyValue::UnknownProperty`] holding the bytes. Inserting, removing and
+/// moving elements work on the individual bytes, as [`PropertyValue::ByteProperty`] values, but the bytes can't be
+/// retrieved by index.
 pub trait Indexable {
     /// Adds a property to the object.
     fn add_property(&mut self, property: Property) -> Result<()>;
+    /// Inserts a property at the given position among the object's properties.
+    fn insert_property(&mut self, position: usize, property: Property) -> Result<()>;
+    /// Inserts an element at the given index of an array or set.
+    fn insert_index(&mut self, index: usize, value: PropertyValue) -> Result<()>;
     /// Gets the property with the given name or value for the given key, depending on the type.
     fn get_key(&self, key: &str) -> Option<&PropertyValue>;
     /// Gets a mutable reference to the property with the given name or value for the given key, depending on the type.
@@ -58,11 +57,23 @@ pub trait Indexable {
     fn get_index(&self, index: usize) -> Option<&PropertyValue>;
     /// Gets a mutable reference to the value at the given index if it exists.
     fn get_index_mut(&mut self, index: usize) -> Option<&mut PropertyValue>;
+    /// Removes the property with the given name or the entry for the given key, returning its value.
+    fn remove_key(&mut self, key: &str) -> Option<PropertyValue>;
+    /// Removes the value at the given index, returning it.
+    fn remove_index(&mut self, index: usize) -> Option<PropertyValue>;
+    /// Renames the property with the given name or changes the key of the entry for the given key.
+    fn rename_key(&mut self, key: &str, new_key: &str) -> Result<()>;
+    /// Lists the object's children in order, with the name, index or key of each one.
+    fn children(&self) -> Vec<(ChildKey<'_>, &PropertyValue)>;
+    /// Moves the child at position `from` to position `to`, shifting the children in between.
+    fn move_child(&mut self, from: usize, to: usize) -> Result<()>;
 }
 
 /// A type that can be used to index into a save object
 pub trait PropertyIndex {
+    /// Gets the value this index refers to in the given object.
     fn get_from<'a, I: Indexable>(&self, indexable: &'a I) -> Option<&'a PropertyValue>;
+    /// Gets a mutable reference to the value this index refers to in the given object.
     fn get_from_mut<'a, I: Indexable>(&self, indexable: &'a mut I) -> Option<&'a mut PropertyValue>;
 }
 
@@ -86,14 +97,19 @@ impl PropertyIndex for usize {
     }
 }
 
+/// Looks up a chain of names and/or indexes in a save object
+///
+/// `prop!(&save.save_data, ["HinakoRecord"]["Health"])` is equivalent to calling `get_key("HinakoRecord")` and then
+/// `get_key("Health")` on the result, stopping early if any step returns `None`.
+#[macro_export]
 macro_rules! prop {
     // first index is treated specially because we're not necessarily dealing with a PropertyValue
     // at that point
     ($obj:expr, [$idx1:expr] $( [$idx:expr] )* ) => {{
-        let mut cur = $idx1.get_from($obj);
+        let mut cur = $crate::save::PropertyIndex::get_from(&$idx1, $obj);
         $(
             cur = match cur {
-                Some(p) => $idx.get_from(p),
+                Some(p) => $crate::save::PropertyIndex::get_from(&$idx, p),
                 None => None,
             };
         )*
@@ -101,16 +117,18 @@ macro_rules! prop {
     }};
 }
 
-pub(crate) use prop;
+pub use prop;
 
+/// Mutable version of [`prop!`]
+#[macro_export]
 macro_rules! prop_mut {
     // first index is treated specially because we're not necessarily dealing with a PropertyValue
     // at that point
     ($obj:expr, [$idx1:expr] $( [$idx:expr] )* ) => {{
-        let mut cur = $idx1.get_from_mut($obj);
+        let mut cur = $crate::save::PropertyIndex::get_from_mut(&$idx1, $obj);
         $(
             cur = match cur {
-                Some(p) => $idx.get_from_mut(p),
+                Some(p) => $crate::save::PropertyIndex::get_from_mut(&$idx, p),
                 None => None,
             };
         )*
@@ -118,11 +136,11 @@ macro_rules! prop_mut {
     }};
 }
 
-pub(crate) use prop_mut;
+pub use prop_mut;
 
 /// A 16-byte GUID
 #[binrw]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Guid([u8; 16]);
 
 impl Display for Guid {
@@ -164,25 +182,37 @@ impl FromStr for Guid {
 }
 
 /// An Unreal Engine 5 string
+///
+/// Empty strings may be stored either as a bare zero length or as a single null terminator. We remember which one we
+/// read so the string is written back the same way.
 #[binrw]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Default)]
 pub struct FString {
-    #[bw(calc = string.len() as u32 + 1)]
+    #[bw(calc = if string.is_empty() && *unterminated { 0 } else { string.len() as u32 + 1 })]
     size: u32,
-    #[br(map = |s: NullString| s.to_string(), assert(string.len() as u32 == size - 1))]
-    #[bw(map = |s| NullString::from(s.as_str()))]
+    #[br(calc = size == 0)]
+    #[bw(ignore)]
+    unterminated: bool,
+    #[br(if(!unterminated), map = |s: NullString| s.to_string(), assert(unterminated || string.len() as u32 + 1 == size))]
+    #[bw(if(!(string.is_empty() && *unterminated)), map = |s| NullString::from(s.as_str()))]
     string: String,
 }
 
 impl FString {
     /// Creates a new, empty FString.
     pub const fn new() -> Self {
-        Self { string: String::new() }
+        Self { unterminated: false, string: String::new() }
+    }
+
+    /// Creates a new, empty FString that's stored as a bare zero length.
+    pub const fn new_unterminated() -> Self {
+        Self { unterminated: true, string: String::new() }
     }
 
     /// Creates a new FString from a string slice.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
-        Self { string: s.to_string() }
+        Self { unterminated: false, string: s.to_string() }
     }
 
     /// Returns a reference to the string's contents.
@@ -195,8 +225,21 @@ impl FString {
         self.string.len()
     }
 
+    /// Returns true if the string is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.string.is_empty()
+    }
+
+    /// Returns true if the string is empty and stored as a bare zero length without a null terminator.
+    pub const fn is_unterminated(&self) -> bool {
+        self.is_empty() && self.unterminated
+    }
+
     /// Returns the total size of the FString in bytes, including the length prefix and null terminator.
     pub const fn byte_size(&self) -> usize {
+        if self.is_unterminated() {
+            return 4;
+        }
         // +4 for length prefix, +1 for null terminator
         self.len() + 4 + 1
     }
@@ -207,6 +250,20 @@ impl FString {
     }
 }
 
+impl PartialEq for FString {
+    fn eq(&self, other: &Self) -> bool {
+        self.string == other.string
+    }
+}
+
+impl Eq for FString {}
+
+impl Hash for FString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.string.hash(state);
+    }
+}
+
 impl PartialEq<str> for FString {
     fn eq(&self, other: &str) -> bool {
         self.string == other
@@ -234,6 +291,7 @@ impl Display for FString {
 impl From<String> for FString {
     fn from(value: String) -> Self {
         Self {
+            unterminated: false,
             string: value,
         }
     }
@@ -242,6 +300,7 @@ impl From<String> for FString {
 impl From<&str> for FString {
     fn from(value: &str) -> Self {
         Self {
+            unterminated: false,
             string: value.into(),
         }
     }
@@ -295,14 +354,172 @@ pub struct PropertyValueArgs<'a> {
     property_type: &'a PropertyType,
     flags: u8,
     data_size: u32,
+    options: ParseOptions,
 }
 
 impl<'a> PropertyValueArgs<'a> {
-    const fn new(property_type: &'a PropertyType, flags: u8, data_size: u32) -> Self {
-        Self { property_type, flags, data_size }
+    fn new(property_type: &'a PropertyType, flags: u8, data_size: u32, options: ParseOptions) -> Self {
+        Self { property_type, flags, data_size, options }
+    }
+}
+
+/// Options controlling how a save is parsed
+///
+/// By default, a value that fails to parse fails the whole parse. In lenient mode, a property value that fails to parse
+/// is instead kept as raw bytes in an `UnknownProperty`, and the error is recorded so it can be reported later.
+///
+/// Custom struct data is normally only decoded for classes in the [`registry`](crate::registry). With custom struct
+/// detection enabled, the data of other classes is also decoded if it can be read as a custom struct that serializes
+/// back to exactly the same bytes.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    quarantine: Option<Rc<RefCell<Vec<ParseError>>>>,
+    detect_custom_structs: bool,
+}
+
+impl ParseOptions {
+    /// Creates options for a lenient parse
+    pub fn lenient() -> Self {
+        Self { quarantine: Some(Rc::new(RefCell::new(Vec::new()))), detect_custom_structs: false }
+    }
+
+    /// Enables or disables decoding custom struct data of unregistered classes
+    pub fn with_custom_struct_detection(mut self, enabled: bool) -> Self {
+        self.detect_custom_structs = enabled;
+        self
+    }
+
+    /// Returns whether custom struct data of unregistered classes will be decoded when possible
+    pub fn detects_custom_structs(&self) -> bool {
+        self.detect_custom_structs
+    }
+
+    /// Returns whether values that fail to parse will be quarantined instead of failing the parse
+    pub fn is_lenient(&self) -> bool {
+        self.quarantine.is_some()
+    }
+
+    /// Gets the errors for all values that were quarantined so far
+    pub fn quarantined(&self) -> Vec<ParseError> {
+        self.quarantine.as_ref().map(|q| q.borrow().clone()).unwrap_or_default()
+    }
+
+    /// Returns a marker for the current position in the quarantine list
+    fn mark(&self) -> usize {
+        self.quarantine.as_ref().map_or(0, |q| q.borrow().len())
+    }
+
+    /// Adds a parent name or index to the front of the paths of all errors quarantined since the given mark
+    fn prefix_since(&self, mark: usize, segment: &str) {
+        if let Some(ref quarantine) = self.quarantine {
+            for error in &mut quarantine.borrow_mut()[mark..] {
+                *error = std::mem::take(error).prefixed(segment);
+            }
+        }
+    }
+
+    /// Shifts the offsets of all errors quarantined since the given mark
+    fn offset_since(&self, mark: usize, delta: u64) {
+        if let Some(ref quarantine) = self.quarantine {
+            for error in &mut quarantine.borrow_mut()[mark..] {
+                error.offset += delta;
+            }
+        }
+    }
+
+    /// Records an error for a value which will be kept as raw bytes
+    ///
+    /// Any errors quarantined since the given mark were inside the value and are discarded. If the parse isn't lenient,
+    /// the error is returned back.
+    fn quarantine(&self, mark: usize, error: ParseError) -> Result<(), ParseError> {
+        match self.quarantine {
+            Some(ref quarantine) => {
+                let mut quarantine = quarantine.borrow_mut();
+                quarantine.truncate(mark);
+                quarantine.push(error);
+                Ok(())
+            }
+            None => Err(error),
+        }
+    }
+}
+
+/// An error encountered while parsing a property, with details of where it happened
+#[derive(Debug, Clone, Default)]
+pub struct ParseError {
+    /// The path of the property that failed to parse, e.g. `PlayerStateRecord.ComponentRecords[3].Data`
+    pub path: String,
+    /// The stream offset of the start of the value that failed to parse
+    pub offset: u64,
+    /// The data size declared for the value, if it was read
+    pub data_size: Option<u32>,
+    /// A description of the value's type, if it was read
+    pub type_description: Option<String>,
+    /// A description of the underlying error
+    pub message: String,
+    /// Whether the underlying error was an unexpected end of file
+    pub is_eof: bool,
+}
+
+impl ParseError {
+    /// Gets the ParseError contained in a binrw error, or creates a new one describing the error
+    fn from_binrw(err: &binrw::Error, offset: u64, data_size: Option<u32>, type_description: Option<String>) -> Self {
+        match err.custom_err::<Self>() {
+            Some(parse_error) => parse_error.clone(),
+            None => Self {
+                path: String::new(),
+                offset,
+                data_size,
+                type_description,
+                message: err.root_cause().to_string(),
+                is_eof: err.is_eof(),
+            },
+        }
+    }
+
+    /// Adds a parent name or index to the front of the path
+    fn prefixed(mut self, segment: &str) -> Self {
+        self.path = if self.path.is_empty() || self.path.starts_with('[') {
+            format!("{segment}{}", self.path)
+        } else {
+            format!("{segment}.{}", self.path)
+        };
+        self
+    }
+
+    fn into_binrw(self) -> binrw::Error {
+        binrw::Error::Custom { pos: self.offset, err: Box::new(self) }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() { "property" } else { self.path.as_str() };
+        write!(f, "failed to parse {path}")?;
+        match (&self.type_description, self.data_size) {
+            (Some(description), Some(data_size)) => write!(f, " ({description}, {data_size} bytes)")?,
+            (Some(description), None) => write!(f, " ({description})")?,
+            (None, Some(data_size)) => write!(f, " ({data_size} bytes)")?,
+            (None, None) => (),
+        }
+        write!(f, " at offset {:#x}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Adds a parent name or index to the front of the path of a ParseError contained in a binrw error
+fn prefix_parse_error(err: binrw::Error, segment: &str) -> binrw::Error {
+    match err.custom_err::<ParseError>() {
+        Some(parse_error) => parse_error.clone().prefixed(segment).into_binrw(),
+        None => err,
     }
 }
 
+fn is_eof(err: &binrw::Error) -> bool {
+    err.is_eof() || err.custom_err::<ParseError>().is_some_and(|e| e.is_eof)
+}
+
 bitflags! {
     /// Flags indicating various properties of a TextProperty
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -317,6 +534,173 @@ bitflags! {
     }
 }
 
+/// A text value nested inside another, e.g. the pattern of a formatted text
+#[binrw]
+#[derive(Debug, Clone)]
+pub struct FText {
+    #[br(map = TextFlags::from_bits_retain)]
+    #[bw(map = TextFlags::bits)]
+    pub flags: TextFlags,
+    pub data: Box<TextData>,
+}
+
+impl FText {
+    /// Returns the size of the FText in bytes
+    pub fn size(&self) -> usize {
+        4 + self.data.size()
+    }
+}
+
+/// An argument to a NamedFormat or OrderedFormat text
+#[binrw]
+#[derive(Debug, Clone)]
+pub enum FormatArgumentValue {
+    #[brw(magic = 0i8)]
+    Int(i64),
+    #[brw(magic = 1i8)]
+    UInt(u64),
+    #[brw(magic = 2i8)]
+    Float(f32),
+    #[brw(magic = 3i8)]
+    Double(f64),
+    #[brw(magic = 4i8)]
+    Text(FText),
+    #[brw(magic = 5i8)]
+    Gender(u8),
+}
+
+impl FormatArgumentValue {
+    /// Returns the size of the FormatArgumentValue in bytes
+    pub fn size(&self) -> usize {
+        // +1 for magic
+        1 + match self {
+            Self::Int(_) | Self::UInt(_) | Self::Double(_) => 8,
+            Self::Float(_) => 4,
+            Self::Text(text) => text.size(),
+            Self::Gender(_) => 1,
+        }
+    }
+}
+
+impl From<&FormatArgumentDataValue> for FormatArgumentValue {
+    fn from(value: &FormatArgumentDataValue) -> Self {
+        match value {
+            FormatArgumentDataValue::Int(i) => Self::Int(*i as i64),
+            FormatArgumentDataValue::Float(f) => Self::Float(*f),
+            FormatArgumentDataValue::Double(d) => Self::Double(*d),
+            FormatArgumentDataValue::Text(text) => Self::Text(text.clone()),
+            FormatArgumentDataValue::Gender(g) => Self::Gender(*g),
+        }
+    }
+}
+
+/// The value of an ArgumentFormat text argument
+///
+/// This is an older encoding than [`FormatArgumentValue`] with narrower integers and no unsigned type.
+#[binrw]
+#[derive(Debug, Clone)]
+pub enum FormatArgumentDataValue {
+    #[brw(magic = 0u8)]
+    Int(i32),
+    #[brw(magic = 2u8)]
+    Float(f32),
+    #[brw(magic = 3u8)]
+    Double(f64),
+    #[brw(magic = 4u8)]
+    Text(FText),
+    #[brw(magic = 5u8)]
+    Gender(u8),
+}
+
+impl From<&FormatArgumentValue> for FormatArgumentDataValue {
+    /// Converts a format argument to the older encoding, clamping integers that are out of range
+    fn from(value: &FormatArgumentValue) -> Self {
+        match value {
+            FormatArgumentValue::Int(i) => Self::Int((*i).clamp(i32::MIN as i64, i32::MAX as i64) as i32),
+            FormatArgumentValue::UInt(u) => Self::Int((*u).min(i32::MAX as u64) as i32),
+            FormatArgumentValue::Float(f) => Self::Float(*f),
+            FormatArgumentValue::Double(d) => Self::Double(*d),
+            FormatArgumentValue::Text(text) => Self::Text(text.clone()),
+            FormatArgumentValue::Gender(g) => Self::Gender(*g),
+        }
+    }
+}
+
+/// A named argument to an ArgumentFormat text
+#[binrw]
+#[derive(Debug, Clone)]
+pub struct FormatArgumentData {
+    pub name: FString,
+    pub value: FormatArgumentDataValue,
+}
+
+impl FormatArgumentData {
+    /// Returns the size of the FormatArgumentData in bytes
+    pub fn size(&self) -> usize {
+        // +1 for magic
+        self.name.byte_size() + 1 + match &self.value {
+            FormatArgumentDataValue::Int(_) | FormatArgumentDataValue::Float(_) => 4,
+            FormatArgumentDataValue::Double(_) => 8,
+            FormatArgumentDataValue::Text(text) => text.size(),
+            FormatArgumentDataValue::Gender(_) => 1,
+        }
+    }
+}
+
+/// Options controlling how a number is formatted as text
+#[binrw]
+#[derive(Debug, Clone)]
+pub struct NumberFormattingOptions {
+    #[br(map = |b: u32| b != 0)]
+    #[bw(map = |b| *b as u32)]
+    pub always_sign: bool,
+    #[br(map = |b: u32| b != 0)]
+    #[bw(map = |b| *b as u32)]
+    pub use_grouping: bool,
+    pub rounding_mode: i8,
+    pub minimum_integral_digits: i32,
+    pub maximum_integral_digits: i32,
+    pub minimum_fractional_digits: i32,
+    pub maximum_fractional_digits: i32,
+}
+
+impl NumberFormattingOptions {
+    /// Returns the size of the NumberFormattingOptions in bytes
+    pub const fn size(&self) -> usize {
+        25
+    }
+}
+
+/// A number to be formatted as text, shared by the AsNumber, AsPercent and AsCurrency text history types
+#[binrw]
+#[derive(Debug, Clone)]
+pub struct FormatNumber {
+    pub source_value: FormatArgumentValue,
+    #[br(temp)]
+    #[bw(calc = options.is_some() as u32)]
+    has_options: u32,
+    #[br(if(has_options != 0))]
+    pub options: Option<NumberFormattingOptions>,
+    pub culture_name: FString,
+}
+
+impl Default for FormatNumber {
+    fn default() -> Self {
+        Self {
+            source_value: FormatArgumentValue::Int(0),
+            options: None,
+            culture_name: FString::new(),
+        }
+    }
+}
+
+impl FormatNumber {
+    /// Returns the size of the FormatNumber in bytes
+    pub fn size(&self) -> usize {
+        self.source_value.size() + 4 + self.options.as_ref().map_or(0, NumberFormattingOptions::size) + self.culture_name.byte_size()
+    }
+}
+
 /// The value of a TextProperty
 #[binrw]
 #[derive(Debug, Clone)]
@@ -334,6 +718,57 @@ pub enum TextData {
         key: FString,
         source_string: FString,
     },
+    #[brw(magic = 1i8)]
+    NamedFormat {
+        source_format: FText,
+        #[bw(calc = arguments.len() as u32)]
+        count: u32,
+        #[br(count = count)]
+        arguments: Vec<(FString, FormatArgumentValue)>,
+    },
+    #[brw(magic = 2i8)]
+    OrderedFormat {
+        source_format: FText,
+        #[bw(calc = arguments.len() as u32)]
+        count: u32,
+        #[br(count = count)]
+        arguments: Vec<FormatArgumentValue>,
+    },
+    #[brw(magic = 3i8)]
+    ArgumentFormat {
+        source_format: FText,
+        #[bw(calc = arguments.len() as u32)]
+        count: u32,
+        #[br(count = count)]
+        arguments: Vec<FormatArgumentData>,
+    },
+    #[brw(magic = 4i8)]
+    AsNumber {
+        number: FormatNumber,
+    },
+    #[brw(magic = 5i8)]
+    AsPercent {
+        number: FormatNumber,
+    },
+    #[brw(magic = 6i8)]
+    AsCurrency {
+        currency_code: FString,
+        number: FormatNumber,
+    },
+    #[brw(magic = 7i8)]
+    AsDate {
+        ticks: i64,
+        date_style: i8,
+        time_zone: FString,
+        culture_name: FString,
+    },
+    #[brw(magic = 8i8)]
+    AsTime {
+        ticks: i64,
+        time_style: i8,
+        time_zone: FString,
+        culture_name: FString,
+    },
     #[brw(magic = 9i8)]
     AsDateTime {
         ticks: i64,
@@ -342,6 +777,11 @@ pub enum TextData {
         time_zone: FString,
         culture_name: FString,
     },
+    #[brw(magic = 10i8)]
+    Transform {
+        source_text: FText,
+        transform_type: u8,
+    },
     #[brw(magic = 11i8)]
     StringTableEntry {
         table: FString,
@@ -350,20 +790,228 @@ pub enum TextData {
 }
 
 impl TextData {
+    /// Names of the text history types, in the order of their type numbers
+    pub const HISTORY_NAMES: [&str; 13] = [
+        "None",
+        "Base",
+        "NamedFormat",
+        "OrderedFormat",
+        "ArgumentFormat",
+        "AsNumber",
+        "AsPercent",
+        "AsCurrency",
+        "AsDate",
+        "AsTime",
+        "AsDateTime",
+        "Transform",
+        "StringTableEntry",
+    ];
+
+    /// Returns the name of this text's history type
+    pub const fn history_name(&self) -> &'static str {
+        match self {
+            Self::None { .. } => "None",
+            Self::Base { .. } => "Base",
+            Self::NamedFormat { .. } => "NamedFormat",
+            Self::OrderedFormat { .. } => "OrderedFormat",
+            Self::ArgumentFormat { .. } => "ArgumentFormat",
+            Self::AsNumber { .. } => "AsNumber",
+            Self::AsPercent { .. } => "AsPercent",
+            Self::AsCurrency { .. } => "AsCurrency",
+            Self::AsDate { .. } => "AsDate",
+            Self::AsTime { .. } => "AsTime",
+            Self::AsDateTime { .. } => "AsDateTime",
+            Self::Transform { .. } => "Transform",
+            Self::StringTableEntry { .. } => "StringTableEntry",
+        }
+    }
+
+    /// Returns the source string of this text, looking through formats and transforms to the text they're based on
+    pub fn source_string(&self) -> Option<&FString> {
+        match self {
+            Self::None { values } => values.first(),
+            Self::Base { source_string, .. } => Some(source_string),
+            Self::NamedFormat { source_format, .. } | Self::OrderedFormat { source_format, .. } | Self::ArgumentFormat { source_format, .. } => {
+                source_format.data.source_string()
+            }
+            Self::Transform { source_text, .. } => source_text.data.source_string(),
+            _ => None,
+        }
+    }
+
+    /// Converts this text to the history type with the given name, carrying over any fields the two types have in
+    /// common
+    ///
+    /// For example, the source string of a Base text becomes the value of a None text, and the number of an AsNumber
+    /// text becomes the number of an AsCurrency text. Simple texts become the source of a format or transform.
+    /// Returns `None` if the name isn't one of [`Self::HISTORY_NAMES`].
+    pub fn converted(&self, history_name: &str) -> Option<Self> {
+        let source_string = self.source_string().cloned().unwrap_or_default();
+        let (namespace, key) = match self {
+            Self::Base { namespace, key, .. } => (namespace.clone(), key.clone()),
+            Self::StringTableEntry { key, .. } => (FString::new(), key.clone()),
+            _ => (FString::new(), FString::new()),
+        };
+        let source_text = match self {
+            Self::NamedFormat { source_format, .. } | Self::OrderedFormat { source_format, .. } | Self::ArgumentFormat { source_format, .. } => {
+                source_format.clone()
+            }
+            Self::Transform { source_text, .. } => source_text.clone(),
+            _ => FText { flags: TextFlags::empty(), data: Box::new(self.clone()) },
+        };
+        let arguments: Vec<(FString, FormatArgumentValue)> = match self {
+            Self::NamedFormat { arguments, .. } => arguments.clone(),
+            Self::OrderedFormat { arguments, .. } => {
+                arguments.iter().enumerate().map(|(i, value)| (FString::from(i.to_string()), value.clone())).collect()
+            }
+            Self::ArgumentFormat { arguments, .. } => {
+                arguments.iter().map(|argument| (argument.name.clone(), FormatArgumentValue::from(&argument.value))).collect()
+            }
+            _ => Vec::new(),
+        };
+        let (ticks, date_style, time_style, time_zone) = match self {
+            Self::AsDate { ticks, date_style, time_zone, .. } => (*ticks, *date_style, 0, time_zone.clone()),
+            Self::AsTime { ticks, time_style, time_zone, .. } => (*ticks, 0, *time_style, time_zone.clone()),
+            Self::AsDateTime { ticks, date_style, time_style, time_zone, .. } => (*ticks, *date_style, *time_style, time_zone.clone()),
+            _ => (0, 0, 0, FString::new()),
+        };
+        let (currency_code, number) = match self {
+            Self::AsNumber { number } | Self::AsPercent { number } => (FString::new(), number.clone()),
+            Self::AsCurrency { currency_code, number } => (currency_code.clone(), number.clone()),
+            _ => (FString::new(), FormatNumber::default()),
+        };
+        let culture_name = match self {
+            Self::AsDate { culture_name, .. } | Self::AsTime { culture_name, .. } | Self::AsDateTime { culture_name, .. } => culture_name.clone(),
+            _ => number.culture_name.clone(),
+        };
+
+        Some(match history_name {
+            "None" => Self::None { values: self.source_string().into_iter().cloned().collect() },
+            "Base" => Self::Base { namespace, key, source_string },
+            "NamedFormat" => Self::NamedFormat { source_format: source_text, arguments },
+            "OrderedFormat" => Self::OrderedFormat {
+                source_format: source_text,
+                arguments: arguments.into_iter().map(|(_, value)| value).collect(),
+            },
+            "ArgumentFormat" => Self::ArgumentFormat {
+                source_format: source_text,
+                arguments: arguments.iter().map(|(name, value)| FormatArgumentData { name: name.clone(), value: value.into() }).collect(),
+            },
+            "AsNumber" => Self::AsNumber { number: FormatNumber { culture_name, ..number } },
+            "AsPercent" => Self::AsPercent { number: FormatNumber { culture_name, ..number } },
+            "AsCurrency" => Self::AsCurrency { currency_code, number: FormatNumber { culture_name, ..number } },
+            "AsDate" => Self::AsDate { ticks, date_style, time_zone, culture_name },
+            "AsTime" => Self::AsTime { ticks, time_style, time_zone, culture_name },
+            "AsDateTime" => Self::AsDateTime { ticks, date_style, time_style, time_zone, culture_name },
+            "Transform" => Self::Transform { source_text, transform_type: 0 },
+            "StringTableEntry" => Self::StringTableEntry { table: FString::new(), key },
+            _ => return None,
+        })
+    }
+
     /// Returns the size of the TextData in bytes
     pub fn size(&self) -> usize {
         // +1 for magic
         1 + match self {
             Self::None { values } => 4 + values.iter().map(FString::byte_size).sum::<usize>(),
             Self::Base { namespace, key, source_string } => namespace.byte_size() + key.byte_size() + source_string.byte_size(),
+            Self::NamedFormat { source_format, arguments } => {
+                source_format.size() + 4 + arguments.iter().map(|(name, value)| name.byte_size() + value.size()).sum::<usize>()
+            }
+            Self::OrderedFormat { source_format, arguments } => {
+                source_format.size() + 4 + arguments.iter().map(FormatArgumentValue::size).sum::<usize>()
+            }
+            Self::ArgumentFormat { source_format, arguments } => {
+                source_format.size() + 4 + arguments.iter().map(FormatArgumentData::size).sum::<usize>()
+            }
+            Self::AsNumber { number } | Self::AsPercent { number } => number.size(),
+            Self::AsCurrency { currency_code, number } => currency_code.byte_size() + number.size(),
+            Self::AsDate { time_zone, culture_name, .. } | Self::AsTime { time_zone, culture_name, .. } => {
+                9 + time_zone.byte_size() + culture_name.byte_size()
+            }
             Self::AsDateTime { time_zone, culture_name, .. } => 10 + time_zone.byte_size() + culture_name.byte_size(),
+            Self::Transform { source_text, .. } => source_text.size() + 1,
             Self::StringTableEntry { table, key } => table.byte_size() + key.byte_size(),
         }
     }
 }
 
+/// A reference to an asset by path, the value of a SoftObjectProperty or SoftClassProperty
+///
+/// The package name and asset name together make up the top-level asset path, e.g.
+/// `/Game/Maps/Village` and `Village`. The sub-path identifies an object inside the asset and is usually empty.
+#[binrw]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoftObjectPath {
+    pub package_name: FString,
+    pub asset_name: FString,
+    pub sub_path: FString,
+}
+
+impl SoftObjectPath {
+    /// Returns the size of the SoftObjectPath in bytes
+    pub fn size(&self) -> usize {
+        self.package_name.byte_size() + self.asset_name.byte_size() + self.sub_path.byte_size()
+    }
+}
+
+impl Display for SoftObjectPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.asset_name.is_empty() {
+            write!(f, "{}.{}", self.package_name, self.asset_name)?;
+        } else {
+            write!(f, "{}", self.package_name)?;
+        }
+        if !self.sub_path.is_empty() {
+            write!(f, ":{}", self.sub_path)?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of gameplay tags, the value of a `/Script/GameplayTags.GameplayTagContainer` struct
+///
+/// Tags are hierarchical names like `Noce.Event.Shrine.Visited`. The game treats the container as a set, so the order
+/// of the tags doesn't matter.
+#[binrw]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameplayTagContainer {
+    #[bw(calc = tags.len() as u32)]
+    count: u32,
+    #[br(count = count)]
+    pub tags: Vec<FString>,
+}
+
+impl GameplayTagContainer {
+    /// Returns whether the container has the given tag
+    pub fn contains(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Adds a tag to the container, returning false if it was already there
+    pub fn add(&mut self, tag: &str) -> bool {
+        if self.contains(tag) {
+            return false;
+        }
+        self.tags.push(FString::from_str(tag));
+        true
+    }
+
+    /// Removes a tag from the container, returning false if it wasn't there
+    pub fn remove(&mut self, tag: &str) -> bool {
+        let num_tags = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() != num_tags
+    }
+
+    /// Returns the size of the GameplayTagContainer in bytes
+    pub fn size(&self) -> usize {
+        4 + self.tags.iter().map(FString::byte_size).sum::<usize>()
+    }
+}
+
 #[binrw::parser(reader, endian)]
-fn read_properties_with_footer(footer_size: u64) -> BinResult<Vec<Property>> {
+fn read_properties_with_footer(footer_size: u64, options: ParseOptions) -> BinResult<Vec<Property>> {
     let mut props = Vec::new();
 
     let start = reader.stream_position()?;
@@ -374,9 +1022,9 @@ fn read_properties_with_footer(footer_size: u64) -> BinResult<Vec<Property>> {
     let end = eof - footer_size;
 
     while reader.stream_position()? < end {
-        match Property::read_options(reader, endian, ()) {
+        match Property::read_options(reader, endian, options.clone()) {
             Ok(prop) => props.push(prop),
-            Err(e) if e.is_eof() && footer_size == 0 => break,
+            Err(e) if is_eof(&e) && footer_size == 0 => break,
             Err(e) => return Err(e),
         }
     }
@@ -384,10 +1032,13 @@ fn read_properties_with_footer(footer_size: u64) -> BinResult<Vec<Property>> {
     Ok(props)
 }
 
+/// Footer sizes tried when detecting custom struct data of unregistered classes
+const CUSTOM_STRUCT_FOOTER_SIZES: [usize; 3] = [0, 4, 8];
+
 /// A custom game type which is encoded as a byte array instead of a StructProperty
 #[binrw]
-#[derive(Debug)]
-#[br(import(extra_bytes: usize))]
+#[derive(Debug, Clone)]
+#[br(import(extra_bytes: usize, options: ParseOptions))]
 pub struct CustomStruct {
     // this field is ignored on read because it's read as part of the ArrayProperty before we detect
     // whether the array contents are a custom struct or not
@@ -395,7 +1046,7 @@ pub struct CustomStruct {
     #[bw(calc = self.size() as u32 - 4)]
     data_size: u32,
     pub flags: u8,
-    #[br(parse_with = |r, e, _: ()| read_properties_with_footer(r, e, (extra_bytes as u64,)))]
+    #[br(parse_with = |r, e, _: ()| read_properties_with_footer(r, e, (extra_bytes as u64, options.clone())))]
     pub properties: Vec<Property>,
     #[br(count = extra_bytes)]
     pub extra: Vec<u8>,
@@ -411,10 +1062,16 @@ impl CustomStruct {
 /// A list of names of property types considered scalar
 ///
 /// This excludes types with complex initialization.
-pub const SCALAR_TYPE_NAMES: [&str; 9] = [
+pub const SCALAR_TYPE_NAMES: [&str; 15] = [
     "BoolProperty",
     "ByteProperty",
     "IntProperty",
+    "Int8Property",
+    "Int16Property",
+    "Int64Property",
+    "UInt16Property",
+    "UInt32Property",
+    "UInt64Property",
     "FloatProperty",
     "DoubleProperty",
     "StrProperty",
@@ -425,12 +1082,19 @@ pub const SCALAR_TYPE_NAMES: [&str; 9] = [
 
 /// The value of a UE5 property
 #[binwrite]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum PropertyValue {
     StrProperty(FString),
     BoolProperty(#[bw(map = |b| b.map(|b| b as u8))] Option<bool>),
     ByteProperty(u8),
     IntProperty(i32),
+    Int8Property(i8),
+    Int16Property(i16),
+    Int64Property(i64),
+    UInt16Property(u16),
+    UInt32Property(u32),
+    UInt64Property(u64),
     FloatProperty(f32),
     DoubleProperty(f64),
     TextProperty {
@@ -439,11 +1103,18 @@ pub enum PropertyValue {
         data: TextData,
     },
     EnumProperty(FString),
+    /// An enum value stored in a ByteProperty
+    ///
+    /// The enum type is only recorded in the property's type; see [`PropertyType::enum_type`].
+    ByteEnumProperty(FString),
     NameProperty(FString),
     ObjectProperty(FString),
+    SoftObjectProperty(SoftObjectPath),
+    SoftClassProperty(SoftObjectPath),
     StructProperty(Vec<Property>),
     CustomStructProperty(CustomStruct),
     CoreUObjectStructProperty(#[bw(write_with = write_uobject)] Box<dyn CoreUObject>),
+    GameplayTagContainerProperty(GameplayTagContainer),
     ArrayProperty {
         #[bw(calc = self.array_len().unwrap() as u32)]
         count: u32,
@@ -455,6 +1126,12 @@ pub enum PropertyValue {
         count: u32,
         values: Vec<(PropertyValue, PropertyValue)>,
     },
+    SetProperty {
+        removed_count: u32,
+        #[bw(calc = self.array_len().unwrap() as u32)]
+        count: u32,
+        values: Vec<PropertyValue>,
+    },
     UnknownProperty(Vec<u8>),
 }
 
@@ -465,10 +1142,18 @@ impl PropertyValue {
             "BoolProperty" => Self::BoolProperty(None),
             "ByteProperty" => Self::ByteProperty(0),
             "IntProperty" => Self::IntProperty(0),
+            "Int8Property" => Self::Int8Property(0),
+            "Int16Property" => Self::Int16Property(0),
+            "Int64Property" => Self::Int64Property(0),
+            "UInt16Property" => Self::UInt16Property(0),
+            "UInt32Property" => Self::UInt32Property(0),
+            "UInt64Property" => Self::UInt64Property(0),
             "FloatProperty" => Self::FloatProperty(0.0),
             "DoubleProperty" => Self::DoubleProperty(0.0),
             "StrProperty" => Self::StrProperty(FString::new()),
             "ObjectProperty" => Self::ObjectProperty(FString::new()),
+            "SoftObjectProperty" => Self::SoftObjectProperty(SoftObjectPath::default()),
+            "SoftClassProperty" => Self::SoftClassProperty(SoftObjectPath::default()),
             "NameProperty" => Self::NameProperty(FString::new()),
             "EnumProperty" => Self::EnumProperty(FString::new()),
             "TextProperty" => Self::TextProperty {
@@ -480,6 +1165,7 @@ impl PropertyValue {
             "StructProperty" => Self::StructProperty(Vec::new()),
             "ArrayProperty" => Self::ArrayProperty { values: Vec::new() },
             "MapProperty" => Self::MapProperty { removed_count: 0, values: Vec::new() },
+            "SetProperty" => Self::SetProperty { removed_count: 0, values: Vec::new() },
             _ => Self::UnknownProperty(Vec::new()),
         }
     }
@@ -488,16 +1174,21 @@ impl PropertyValue {
     pub fn size(&self) -> usize {
         match self {
             Self::StrProperty(s) | Self::EnumProperty(s) | Self::NameProperty(s) | Self::ObjectProperty(s) => s.byte_size(),
+            Self::ByteEnumProperty(value) => value.byte_size(),
             Self::BoolProperty(None) => 0,
-            Self::ByteProperty(_) | Self::BoolProperty(Some(_)) => 1,
-            Self::IntProperty(_) | Self::FloatProperty(_) => 4,
-            Self::DoubleProperty(_) => 8,
+            Self::ByteProperty(_) | Self::Int8Property(_) | Self::BoolProperty(Some(_)) => 1,
+            Self::Int16Property(_) | Self::UInt16Property(_) => 2,
+            Self::IntProperty(_) | Self::UInt32Property(_) | Self::FloatProperty(_) => 4,
+            Self::Int64Property(_) | Self::UInt64Property(_) | Self::DoubleProperty(_) => 8,
             Self::TextProperty { data, .. } => 4 + data.size(),
+            Self::SoftObjectProperty(path) | Self::SoftClassProperty(path) => path.size(),
             Self::StructProperty(props) => props.iter().map(Property::size).sum::<usize>(),
             Self::CustomStructProperty(s) => s.size(),
             Self::CoreUObjectStructProperty(s) => s.size(),
+            Self::GameplayTagContainerProperty(container) => container.size(),
             Self::ArrayProperty { values } => 4 + values.iter().map(PropertyValue::size).sum::<usize>(),
             Self::MapProperty { values, .. } => 8 + values.iter().map(|(k, v)| k.size() + v.size()).sum::<usize>(),
+            Self::SetProperty { values, .. } => 8 + values.iter().map(PropertyValue::size).sum::<usize>(),
             Self::UnknownProperty(v) => v.len(),
         }
     }
@@ -508,6 +1199,7 @@ impl PropertyValue {
             // custom structs are encoded as byte arrays
             Self::CustomStructProperty(s) => s.size(),
             Self::MapProperty { values, .. } => values.len(),
+            Self::SetProperty { values, .. } => values.len(),
             Self::ArrayProperty { values } => {
                 let num_values = values.len();
                 // as an optimization, we read byte arrays as a single UnknownProperty instead of a
@@ -527,44 +1219,235 @@ impl PropertyValue {
         match self {
             Self::StrProperty(_) => "StrProperty",
             Self::BoolProperty(_) => "BoolProperty",
-            Self::ByteProperty(_) => "ByteProperty",
+            Self::ByteProperty(_) | Self::ByteEnumProperty(_) => "ByteProperty",
             Self::IntProperty(_) => "IntProperty",
+            Self::Int8Property(_) => "Int8Property",
+            Self::Int16Property(_) => "Int16Property",
+            Self::Int64Property(_) => "Int64Property",
+            Self::UInt16Property(_) => "UInt16Property",
+            Self::UInt32Property(_) => "UInt32Property",
+            Self::UInt64Property(_) => "UInt64Property",
             Self::FloatProperty(_) => "FloatProperty",
             Self::DoubleProperty(_) => "DoubleProperty",
             Self::TextProperty { .. } => "TextProperty",
             Self::EnumProperty(_) => "EnumProperty",
             Self::NameProperty(_) => "NameProperty",
             Self::ObjectProperty(_) => "ObjectProperty",
-            Self::StructProperty(_) | Self::CoreUObjectStructProperty(_) => "StructProperty",
+            Self::SoftObjectProperty(_) => "SoftObjectProperty",
+            Self::SoftClassProperty(_) => "SoftClassProperty",
+            Self::StructProperty(_) | Self::CoreUObjectStructProperty(_) | Self::GameplayTagContainerProperty(_) => "StructProperty",
             Self::ArrayProperty { .. } | Self::CustomStructProperty(_) => "ArrayProperty",
             Self::MapProperty { .. } => "MapProperty",
+            Self::SetProperty { .. } => "SetProperty",
             Self::UnknownProperty(_) => "",
         }
     }
-}
 
-impl Indexable for PropertyValue {
-    fn add_property(&mut self, property: Property) -> Result<()> {
+    /// Returns a string representation of this value if it's a scalar
+    pub fn scalar_string(&self) -> Option<String> {
+        Some(match self {
+            Self::StrProperty(s) | Self::EnumProperty(s) | Self::NameProperty(s) | Self::ObjectProperty(s) => s.to_string(),
+            Self::ByteEnumProperty(value) => value.to_string(),
+            Self::BoolProperty(Some(b)) => b.to_string(),
+            Self::ByteProperty(b) => b.to_string(),
+            Self::IntProperty(i) => i.to_string(),
+            Self::Int8Property(i) => i.to_string(),
+            Self::Int16Property(i) => i.to_string(),
+            Self::Int64Property(i) => i.to_string(),
+            Self::UInt16Property(i) => i.to_string(),
+            Self::UInt32Property(i) => i.to_string(),
+            Self::UInt64Property(i) => i.to_string(),
+            Self::FloatProperty(f) => f.to_string(),
+            Self::DoubleProperty(d) => d.to_string(),
+            _ => return None,
+        })
+    }
+
+    /// Returns a short human-readable description of this value
+    ///
+    /// Scalars are shown as their value; containers are summarized by their number of elements.
+    pub fn summary(&self) -> String {
+        if let Some(s) = self.scalar_string() {
+            return s;
+        }
+
+        let count_properties = |props: &[Property]| props.iter().filter(|p| !p.is_none()).count();
         match self {
-            Self::StructProperty(properties) => {
-                // if we have a None property at the end of our property list, which we should, then we
-                // should insert the new property before it
-                if !property.is_none() && properties.last().map(Property::is_none).unwrap_or(false) {
-                    properties.insert(properties.len() - 1, property);
-                } else {
-                    properties.push(property);
-                }
-                Ok(())
-            }
-            _ => Err(anyhow!("Cannot add property to non-struct property")),
+            Self::BoolProperty(None) => String::from("(stored in flags)"),
+            Self::TextProperty { data, .. } => format!("{data:?}"),
+            Self::SoftObjectProperty(path) | Self::SoftClassProperty(path) => path.to_string(),
+            Self::StructProperty(props) => format!("({} properties)", count_properties(props)),
+            Self::CustomStructProperty(custom_struct) => format!("({} properties)", count_properties(&custom_struct.properties)),
+            Self::CoreUObjectStructProperty(object) => format!("{object:?}"),
+            Self::GameplayTagContainerProperty(container) => format!("({} tags)", container.tags.len()),
+            Self::ArrayProperty { values } => match values.first() {
+                Some(Self::UnknownProperty(data)) if values.len() == 1 => format!("({} bytes)", data.len()),
+                _ => format!("({} values)", values.len()),
+            },
+            Self::MapProperty { values, .. } => format!("({} entries)", values.len()),
+            Self::SetProperty { values, .. } => format!("({} values)", values.len()),
+            Self::UnknownProperty(data) => format!("({} bytes)", data.len()),
+            _ => unreachable!("scalar values are handled above"),
         }
     }
-    
-    fn get_key(&self, key: &str) -> Option<&Self> {
+
+    /// Sets this value from its string representation
+    ///
+    /// Only scalar values can be set this way. If the string cannot be parsed as the value's type,
+    /// the value is left unchanged.
+    pub fn set_from_str(&mut self, s: &str) -> Result<()> {
         match self {
-            Self::StructProperty(props) => props.iter().find_map(|p| if p.name == key {
-                p.body.as_ref().map(|b| &b.value)
-            } else {
+            Self::StrProperty(v) | Self::EnumProperty(v) | Self::NameProperty(v) | Self::ObjectProperty(v) => *v = FString::from_str(s),
+            Self::ByteEnumProperty(value) => *value = FString::from_str(s),
+            Self::BoolProperty(Some(b)) => *b = s.parse()?,
+            Self::BoolProperty(None) => return Err(anyhow!("BoolProperty value is stored in the property flags")),
+            Self::ByteProperty(b) => *b = s.parse()?,
+            Self::IntProperty(i) => *i = s.parse()?,
+            Self::Int8Property(i) => *i = s.parse()?,
+            Self::Int16Property(i) => *i = s.parse()?,
+            Self::Int64Property(i) => *i = s.parse()?,
+            Self::UInt16Property(i) => *i = s.parse()?,
+            Self::UInt32Property(i) => *i = s.parse()?,
+            Self::UInt64Property(i) => *i = s.parse()?,
+            Self::FloatProperty(f) => *f = s.parse()?,
+            Self::DoubleProperty(d) => *d = s.parse()?,
+            _ => return Err(anyhow!("Cannot set a non-scalar {} from a string", self.type_name())),
+        }
+        Ok(())
+    }
+}
+
+fn add_to_properties(properties: &mut Vec<Property>, property: Property) {
+    // if we have a None property at the end of our property list, which we should, then we
+    // should insert the new property before it
+    if !property.is_none() && properties.last().map(Property::is_none).unwrap_or(false) {
+        properties.insert(properties.len() - 1, property);
+    } else {
+        properties.push(property);
+    }
+}
+
+/// Returns the number of properties in a list, not counting the terminating None property
+fn count_children(properties: &[Property]) -> usize {
+    properties.iter().filter(|p| !p.is_none()).count()
+}
+
+fn insert_into_properties(properties: &mut Vec<Property>, position: usize, property: Property) -> Result<()> {
+    if property.is_none() {
+        return Err(anyhow!("Cannot insert a None property"));
+    }
+    let count = count_children(properties);
+    if position > count {
+        return Err(anyhow!("Position {position} is out of bounds for {count} properties"));
+    }
+    properties.insert(position, property);
+    Ok(())
+}
+
+fn remove_from_properties(properties: &mut Vec<Property>, name: &str) -> Option<PropertyValue> {
+    let position = properties.iter().position(|p| p.name == name && !p.is_none())?;
+    properties.remove(position).body.map(|b| b.value)
+}
+
+fn rename_in_properties(properties: &mut [Property], name: &str, new_name: &str) -> Result<()> {
+    if new_name.is_empty() || new_name == "None" {
+        return Err(anyhow!("Invalid property name {new_name:?}"));
+    }
+    if name != new_name && properties.iter().any(|p| p.name == new_name) {
+        return Err(anyhow!("A property named {new_name} already exists"));
+    }
+    let property = properties.iter_mut().find(|p| p.name == name && !p.is_none()).ok_or_else(|| anyhow!("No property named {name}"))?;
+    property.name = FString::from_str(new_name);
+    Ok(())
+}
+
+fn property_children(properties: &[Property]) -> Vec<(ChildKey<'_>, &PropertyValue)> {
+    properties.iter().filter_map(|p| p.body.as_ref().map(|b| (ChildKey::Name(p.name.as_str()), &b.value))).collect()
+}
+
+/// Returns whether a map key has the given string form, so keys of any scalar type can be named by a string
+fn map_key_matches(map_key: &PropertyValue, key: &str) -> bool {
+    map_key.scalar_string().as_deref() == Some(key)
+}
+
+/// Returns the bytes of an array of bytes, which is read as a single UnknownProperty rather than a list of ByteProperty
+fn byte_array_mut(values: &mut [PropertyValue]) -> Option<&mut Vec<u8>> {
+    match values {
+        [PropertyValue::UnknownProperty(buf)] => Some(buf),
+        _ => None,
+    }
+}
+
+fn is_byte_array(values: &[PropertyValue]) -> bool {
+    matches!(values, [PropertyValue::UnknownProperty(_)])
+}
+
+fn move_in_list<T>(values: &mut [T], from: usize, to: usize) -> Result<()> {
+    let count = values.len();
+    if from >= count || to >= count {
+        return Err(anyhow!("Cannot move from position {from} to {to} among {count} children"));
+    }
+    if from < to {
+        values[from..=to].rotate_left(1);
+    } else {
+        values[to..=from].rotate_right(1);
+    }
+    Ok(())
+}
+
+fn move_in_properties(properties: &mut [Property], from: usize, to: usize) -> Result<()> {
+    // the None property is always last, so leave it out
+    let count = count_children(properties);
+    move_in_list(&mut properties[..count], from, to)
+}
+
+impl Indexable for PropertyValue {
+    fn add_property(&mut self, property: Property) -> Result<()> {
+        match self {
+            Self::StructProperty(properties) => add_to_properties(properties, property),
+            Self::CustomStructProperty(s) => add_to_properties(&mut s.properties, property),
+            _ => return Err(anyhow!("Cannot add property to non-struct property")),
+        }
+        Ok(())
+    }
+
+    fn insert_property(&mut self, position: usize, property: Property) -> Result<()> {
+        match self {
+            Self::StructProperty(properties) => insert_into_properties(properties, position, property),
+            Self::CustomStructProperty(s) => insert_into_properties(&mut s.properties, position, property),
+            _ => Err(anyhow!("Cannot insert property into non-struct property")),
+        }
+    }
+
+    fn insert_index(&mut self, index: usize, value: PropertyValue) -> Result<()> {
+        if let Self::ArrayProperty { values } = self && let Some(buf) = byte_array_mut(values) {
+            let Self::ByteProperty(byte) = value else {
+                return Err(anyhow!("Only bytes can be inserted into a byte array"));
+            };
+            if index > buf.len() {
+                return Err(anyhow!("Index {index} is out of bounds for {} bytes", buf.len()));
+            }
+            buf.insert(index, byte);
+            return Ok(());
+        }
+
+        match self {
+            Self::ArrayProperty { values, .. } | Self::SetProperty { values, .. } => {
+                if index > values.len() {
+                    return Err(anyhow!("Index {index} is out of bounds for {} values", values.len()));
+                }
+                values.insert(index, value);
+                Ok(())
+            }
+            _ => Err(anyhow!("Cannot insert an element into a property that isn't an array or set")),
+        }
+    }
+    
+    fn get_key(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::StructProperty(props) => props.iter().find_map(|p| if p.name == key {
+                p.body.as_ref().map(|b| &b.value)
+            } else {
                 None
             }),
             Self::CustomStructProperty(s) => s.properties.iter().find_map(|p| if p.name == key {
@@ -572,7 +1455,7 @@ impl Indexable for PropertyValue {
             } else {
                 None
             }),
-            Self::MapProperty { values, .. } => values.iter().find_map(|(k, v)| (k == key).then_some(v)),
+            Self::MapProperty { values, .. } => values.iter().find_map(|(k, v)| map_key_matches(k, key).then_some(v)),
             _ => None,
         }
     }
@@ -589,14 +1472,16 @@ impl Indexable for PropertyValue {
             } else {
                 None
             }),
-            Self::MapProperty { values, .. } => values.iter_mut().find_map(|(k, v)| (k == key).then_some(v)),
+            Self::MapProperty { values, .. } => values.iter_mut().find_map(|(k, v)| map_key_matches(k, key).then_some(v)),
             _ => None,
         }
     }
 
     fn get_index(&self, index: usize) -> Option<&Self> {
         match self {
-            Self::ArrayProperty { values, .. } => values.get(index),
+            // the bytes of a byte array aren't stored as values we can return
+            Self::ArrayProperty { values } if is_byte_array(values) => None,
+            Self::ArrayProperty { values, .. } | Self::SetProperty { values, .. } => values.get(index),
             Self::MapProperty { values, .. } => values.iter().find_map(|(k, v)| (*k == index).then_some(v)),
             _ => None,
         }
@@ -604,17 +1489,89 @@ impl Indexable for PropertyValue {
 
     fn get_index_mut(&mut self, index: usize) -> Option<&mut Self> {
         match self {
-            Self::ArrayProperty { values, .. } => values.get_mut(index),
+            Self::ArrayProperty { values } if is_byte_array(values) => None,
+            Self::ArrayProperty { values, .. } | Self::SetProperty { values, .. } => values.get_mut(index),
             Self::MapProperty { values, .. } => values.iter_mut().find_map(|(k, v)| (*k == index).then_some(v)),
             _ => None,
         }
     }
+
+    fn remove_key(&mut self, key: &str) -> Option<Self> {
+        match self {
+            Self::StructProperty(props) => remove_from_properties(props, key),
+            Self::CustomStructProperty(s) => remove_from_properties(&mut s.properties, key),
+            Self::MapProperty { values, .. } => {
+                let position = values.iter().position(|(k, _)| map_key_matches(k, key))?;
+                Some(values.remove(position).1)
+            }
+            _ => None,
+        }
+    }
+
+    fn remove_index(&mut self, index: usize) -> Option<Self> {
+        if let Self::ArrayProperty { values } = self && let Some(buf) = byte_array_mut(values) {
+            return (index < buf.len()).then(|| Self::ByteProperty(buf.remove(index)));
+        }
+
+        match self {
+            Self::ArrayProperty { values, .. } | Self::SetProperty { values, .. } => {
+                (index < values.len()).then(|| values.remove(index))
+            }
+            Self::MapProperty { values, .. } => {
+                let position = values.iter().position(|(k, _)| *k == index)?;
+                Some(values.remove(position).1)
+            }
+            _ => None,
+        }
+    }
+
+    fn rename_key(&mut self, key: &str, new_key: &str) -> Result<()> {
+        match self {
+            Self::StructProperty(props) => rename_in_properties(props, key, new_key),
+            Self::CustomStructProperty(s) => rename_in_properties(&mut s.properties, key, new_key),
+            Self::MapProperty { values, .. } => {
+                if key != new_key && values.iter().any(|(k, _)| map_key_matches(k, new_key)) {
+                    return Err(anyhow!("Map already contains the key {new_key}"));
+                }
+                let (k, _) = values.iter_mut().find(|(k, _)| map_key_matches(k, key)).ok_or_else(|| anyhow!("Map doesn't contain the key {key}"))?;
+                k.set_from_str(new_key)
+            }
+            _ => Err(anyhow!("Cannot rename a key of a property that isn't a struct or map")),
+        }
+    }
+
+    fn children(&self) -> Vec<(ChildKey<'_>, &Self)> {
+        match self {
+            Self::StructProperty(props) => property_children(props),
+            Self::CustomStructProperty(s) => property_children(&s.properties),
+            Self::ArrayProperty { values, .. } | Self::SetProperty { values, .. } => {
+                values.iter().enumerate().map(|(i, v)| (ChildKey::Index(i), v)).collect()
+            }
+            Self::MapProperty { values, .. } => values.iter().map(|(k, v)| (ChildKey::MapKey(k), v)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<()> {
+        if let Self::ArrayProperty { values } = self && let Some(buf) = byte_array_mut(values) {
+            return move_in_list(buf, from, to);
+        }
+
+        match self {
+            Self::StructProperty(props) => move_in_properties(props, from, to),
+            Self::CustomStructProperty(s) => move_in_properties(&mut s.properties, from, to),
+            Self::ArrayProperty { values, .. } | Self::SetProperty { values, .. } => move_in_list(values, from, to),
+            Self::MapProperty { values, .. } => move_in_list(values, from, to),
+            _ => Err(anyhow!("Property has no children to move")),
+        }
+    }
 }
 
 impl PartialEq<str> for PropertyValue {
     fn eq(&self, other: &str) -> bool {
         match self {
             Self::StrProperty(s) | Self::EnumProperty(s) | Self::NameProperty(s) | Self::ObjectProperty(s) => s == other,
+            Self::ByteEnumProperty(value) => value == other,
             _ => false,
         }
     }
@@ -637,6 +1594,12 @@ impl PartialEq<i32> for PropertyValue {
         match self {
             Self::IntProperty(i) => *i == *other,
             Self::ByteProperty(b) => *b as i32 == *other,
+            Self::Int8Property(i) => i32::from(*i) == *other,
+            Self::Int16Property(i) => i32::from(*i) == *other,
+            Self::Int64Property(i) => *i == i64::from(*other),
+            Self::UInt16Property(i) => i32::from(*i) == *other,
+            Self::UInt32Property(i) => i64::from(*i) == i64::from(*other),
+            Self::UInt64Property(i) => i64::try_from(*i).is_ok_and(|i| i == i64::from(*other)),
             _ => false,
         }
     }
@@ -651,17 +1614,20 @@ impl PartialEq<usize> for PropertyValue {
                 *i as usize == *other
             },
             Self::ByteProperty(b) => *b as usize == *other,
+            Self::Int8Property(i) => usize::try_from(*i).is_ok_and(|i| i == *other),
+            Self::Int16Property(i) => usize::try_from(*i).is_ok_and(|i| i == *other),
+            Self::Int64Property(i) => usize::try_from(*i).is_ok_and(|i| i == *other),
+            Self::UInt16Property(i) => *i as usize == *other,
+            Self::UInt32Property(i) => *i as usize == *other,
+            Self::UInt64Property(i) => usize::try_from(*i).is_ok_and(|i| i == *other),
             _ => false,
         }
     }
 }
 
-impl BinRead for PropertyValue {
-    type Args<'a> = PropertyValueArgs<'a>;
-
-    fn read_options<R: Read + Seek>(reader: &mut R, endian: Endian, args: Self::Args<'_>) -> BinResult<Self> {
+impl PropertyValue {
+    fn read_value<R: Read + Seek>(reader: &mut R, endian: Endian, args: PropertyValueArgs, start: u64) -> BinResult<Self> {
         let type_name = args.property_type.name.as_str();
-        let start = reader.stream_position()?;
         let end = start + args.data_size as u64;
 
         let value = match type_name {
@@ -676,10 +1642,10 @@ impl BinRead for PropertyValue {
             "ByteProperty" => {
                 if args.data_size == 1 {
                     Self::ByteProperty(u8::read_options(reader, endian, ())?)
-                } else if !args.property_type.tags.is_empty() && let Ok(s) = FString::read_options(reader, endian, ()) {
+                } else if args.property_type.enum_type().is_some() && let Ok(value) = FString::read_options(reader, endian, ()) {
                     // it looks like sometimes enum values are recorded as ByteProperty? so if we have tags
                     // and data_size != 1, see if we can parse as an enum value
-                    Self::EnumProperty(s)
+                    Self::ByteEnumProperty(value)
                 } else {
                     // reset stream position in case enum value parse failed
                     reader.seek(SeekFrom::Start(start))?;
@@ -689,6 +1655,12 @@ impl BinRead for PropertyValue {
                 }
             }
             "IntProperty" => Self::IntProperty(i32::read_options(reader, endian, ())?),
+            "Int8Property" => Self::Int8Property(i8::read_options(reader, endian, ())?),
+            "Int16Property" => Self::Int16Property(i16::read_options(reader, endian, ())?),
+            "Int64Property" => Self::Int64Property(i64::read_options(reader, endian, ())?),
+            "UInt16Property" => Self::UInt16Property(u16::read_options(reader, endian, ())?),
+            "UInt32Property" => Self::UInt32Property(u32::read_options(reader, endian, ())?),
+            "UInt64Property" => Self::UInt64Property(u64::read_options(reader, endian, ())?),
             "FloatProperty" => Self::FloatProperty(f32::read_options(reader, endian, ())?),
             "DoubleProperty" => Self::DoubleProperty(f64::read_options(reader, endian, ())?),
             "TextProperty" => {
@@ -700,18 +1672,14 @@ impl BinRead for PropertyValue {
             "EnumProperty" => Self::EnumProperty(FString::read_options(reader, endian, ())?),
             "NameProperty" => Self::NameProperty(FString::read_options(reader, endian, ())?),
             "ObjectProperty" => Self::ObjectProperty(FString::read_options(reader, endian, ())?),
+            "SoftObjectProperty" => Self::SoftObjectProperty(SoftObjectPath::read_options(reader, endian, ())?),
+            "SoftClassProperty" => Self::SoftClassProperty(SoftObjectPath::read_options(reader, endian, ())?),
             "StructProperty" => {
                 // non-zero flags (or possibly just 08) seems to indicate types that don't have explicit field descriptions
                 if args.flags != 0 {
                     let description = args.property_type.describe();
                     if description == GAMEPLAY_TAG_CONTAINER_TYPE {
-                        // data is effectively an ArrayProperty[NameProperty]
-                        let count = u32::read_options(reader, endian, ())? as usize;
-                        let mut values = Vec::with_capacity(count);
-                        for _ in 0..count {
-                            values.push(Self::NameProperty(FString::read_options(reader, endian, ())?));
-                        }
-                        Self::ArrayProperty { values }
+                        Self::GameplayTagContainerProperty(GameplayTagContainer::read_options(reader, endian, ())?)
                     } else if description.starts_with(CORE_UOBJECT_TYPE_PREFIX) {
                         // unwrap is safe because there must be a tag if the description matched the prefix
                         let type_name = args.property_type.tags.first().unwrap().value.as_str();
@@ -733,11 +1701,30 @@ impl BinRead for PropertyValue {
 
                     let mut custom_struct_footer_size = None;
                     while reader.stream_position()? < end {
-                        let mut prop = Property::read_options(reader, endian, ())?;
+                        let mut prop = Property::read_options(reader, endian, args.options.clone())?;
                         if custom_struct_footer_size.is_none() {
                             custom_struct_footer_size = prop.custom_struct_footer_size();
+                            if custom_struct_footer_size.is_none() && args.options.detects_custom_structs() {
+                                prop.detect_custom_struct_data(&args.options);
+                            }
                         } else if let Some(footer_size) = custom_struct_footer_size && prop.is_custom_struct_data() {
-                            prop.parse_custom_struct_data(footer_size)?;
+                            // the custom struct is parsed from a separate buffer, so errors need to be adjusted
+                            // to point to the right place in the file
+                            let data_size = prop.body.as_ref().and_then(|b| b.value.array_len()).unwrap_or(0);
+                            let data_start = reader.stream_position()? - data_size as u64;
+                            let mark = args.options.mark();
+                            match prop.parse_custom_struct_data(footer_size, args.options.clone()) {
+                                Ok(()) => {
+                                    args.options.offset_since(mark, data_start);
+                                    args.options.prefix_since(mark, prop.name.as_str());
+                                }
+                                Err(err) => {
+                                    // in lenient mode, the data is left as a byte array
+                                    let mut parse_error = ParseError::from_binrw(&err, 0, Some(data_size as u32), prop.body.as_ref().map(|b| b.property_type.describe()));
+                                    parse_error.offset += data_start;
+                                    args.options.quarantine(mark, parse_error.prefixed(prop.name.as_str())).map_err(ParseError::into_binrw)?;
+                                }
+                            }
                         }
                         let is_none = prop.is_none();
                         props.push(prop);
@@ -761,10 +1748,14 @@ impl BinRead for PropertyValue {
                     Self::ArrayProperty { values: vec![Self::UnknownProperty(buf)] }
                 } else {
                     let mut values = Vec::with_capacity(count);
-                    for _ in 0..count {
+                    for i in 0..count {
                         let current = reader.stream_position()?;
                         let remaining_size = (end - current) as u32;
-                        values.push(PropertyValue::read_options(reader, endian, PropertyValueArgs::new(&element_type, args.flags, remaining_size))?);
+                        let mark = args.options.mark();
+                        let element_args = PropertyValueArgs::new(&element_type, args.flags, remaining_size, args.options.clone());
+                        let segment = format!("[{i}]");
+                        values.push(PropertyValue::read_options(reader, endian, element_args).map_err(|e| prefix_parse_error(e, &segment))?);
+                        args.options.prefix_since(mark, &segment);
                     }
                     Self::ArrayProperty { values }
                 }
@@ -779,21 +1770,41 @@ impl BinRead for PropertyValue {
                 let removed_count = u32::read_options(reader, endian, ())?;
                 let count = u32::read_options(reader, endian, ())? as usize;
                 let mut values = Vec::with_capacity(count);
-                for _ in 0..count {
+                for i in 0..count {
+                    let mark = args.options.mark();
+                    let segment = format!("[{i}]");
+
                     let current = reader.stream_position()?;
                     let remaining_size = (end - current) as u32;
-                    let args = PropertyValueArgs::new(&key_type, flags, remaining_size);
-                    let key = PropertyValue::read_options(reader, endian, args)?;
+                    let key_args = PropertyValueArgs::new(&key_type, flags, remaining_size, args.options.clone());
+                    let key = PropertyValue::read_options(reader, endian, key_args).map_err(|e| prefix_parse_error(e, &segment))?;
 
                     let current = reader.stream_position()?;
                     let remaining_size = (end - current) as u32;
-                    let args = PropertyValueArgs::new(&value_type, flags, remaining_size);
-                    let value = PropertyValue::read_options(reader, endian, args)?;
+                    let value_args = PropertyValueArgs::new(&value_type, flags, remaining_size, args.options.clone());
+                    let value = PropertyValue::read_options(reader, endian, value_args).map_err(|e| prefix_parse_error(e, &segment))?;
 
+                    args.options.prefix_since(mark, &segment);
                     values.push((key, value));
                 }
                 Self::MapProperty { removed_count, values }
             }
+            "SetProperty" => {
+                let element_type = args.property_type.element_type().into_owned();
+                let removed_count = u32::read_options(reader, endian, ())?;
+                let count = u32::read_options(reader, endian, ())? as usize;
+                let mut values = Vec::with_capacity(count);
+                for i in 0..count {
+                    let current = reader.stream_position()?;
+                    let remaining_size = (end - current) as u32;
+                    let mark = args.options.mark();
+                    let element_args = PropertyValueArgs::new(&element_type, args.flags, remaining_size, args.options.clone());
+                    let segment = format!("[{i}]");
+                    values.push(PropertyValue::read_options(reader, endian, element_args).map_err(|e| prefix_parse_error(e, &segment))?);
+                    args.options.prefix_since(mark, &segment);
+                }
+                Self::SetProperty { removed_count, values }
+            }
             _ => {
                 let mut buf = vec![0u8; args.data_size as usize];
                 reader.read_exact(&mut buf)?;
@@ -810,11 +1821,57 @@ impl BinRead for PropertyValue {
     }
 }
 
+impl BinRead for PropertyValue {
+    type Args<'a> = PropertyValueArgs<'a>;
+
+    fn read_options<R: Read + Seek>(reader: &mut R, endian: Endian, args: Self::Args<'_>) -> BinResult<Self> {
+        let start = reader.stream_position()?;
+        Self::read_value(reader, endian, args.clone(), start).map_err(|err| {
+            ParseError::from_binrw(&err, start, Some(args.data_size), Some(args.property_type.describe())).into_binrw()
+        })
+    }
+}
+
+/// Reads the value of a property, quarantining it as raw bytes if it fails to parse in lenient mode
+fn read_body_value<R: Read + Seek>(reader: &mut R, endian: Endian, args: PropertyValueArgs) -> BinResult<PropertyValue> {
+    let start = reader.stream_position()?;
+    let mark = args.options.mark();
+    let err = match PropertyValue::read_options(reader, endian, args.clone()) {
+        Ok(value) => return Ok(value),
+        Err(err) if !args.options.is_lenient() => return Err(err),
+        Err(err) => err,
+    };
+
+    // if we can't even read the declared number of bytes, there's nothing to recover
+    reader.seek(SeekFrom::Start(start))?;
+    let mut buf = vec![0u8; args.data_size as usize];
+    if reader.read_exact(&mut buf).is_err() {
+        return Err(err);
+    }
+
+    // the error is recorded against the value being quarantined, which may be a parent of where the error occurred
+    let inner = ParseError::from_binrw(&err, start, Some(args.data_size), Some(args.property_type.describe()));
+    let parse_error = if inner.path.is_empty() {
+        inner
+    } else {
+        ParseError {
+            path: String::new(),
+            offset: start,
+            data_size: Some(args.data_size),
+            type_description: Some(args.property_type.describe()),
+            message: inner.to_string(),
+            is_eof: inner.is_eof,
+        }
+    };
+    args.options.quarantine(mark, parse_error).map_err(ParseError::into_binrw)?;
+    Ok(PropertyValue::UnknownProperty(buf))
+}
+
 /// A string associated with a property type
 ///
 /// Type tags typically encode the names and namespaces of inner or backing types
 #[binrw]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TypeTag {
     pub kind: u32,
     pub value: FString,
@@ -879,7 +1936,7 @@ fn num_inner_types(name: &str, tags: &[TypeTag]) -> usize {
                 _ => 1,
             }
         }
-        "ArrayProperty" => {
+        "ArrayProperty" | "SetProperty" => {
             match tags.first() {
                 Some(tag) if tag.value == "EnumProperty" => 1,
                 Some(tag) if tag.value == "MapProperty" => {
@@ -897,7 +1954,7 @@ fn num_inner_types(name: &str, tags: &[TypeTag]) -> usize {
 
 /// A description of the type of a property
 #[binrw]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PropertyType {
     pub name: FString,
     #[br(parse_with = read_tags)]
@@ -945,6 +2002,12 @@ impl PropertyType {
                 Self::describe_by_name(desc, inner_type, &tags[1..], inner_types);
                 desc.push(']');
             }
+            "SetProperty" => {
+                desc.push('{');
+                let inner_type = tags.first().unwrap().value.as_str();
+                Self::describe_by_name(desc, inner_type, &tags[1..], inner_types);
+                desc.push('}');
+            }
             "MapProperty" if !inner_types.is_empty() => {
                 desc.push('<');
 
@@ -962,6 +2025,22 @@ impl PropertyType {
         }
     }
 
+    /// Returns the full name of the enum type of an enum or byte property, e.g. `/Script/GameNoce.ENoceActionLevel`
+    ///
+    /// Returns `None` for other types and for byte properties that aren't enums.
+    pub fn enum_type(&self) -> Option<String> {
+        match self.name.as_str() {
+            "EnumProperty" | "ByteProperty" => {
+                let enum_name = self.tags.first()?;
+                Some(match self.tags.get(1) {
+                    Some(namespace) => format!("{}.{}", namespace.value, enum_name.value),
+                    None => enum_name.value.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
     /// Returns a string describing the type
     pub fn describe(&self) -> String {
         let mut desc = String::new();
@@ -978,7 +2057,7 @@ impl PropertyType {
     /// Returns the type of the elements contained within this type if applicable, otherwise returns the type itself
     pub fn element_type(&self) -> Cow<'_, Self> {
         match self.name.as_str() {
-            "ArrayProperty" | "MapProperty" if !self.tags.is_empty() => {
+            "ArrayProperty" | "MapProperty" | "SetProperty" if !self.tags.is_empty() => {
                 let name = self.tags[0].value.clone();
                 let tags = self.tags[1..].to_vec();
                 let inner_types = if name == "EnumProperty" && let Some(inner_type) = self.inner_types.first() {
@@ -988,7 +2067,6 @@ impl PropertyType {
                 };
                 Cow::Owned(Self { name, tags, inner_types })
             }
-            "StructProperty" if self.describe() == GAMEPLAY_TAG_CONTAINER_TYPE => Cow::Owned(Self { name: FString::from_str("NameProperty"), tags: Vec::new(), inner_types: Vec::new() }),
             _ => Cow::Borrowed(self),
         }
     }
@@ -999,17 +2077,25 @@ impl PropertyType {
             "BoolProperty" => PropertyValue::BoolProperty(Some(false)),
             "ByteProperty" => PropertyValue::ByteProperty(0),
             "IntProperty" => PropertyValue::IntProperty(0),
+            "Int8Property" => PropertyValue::Int8Property(0),
+            "Int16Property" => PropertyValue::Int16Property(0),
+            "Int64Property" => PropertyValue::Int64Property(0),
+            "UInt16Property" => PropertyValue::UInt16Property(0),
+            "UInt32Property" => PropertyValue::UInt32Property(0),
+            "UInt64Property" => PropertyValue::UInt64Property(0),
             "FloatProperty" => PropertyValue::FloatProperty(0.0),
             "DoubleProperty" => PropertyValue::DoubleProperty(0.0),
             "StrProperty" => PropertyValue::StrProperty(FString::new()),
             "NameProperty" => PropertyValue::NameProperty(FString::new()),
             "ObjectProperty" => PropertyValue::ObjectProperty(FString::new()),
+            "SoftObjectProperty" => PropertyValue::SoftObjectProperty(SoftObjectPath::default()),
+            "SoftClassProperty" => PropertyValue::SoftClassProperty(SoftObjectPath::default()),
             "EnumProperty" => PropertyValue::EnumProperty(FString::new()),
             "TextProperty" => PropertyValue::TextProperty { flags: TextFlags::empty(), data: TextData::None { values: Vec::new() } },
             "StructProperty" => {
                 let description = self.describe();
                 if description == GAMEPLAY_TAG_CONTAINER_TYPE {
-                    PropertyValue::ArrayProperty { values: Vec::new() }
+                    PropertyValue::GameplayTagContainerProperty(GameplayTagContainer::default())
                 } else if description.starts_with(CORE_UOBJECT_TYPE_PREFIX) {
                     // unwrap is safe because there must be a tag if the description matched the prefix
                     let type_name = self.tags.first().unwrap().value.as_str();
@@ -1025,6 +2111,7 @@ impl PropertyType {
             }
             "ArrayProperty" => PropertyValue::ArrayProperty { values: Vec::new() },
             "MapProperty" => PropertyValue::MapProperty { removed_count: 0, values: Vec::new() },
+            "SetProperty" => PropertyValue::SetProperty { removed_count: 0, values: Vec::new() },
             _ => PropertyValue::UnknownProperty(Vec::new()),
         }
     }
@@ -1032,13 +2119,14 @@ impl PropertyType {
 
 /// The body of a property, containing the type and value
 #[binrw]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[br(import(options: ParseOptions))]
 pub struct PropertyBody {
     pub property_type: PropertyType,
     #[bw(calc = value.size() as u32)]
     data_size: u32,
     pub flags: u8,
-    #[br(args_raw(PropertyValueArgs::new(&property_type, flags, data_size)))]
+    #[br(parse_with = read_body_value, args_raw(PropertyValueArgs::new(&property_type, flags, data_size, options)))]
     pub value: PropertyValue,
 }
 
@@ -1066,7 +2154,7 @@ impl PropertyBody {
         self.property_type.size() + 4 + 1 + self.value.size()
     }
 
-    fn parse_custom_struct(&mut self, footer_size: usize) -> BinResult<()> {
+    fn parse_custom_struct(&mut self, footer_size: usize, options: ParseOptions) -> BinResult<()> {
         let custom_struct: CustomStruct = {
             let PropertyValue::ArrayProperty { values } = &self.value else {
                 return Ok(());
@@ -1076,7 +2164,7 @@ impl PropertyBody {
             };
 
             let mut reader = Cursor::new(data);
-            reader.read_le_args((footer_size,))?
+            reader.read_le_args((footer_size, options))?
         };
 
         self.value = PropertyValue::CustomStructProperty(custom_struct);
@@ -1104,17 +2192,68 @@ impl Indexable for PropertyBody {
     fn get_index_mut(&mut self, index: usize) -> Option<&mut PropertyValue> {
         self.value.get_index_mut(index)
     }
+
+    fn insert_property(&mut self, position: usize, property: Property) -> Result<()> {
+        self.value.insert_property(position, property)
+    }
+
+    fn insert_index(&mut self, index: usize, value: PropertyValue) -> Result<()> {
+        self.value.insert_index(index, value)
+    }
+
+    fn remove_key(&mut self, key: &str) -> Option<PropertyValue> {
+        self.value.remove_key(key)
+    }
+
+    fn remove_index(&mut self, index: usize) -> Option<PropertyValue> {
+        self.value.remove_index(index)
+    }
+
+    fn rename_key(&mut self, key: &str, new_key: &str) -> Result<()> {
+        self.value.rename_key(key, new_key)
+    }
+
+    fn children(&self) -> Vec<(ChildKey<'_>, &PropertyValue)> {
+        self.value.children()
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<()> {
+        self.value.move_child(from, to)
+    }
 }
 
 /// An Unreal Engine 5 property
-#[binrw]
-#[derive(Debug)]
+#[binwrite]
+#[derive(Debug, Clone)]
 pub struct Property {
     pub name: FString,
-    #[br(if(name != "None" && name != ""))]
     pub body: Option<PropertyBody>,
 }
 
+impl BinRead for Property {
+    type Args<'a> = ParseOptions;
+
+    fn read_options<R: Read + Seek>(reader: &mut R, endian: Endian, options: Self::Args<'_>) -> BinResult<Self> {
+        let start = reader.stream_position()?;
+        let name = FString::read_options(reader, endian, ())
+            .map_err(|e| ParseError::from_binrw(&e, start, None, None).into_binrw())?;
+
+        let body = if name != "None" && !name.is_empty() {
+            let body_start = reader.stream_position()?;
+            let mark = options.mark();
+            let body = PropertyBody::read_options(reader, endian, (options.clone(),)).map_err(|e| {
+                ParseError::from_binrw(&e, body_start, None, None).prefixed(name.as_str()).into_binrw()
+            })?;
+            options.prefix_since(mark, name.as_str());
+            Some(body)
+        } else {
+            None
+        };
+
+        Ok(Self { name, body })
+    }
+}
+
 impl Property {
     /// Creates a new enum property with the given name and value
     pub fn new_enum(name: &str, namespace: &str, enum_name: &str, value: &str) -> Self {
@@ -1139,10 +2278,14 @@ impl Property {
         self.body.is_none()
     }
 
+    fn body_mut(&mut self) -> Result<&mut PropertyBody> {
+        self.body.as_mut().ok_or_else(|| anyhow!("None property has no children"))
+    }
+
     fn custom_struct_footer_size(&self) -> Option<usize> {
         match (self.name.as_str(), self.body.as_ref().map(|b| &b.value)) {
             ("Class", Some(PropertyValue::ObjectProperty(s))) => {
-                CUSTOM_STRUCT_CLASSES.iter().find_map(|(class, footer_size)| (s == class).then_some(*footer_size))
+                registry().custom_struct_footer_size(s.as_str())
             }
             _ => None,
         }
@@ -1161,14 +2304,49 @@ impl Property {
         }
     }
 
-    fn parse_custom_struct_data(&mut self, footer_size: usize) -> BinResult<()> {
+    fn parse_custom_struct_data(&mut self, footer_size: usize, options: ParseOptions) -> BinResult<()> {
         if self.is_custom_struct_data() {
-            self.body.as_mut().unwrap().parse_custom_struct(footer_size)
+            self.body.as_mut().unwrap().parse_custom_struct(footer_size, options)
         } else {
             Ok(())
         }
     }
 
+    /// Tries to decode custom struct data of an unregistered class, leaving it as a byte array if that fails
+    ///
+    /// Each possible footer size is tried in turn, and the first that produces a struct ending in a `None` property and
+    /// serializing back to the original bytes is used.
+    fn detect_custom_struct_data(&mut self, options: &ParseOptions) {
+        if !self.is_custom_struct_data() {
+            return;
+        }
+
+        let body = self.body.as_mut().unwrap();
+        let PropertyValue::ArrayProperty { values } = &body.value else {
+            return;
+        };
+        let Some(PropertyValue::UnknownProperty(data)) = values.first() else {
+            return;
+        };
+
+        // a failed attempt shouldn't quarantine anything, so each one is parsed strictly
+        let options = ParseOptions { quarantine: None, detect_custom_structs: options.detect_custom_structs };
+        for footer_size in CUSTOM_STRUCT_FOOTER_SIZES {
+            let Ok(custom_struct) = Cursor::new(data).read_le_args::<CustomStruct>((footer_size, options.clone())) else {
+                continue;
+            };
+            if !custom_struct.properties.last().is_some_and(Property::is_none) {
+                continue;
+            }
+
+            let mut written = Vec::new();
+            if custom_struct.write_le(&mut Cursor::new(&mut written)).is_ok() && written.get(4..) == Some(data.as_slice()) {
+                body.value = PropertyValue::CustomStructProperty(custom_struct);
+                return;
+            }
+        }
+    }
+
     /// Returns the size of this property in bytes
     pub fn size(&self) -> usize {
         self.name.byte_size() + self.body.as_ref().map(PropertyBody::size).unwrap_or(0)
@@ -1177,11 +2355,7 @@ impl Property {
 
 impl Indexable for Property {
     fn add_property(&mut self, property: Property) -> Result<()> {
-        if let Some(body) = self.body.as_mut() {
-            body.add_property(property)
-        } else {
-            Err(anyhow!("Cannot add property to non-struct property"))
-        }
+        self.body_mut()?.add_property(property)
     }
     
     fn get_key(&self, name: &str) -> Option<&PropertyValue> {
@@ -1199,30 +2373,61 @@ impl Indexable for Property {
     fn get_index_mut(&mut self, index: usize) -> Option<&mut PropertyValue> {
         self.body.as_mut().and_then(|b| b.get_index_mut(index))
     }
+
+    fn insert_property(&mut self, position: usize, property: Property) -> Result<()> {
+        self.body_mut()?.insert_property(position, property)
+    }
+
+    fn insert_index(&mut self, index: usize, value: PropertyValue) -> Result<()> {
+        self.body_mut()?.insert_index(index, value)
+    }
+
+    fn remove_key(&mut self, key: &str) -> Option<PropertyValue> {
+        self.body.as_mut().and_then(|b| b.remove_key(key))
+    }
+
+    fn remove_index(&mut self, index: usize) -> Option<PropertyValue> {
+        self.body.as_mut().and_then(|b| b.remove_index(index))
+    }
+
+    fn rename_key(&mut self, key: &str, new_key: &str) -> Result<()> {
+        self.body_mut()?.rename_key(key, new_key)
+    }
+
+    fn children(&self) -> Vec<(ChildKey<'_>, &PropertyValue)> {
+        self.body.as_ref().map(PropertyBody::children).unwrap_or_default()
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<()> {
+        self.body_mut()?.move_child(from, to)
+    }
 }
 
 /// The main body of the save game after the header and custom entries
 #[binrw]
 #[derive(Debug)]
+#[br(import(options: ParseOptions))]
 pub struct SaveGameData {
     pub type_name: FString,
     pub flags: u8,
-    #[br(parse_with = |r, e, _: ()| read_properties_with_footer(r, e, (4,)))]
+    #[br(parse_with = |r, e, _: ()| read_properties_with_footer(r, e, (4, options.clone())))]
     pub properties: Vec<Property>,
     pub extra: u32,
 }
 
 impl Indexable for SaveGameData {
     fn add_property(&mut self, property: Property) -> Result<()> {
-        // if we have a None property at the end of our property list, which we should, then we
-        // should insert the new property before it
-        if !property.is_none() && self.properties.last().map(Property::is_none).unwrap_or(false) {
-            self.properties.insert(self.properties.len() - 1, property);
-        } else {
-            self.properties.push(property);
-        }
+        add_to_properties(&mut self.properties, property);
         Ok(())
     }
+
+    fn insert_property(&mut self, position: usize, property: Property) -> Result<()> {
+        insert_into_properties(&mut self.properties, position, property)
+    }
+
+    fn insert_index(&mut self, _index: usize, _value: PropertyValue) -> Result<()> {
+        Err(anyhow!("Cannot insert an element into the save data"))
+    }
     
     fn get_key(&self, name: &str) -> Option<&PropertyValue> {
         self.properties.iter().find_map(|p| if p.name == name {
@@ -1247,17 +2452,217 @@ impl Indexable for SaveGameData {
     fn get_index_mut(&mut self, _index: usize) -> Option<&mut PropertyValue> {
         None
     }
+
+    fn remove_key(&mut self, key: &str) -> Option<PropertyValue> {
+        remove_from_properties(&mut self.properties, key)
+    }
+
+    fn remove_index(&mut self, _index: usize) -> Option<PropertyValue> {
+        None
+    }
+
+    fn rename_key(&mut self, key: &str, new_key: &str) -> Result<()> {
+        rename_in_properties(&mut self.properties, key, new_key)
+    }
+
+    fn children(&self) -> Vec<(ChildKey<'_>, &PropertyValue)> {
+        property_children(&self.properties)
+    }
+
+    fn move_child(&mut self, from: usize, to: usize) -> Result<()> {
+        move_in_properties(&mut self.properties, from, to)
+    }
 }
 
 /// A Silent Hill f save game
 #[binrw]
 #[derive(Debug)]
+#[br(import(options: ParseOptions))]
 pub struct SaveGame {
     pub header: SaveGameHeader,
     pub custom_format_data: CustomFormatData,
+    #[br(args(options))]
     pub save_data: SaveGameData,
 }
 
+impl SaveGame {
+    /// Makes a gameplay save with the given properties, for tests
+    #[cfg(test)]
+    pub(crate) fn new_for_test(properties: Vec<Property>) -> Self {
+        Self {
+            header: SaveGameHeader {
+                save_game_version: 3,
+                package_version: (522, 1012),
+                engine_version: EngineVersion { major: 5, minor: 4, patch: 1, build: 0, build_id: FString::new() },
+            },
+            custom_format_data: CustomFormatData { version: 3, entries: Vec::new() },
+            save_data: SaveGameData {
+                type_name: FString::from_str("/Script/GameNoce.NoceSaveGame"),
+                flags: 0,
+                properties,
+                extra: 0,
+            },
+        }
+    }
+
+    /// Makes a component record like those in the player state's `ComponentRecords`, for tests
+    #[cfg(test)]
+    pub(crate) fn component_record_for_test(class: &str, data: PropertyBody) -> PropertyValue {
+        PropertyValue::StructProperty(vec![
+            Property::new_scalar("Class", PropertyValue::ObjectProperty(FString::from_str(class))),
+            Property { name: FString::from_str("Data"), body: Some(data) },
+            Property::new_none(),
+        ])
+    }
+
+    /// Reads a save game from a reader
+    ///
+    /// If a property fails to parse, the error is a [`ParseError`] describing where.
+    pub fn read_from<R: Read + Seek>(reader: &mut R) -> Result<Self> {
+        Self::read_le(reader).map_err(Self::convert_error)
+    }
+
+    /// Reads a save game from a reader in lenient mode
+    ///
+    /// Property values that fail to parse are kept as raw bytes instead of failing the whole parse. Returns the save
+    /// along with the errors for each value that was quarantined this way.
+    pub fn read_lenient<R: Read + Seek>(reader: &mut R) -> Result<(Self, Vec<ParseError>)> {
+        let options = ParseOptions::lenient();
+        let save = Self::read_with_options(reader, options.clone())?;
+        Ok((save, options.quarantined()))
+    }
+
+    /// Reads a save game from a reader with the given options
+    ///
+    /// If the options are lenient, the errors for values that were kept as raw bytes can be retrieved from them
+    /// afterwards with [`ParseOptions::quarantined`].
+    pub fn read_with_options<R: Read + Seek>(reader: &mut R, options: ParseOptions) -> Result<Self> {
+        Self::read_le_args(reader, (options,)).map_err(Self::convert_error)
+    }
+
+    fn convert_error(err: binrw::Error) -> anyhow::Error {
+        match err.custom_err::<ParseError>() {
+            Some(parse_error) => anyhow::Error::new(parse_error.clone()),
+            None => anyhow::Error::new(err),
+        }
+    }
+
+    /// Calls the given function with every list of properties in the save, including nested structs
+    fn for_each_property_list(&self, f: &mut impl FnMut(&[Property])) {
+        fn visit_properties(properties: &[Property], f: &mut impl FnMut(&[Property])) {
+            f(properties);
+            for body in properties.iter().filter_map(|p| p.body.as_ref()) {
+                visit_value(&body.value, f);
+            }
+        }
+
+        fn visit_value(value: &PropertyValue, f: &mut impl FnMut(&[Property])) {
+            match value {
+                PropertyValue::StructProperty(props) => visit_properties(props, f),
+                PropertyValue::CustomStructProperty(custom_struct) => visit_properties(&custom_struct.properties, f),
+                PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. } => {
+                    for value in values {
+                        visit_value(value, f);
+                    }
+                }
+                PropertyValue::MapProperty { values, .. } => {
+                    for (key, value) in values {
+                        visit_value(key, f);
+                        visit_value(value, f);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        visit_properties(&self.save_data.properties, f);
+    }
+
+    /// Calls `f` on every value in the save along with its type, including the keys and elements of arrays, sets and
+    /// maps
+    fn for_each_value(&self, f: &mut impl FnMut(&PropertyType, &PropertyValue)) {
+        fn visit_value(property_type: &PropertyType, value: &PropertyValue, f: &mut impl FnMut(&PropertyType, &PropertyValue)) {
+            f(property_type, value);
+            match value {
+                PropertyValue::StructProperty(props) => visit_properties(props, f),
+                PropertyValue::CustomStructProperty(custom_struct) => visit_properties(&custom_struct.properties, f),
+                PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. } => {
+                    let element_type = property_type.element_type();
+                    for value in values {
+                        visit_value(&element_type, value, f);
+                    }
+                }
+                PropertyValue::MapProperty { values, .. } => {
+                    let key_type = property_type.element_type();
+                    for (key, value) in values {
+                        visit_value(&key_type, key, f);
+                        // maps read from a save always have a value type, but ones built in code may not
+                        match property_type.inner_types.last() {
+                            Some(value_type) => visit_value(value_type, value, f),
+                            None => visit_value(&PropertyType::new_scalar(value.type_name()), value, f),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        fn visit_properties(properties: &[Property], f: &mut impl FnMut(&PropertyType, &PropertyValue)) {
+            for body in properties.iter().filter_map(|p| p.body.as_ref()) {
+                visit_value(&body.property_type, &body.value, f);
+            }
+        }
+
+        visit_properties(&self.save_data.properties, f);
+    }
+
+    /// Returns the classes of objects whose custom struct data was left as raw bytes, sorted and without duplicates
+    ///
+    /// This happens when the class isn't in the [`registry`](crate::registry) or its data couldn't be parsed.
+    pub fn undecoded_classes(&self) -> Vec<String> {
+        let mut classes = BTreeSet::new();
+        self.for_each_property_list(&mut |properties| {
+            let class = properties.iter().find_map(|p| match (p.name.as_str(), p.body.as_ref().map(|b| &b.value)) {
+                ("Class", Some(PropertyValue::ObjectProperty(class))) => Some(class),
+                _ => None,
+            });
+            if let Some(class) = class && properties.iter().any(Property::is_custom_struct_data) {
+                classes.insert(class.to_string());
+            }
+        });
+        classes.into_iter().collect()
+    }
+
+    /// Returns the values of each enum type used anywhere in the save, sorted and without duplicates
+    ///
+    /// Enum types are identified by their full name, as returned by [`PropertyType::enum_type`]. This includes enums in
+    /// arrays, sets and maps, not just those stored directly in a property.
+    pub fn enum_values(&self) -> BTreeMap<String, Vec<String>> {
+        let mut enums: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        self.for_each_value(&mut |property_type, value| {
+            if let PropertyValue::EnumProperty(value) | PropertyValue::ByteEnumProperty(value) = value
+                && let Some(enum_type) = property_type.enum_type()
+            {
+                enums.entry(enum_type).or_default().insert(value.to_string());
+            }
+        });
+        enums.into_iter().map(|(enum_type, values)| (enum_type, values.into_iter().collect())).collect()
+    }
+
+    /// Returns every gameplay tag used anywhere in the save, sorted and without duplicates
+    ///
+    /// This includes tag containers in arrays, sets and maps, not just those stored directly in a property.
+    pub fn gameplay_tags(&self) -> Vec<String> {
+        let mut tags = BTreeSet::new();
+        self.for_each_value(&mut |_, value| {
+            if let PropertyValue::GameplayTagContainerProperty(container) = value {
+                tags.extend(container.tags.iter().map(FString::to_string));
+            }
+        });
+        tags.into_iter().collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1291,4 +2696,589 @@ mod tests {
         writer.write_le(&fstr).unwrap();
         assert_eq!(data, b"\x0D\x00\x00\x00Hello World!\x00");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_fstring_unterminated() {
+        let data = b"\x00\x00\x00\x00\x01\x00\x00\x00\x00";
+        let mut reader = Cursor::new(data);
+        let unterminated: FString = reader.read_le().unwrap();
+        let terminated: FString = reader.read_le().unwrap();
+        assert!(unterminated.is_unterminated());
+        assert!(!terminated.is_unterminated());
+        assert_eq!(unterminated, terminated);
+        assert_eq!(unterminated.byte_size() + terminated.byte_size(), data.len());
+
+        let mut written = Vec::<u8>::new();
+        let mut writer = Cursor::new(&mut written);
+        writer.write_le(&unterminated).unwrap();
+        writer.write_le(&terminated).unwrap();
+        assert_eq!(written, data);
+    }
+
+    #[test]
+    fn test_text_history_roundtrip() {
+        let base = |source: &str| FText {
+            flags: TextFlags::empty(),
+            data: Box::new(TextData::Base { namespace: FString::new(), key: FString::from_str("key"), source_string: FString::from_str(source) }),
+        };
+        let data = TextData::OrderedFormat {
+            source_format: base("{0} costs {1}"),
+            arguments: vec![
+                FormatArgumentValue::Text(base("Omamori")),
+                FormatArgumentValue::Text(FText {
+                    flags: TextFlags::empty(),
+                    data: Box::new(TextData::AsCurrency {
+                        currency_code: FString::from_str("JPY"),
+                        number: FormatNumber {
+                            source_value: FormatArgumentValue::Int(500),
+                            options: Some(NumberFormattingOptions {
+                                always_sign: false,
+                                use_grouping: true,
+                                rounding_mode: 0,
+                                minimum_integral_digits: 1,
+                                maximum_integral_digits: 324,
+                                minimum_fractional_digits: 0,
+                                maximum_fractional_digits: 3,
+                            }),
+                            culture_name: FString::new_unterminated(),
+                        },
+                    }),
+                }),
+                FormatArgumentValue::Gender(1),
+            ],
+        };
+
+        let mut written = Vec::<u8>::new();
+        Cursor::new(&mut written).write_le(&data).unwrap();
+        assert_eq!(written.len(), data.size());
+
+        let read: TextData = Cursor::new(&written).read_le().unwrap();
+        let mut rewritten = Vec::<u8>::new();
+        Cursor::new(&mut rewritten).write_le(&read).unwrap();
+        assert_eq!(rewritten, written);
+    }
+
+    #[test]
+    fn test_text_history_conversion() {
+        let base = TextData::Base { namespace: FString::new(), key: FString::from_str("key"), source_string: FString::from_str("Hello") };
+
+        let none = base.converted("None").unwrap();
+        assert!(matches!(&none, TextData::None { values } if values.len() == 1 && values[0] == "Hello"));
+        assert!(matches!(none.converted("Base").unwrap(), TextData::Base { source_string, .. } if source_string == "Hello"));
+
+        let ordered = base.converted("OrderedFormat").unwrap();
+        assert_eq!(ordered.source_string().unwrap(), "Hello");
+        let TextData::OrderedFormat { source_format, mut arguments } = ordered else { unreachable!() };
+        arguments.push(FormatArgumentValue::Int(5));
+        let named = TextData::OrderedFormat { source_format, arguments }.converted("NamedFormat").unwrap();
+        assert!(matches!(&named, TextData::NamedFormat { arguments, .. } if arguments[0].0 == "0"));
+
+        let number = TextData::AsNumber { number: FormatNumber { source_value: FormatArgumentValue::Int(7), ..Default::default() } };
+        let currency = number.converted("AsCurrency").unwrap();
+        assert!(matches!(currency, TextData::AsCurrency { number: FormatNumber { source_value: FormatArgumentValue::Int(7), .. }, .. }));
+        assert!(base.converted("Unknown").is_none());
+    }
+
+    #[test]
+    fn test_parse_error_path() {
+        let property = Property::new_scalar("Outer", PropertyValue::StructProperty(vec![
+            Property::new_scalar("Inner", PropertyValue::IntProperty(1)),
+            Property::new_none(),
+        ]));
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&property).unwrap();
+
+        // cut off the inner property partway through its value
+        let inner_end = data.len() - 4 - "None".len() - 1;
+        data.truncate(inner_end - 2);
+        let err = Cursor::new(&data).read_le::<Property>().unwrap_err();
+        let parse_error = err.custom_err::<ParseError>().unwrap();
+        assert_eq!(parse_error.path, "Outer.Inner");
+        assert_eq!(parse_error.offset, (inner_end - 4) as u64);
+        assert_eq!(parse_error.data_size, Some(4));
+        assert!(parse_error.is_eof);
+    }
+
+    #[test]
+    fn test_lenient_quarantine() {
+        let outer = Property::new_scalar("Outer", PropertyValue::StructProperty(vec![
+            Property::new_scalar("Bad", PropertyValue::IntProperty(2)),
+            Property::new_scalar("Good", PropertyValue::IntProperty(3)),
+            Property::new_none(),
+        ]));
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&outer).unwrap();
+
+        // shrink Bad's declared size to 2 bytes so the int value overruns it
+        let outer_body = outer.body.as_ref().unwrap();
+        let outer_size_offset = outer.name.byte_size() + outer_body.property_type.size();
+        let PropertyValue::StructProperty(ref inner) = outer_body.value else { unreachable!() };
+        let bad_body = inner[0].body.as_ref().unwrap();
+        let bad_size_offset = outer_size_offset + 5 + inner[0].name.byte_size() + bad_body.property_type.size();
+        data[outer_size_offset..outer_size_offset + 4].copy_from_slice(&(outer_body.value.size() as u32 - 2).to_le_bytes());
+        data[bad_size_offset..bad_size_offset + 4].copy_from_slice(&2u32.to_le_bytes());
+        data.drain(bad_size_offset + 5..bad_size_offset + 7);
+
+        let err = Cursor::new(&data).read_le::<Property>().unwrap_err();
+        assert_eq!(err.custom_err::<ParseError>().unwrap().path, "Outer.Bad");
+
+        let options = ParseOptions::lenient();
+        let property = Cursor::new(&data).read_le_args::<Property>(options.clone()).unwrap();
+        let quarantined = options.quarantined();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].path, "Outer.Bad");
+        assert_eq!(quarantined[0].offset, (bad_size_offset + 5) as u64);
+        assert!(matches!(property.get_key("Bad"), Some(PropertyValue::UnknownProperty(bytes)) if bytes.len() == 2));
+        assert!(matches!(property.get_key("Good"), Some(PropertyValue::IntProperty(3))));
+
+        // quarantined values are written back unchanged
+        let mut written = Vec::<u8>::new();
+        Cursor::new(&mut written).write_le(&property).unwrap();
+        assert_eq!(written, data);
+    }
+
+    #[test]
+    fn test_set_property_roundtrip() {
+        let property = Property {
+            name: FString::from_str("Unlocked"),
+            body: Some(PropertyBody {
+                property_type: PropertyType {
+                    name: FString::from_str("SetProperty"),
+                    tags: vec![TypeTag::new(1, "NameProperty")],
+                    inner_types: Vec::new(),
+                },
+                flags: 0,
+                value: PropertyValue::SetProperty {
+                    removed_count: 1,
+                    values: vec![
+                        PropertyValue::NameProperty(FString::from_str("Key_A")),
+                        PropertyValue::NameProperty(FString::from_str("Key_B")),
+                    ],
+                },
+            }),
+        };
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&property).unwrap();
+
+        let read = Cursor::new(&data).read_le::<Property>().unwrap();
+        let body = read.body.as_ref().unwrap();
+        assert_eq!(body.property_type.describe(), "SetProperty{NameProperty}");
+        assert!(matches!(&body.value, PropertyValue::SetProperty { removed_count: 1, values } if values.len() == 2));
+        assert!(matches!(read.get_index(1), Some(PropertyValue::NameProperty(name)) if name == "Key_B"));
+    }
+
+    #[test]
+    fn test_integer_property_roundtrip() {
+        let properties = vec![
+            Property::new_scalar("Int8", PropertyValue::Int8Property(-5)),
+            Property::new_scalar("Int16", PropertyValue::Int16Property(-300)),
+            Property::new_scalar("Int64", PropertyValue::Int64Property(-5_000_000_000)),
+            Property::new_scalar("UInt16", PropertyValue::UInt16Property(60_000)),
+            Property::new_scalar("UInt32", PropertyValue::UInt32Property(4_000_000_000)),
+            Property::new_scalar("UInt64", PropertyValue::UInt64Property(10_000_000_000)),
+            Property::new_none(),
+        ];
+        let mut data = Vec::<u8>::new();
+        let mut writer = Cursor::new(&mut data);
+        for property in &properties {
+            writer.write_le(property).unwrap();
+        }
+
+        let mut reader = Cursor::new(&data);
+        let read: Vec<_> = properties.iter().map(|_| reader.read_le::<Property>().unwrap()).collect();
+        assert_eq!(reader.position() as usize, data.len());
+        assert!(matches!(read[0].body.as_ref().map(|b| &b.value), Some(PropertyValue::Int8Property(-5))));
+        assert!(matches!(read[1].body.as_ref().map(|b| &b.value), Some(PropertyValue::Int16Property(-300))));
+        assert!(matches!(read[2].body.as_ref().map(|b| &b.value), Some(PropertyValue::Int64Property(-5_000_000_000))));
+        assert!(matches!(read[3].body.as_ref().map(|b| &b.value), Some(PropertyValue::UInt16Property(60_000))));
+        assert!(matches!(read[4].body.as_ref().map(|b| &b.value), Some(PropertyValue::UInt32Property(4_000_000_000))));
+        assert!(matches!(read[5].body.as_ref().map(|b| &b.value), Some(PropertyValue::UInt64Property(10_000_000_000))));
+        assert!(read[6].is_none());
+
+        let mut rewritten = Vec::<u8>::new();
+        let mut writer = Cursor::new(&mut rewritten);
+        for property in &read {
+            writer.write_le(property).unwrap();
+        }
+        assert_eq!(rewritten, data);
+
+        assert_eq!(PropertyValue::Int8Property(-5), -5);
+        assert_eq!(PropertyValue::Int16Property(-300), -300);
+        assert_eq!(PropertyValue::UInt16Property(60_000), 60_000);
+        assert_ne!(PropertyValue::UInt32Property(u32::MAX), -1);
+    }
+
+    #[test]
+    fn test_soft_object_path_roundtrip() {
+        let level = SoftObjectPath {
+            package_name: FString::from_str("/Game/Maps/Village"),
+            asset_name: FString::from_str("Village"),
+            sub_path: FString::new_unterminated(),
+        };
+        let class = SoftObjectPath {
+            package_name: FString::from_str("/Game/Blueprints/BP_Enemy"),
+            asset_name: FString::from_str("BP_Enemy_C"),
+            sub_path: FString::from_str("Component"),
+        };
+        let empty = SoftObjectPath::default();
+
+        for path in [&level, &class, &empty] {
+            let mut data = Vec::<u8>::new();
+            Cursor::new(&mut data).write_le(path).unwrap();
+            assert_eq!(data.len(), path.size());
+            assert_eq!(Cursor::new(&data).read_le::<SoftObjectPath>().unwrap(), *path);
+        }
+
+        let properties = vec![
+            Property::new_scalar("Level", PropertyValue::SoftObjectProperty(level.clone())),
+            Property::new_scalar("Class", PropertyValue::SoftClassProperty(class.clone())),
+            Property::new_none(),
+        ];
+        let mut data = Vec::<u8>::new();
+        let mut writer = Cursor::new(&mut data);
+        for property in &properties {
+            if let Some(body) = &property.body {
+                let mut value_data = Vec::<u8>::new();
+                Cursor::new(&mut value_data).write_le(&body.value).unwrap();
+                assert_eq!(value_data.len(), body.value.size());
+            }
+            writer.write_le(property).unwrap();
+        }
+
+        let mut reader = Cursor::new(&data);
+        let read: Vec<_> = properties.iter().map(|_| reader.read_le::<Property>().unwrap()).collect();
+        assert_eq!(reader.position() as usize, data.len());
+        assert!(matches!(read[0].body.as_ref().map(|b| &b.value), Some(PropertyValue::SoftObjectProperty(path)) if *path == level));
+        assert!(matches!(read[1].body.as_ref().map(|b| &b.value), Some(PropertyValue::SoftClassProperty(path)) if *path == class));
+        assert!(read[2].is_none());
+
+        let mut rewritten = Vec::<u8>::new();
+        let mut writer = Cursor::new(&mut rewritten);
+        for property in &read {
+            writer.write_le(property).unwrap();
+        }
+        assert_eq!(rewritten, data);
+    }
+
+    /// Makes a component record of the given class whose custom struct data is stored as a byte array
+    fn make_component_record(name: &str, class: &str) -> Property {
+        let custom_struct = CustomStruct {
+            flags: 0,
+            properties: vec![Property::new_scalar("Count", PropertyValue::IntProperty(5)), Property::new_none()],
+            extra: vec![1, 2, 3, 4],
+        };
+        let mut custom_struct_data = Vec::<u8>::new();
+        Cursor::new(&mut custom_struct_data).write_le(&custom_struct).unwrap();
+        // the data size is part of the byte array rather than the data
+        custom_struct_data.drain(..4);
+
+        let data = PropertyBody {
+            property_type: PropertyType {
+                name: FString::from_str("ArrayProperty"),
+                tags: vec![TypeTag::new(1, "ByteProperty")],
+                inner_types: Vec::new(),
+            },
+            flags: 0,
+            value: PropertyValue::ArrayProperty { values: vec![PropertyValue::UnknownProperty(custom_struct_data)] },
+        };
+        Property::new_scalar(name, SaveGame::component_record_for_test(class, data))
+    }
+
+    #[test]
+    fn test_detect_custom_struct() {
+        let record = make_component_record("Record", "/Script/Test.UnknownComponent");
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&record).unwrap();
+
+        let read = Cursor::new(&data).read_le::<Property>().unwrap();
+        assert!(matches!(read.get_key("Data"), Some(PropertyValue::ArrayProperty { .. })));
+
+        let options = ParseOptions::default().with_custom_struct_detection(true);
+        let read = Cursor::new(&data).read_le_args::<Property>(options).unwrap();
+        let Some(PropertyValue::CustomStructProperty(detected)) = read.get_key("Data") else {
+            panic!("custom struct wasn't detected");
+        };
+        assert_eq!(detected.extra, [1, 2, 3, 4]);
+        assert!(matches!(read.get_key("Data").and_then(|d| d.get_key("Count")), Some(PropertyValue::IntProperty(5))));
+
+        let mut written = Vec::<u8>::new();
+        Cursor::new(&mut written).write_le(&read).unwrap();
+        assert_eq!(written, data);
+    }
+
+    #[test]
+    fn test_undecoded_classes() {
+        // a built-in class with the same footer size as the test data, so the global registry doesn't need to change
+        let save = SaveGame::new_for_test(vec![
+            make_component_record("Registered", "/Script/GameNoce.NocePickupsSubsystem"),
+            make_component_record("Unregistered", "/Script/Test.UnregisteredComponent"),
+            Property::new_none(),
+        ]);
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&save).unwrap();
+
+        let read = SaveGame::read_from(&mut Cursor::new(&data)).unwrap();
+        assert!(matches!(read.save_data.get_key("Registered").and_then(|r| r.get_key("Data")), Some(PropertyValue::CustomStructProperty(_))));
+        assert_eq!(read.undecoded_classes(), ["/Script/Test.UnregisteredComponent"]);
+    }
+
+    #[test]
+    fn test_gameplay_tag_container() {
+        let mut container = GameplayTagContainer::default();
+        assert!(container.add("Noce.Event.A"));
+        assert!(container.add("Noce.Event.B"));
+        assert!(!container.add("Noce.Event.A"));
+
+        let property = Property {
+            name: FString::from_str("Tags"),
+            body: Some(PropertyBody {
+                property_type: PropertyType {
+                    name: FString::from_str("StructProperty"),
+                    tags: vec![TypeTag::new(2, "GameplayTagContainer"), TypeTag::new(1, "/Script/GameplayTags")],
+                    inner_types: Vec::new(),
+                },
+                flags: 8,
+                value: PropertyValue::GameplayTagContainerProperty(container),
+            }),
+        };
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&property).unwrap();
+
+        let mut read = Cursor::new(&data).read_le::<Property>().unwrap();
+        let Some(PropertyValue::GameplayTagContainerProperty(container)) = read.body.as_mut().map(|b| &mut b.value) else {
+            panic!("tags weren't read as a GameplayTagContainer");
+        };
+        assert_eq!(container.tags, [FString::from_str("Noce.Event.A"), FString::from_str("Noce.Event.B")]);
+        assert!(container.remove("Noce.Event.A"));
+        assert!(!container.remove("Noce.Event.A"));
+        assert!(container.contains("Noce.Event.B"));
+
+        let mut written = Vec::<u8>::new();
+        Cursor::new(&mut written).write_le(&read).unwrap();
+        assert_eq!(written.len(), data.len() - "Noce.Event.A".len() - 5);
+
+        // tags are collected from containers nested in arrays and maps too
+        let nested = |tag: &str| {
+            let mut container = GameplayTagContainer::default();
+            container.add(tag);
+            PropertyValue::GameplayTagContainerProperty(container)
+        };
+        let save = SaveGame::new_for_test(vec![
+            read,
+            Property::new_scalar("Array", PropertyValue::ArrayProperty { values: vec![nested("Noce.Event.C")] }),
+            Property::new_scalar("Map", PropertyValue::MapProperty {
+                removed_count: 0,
+                values: vec![(PropertyValue::IntProperty(0), nested("Noce.Event.D"))],
+            }),
+            Property::new_none(),
+        ]);
+        assert_eq!(save.gameplay_tags(), ["Noce.Event.B", "Noce.Event.C", "Noce.Event.D"]);
+    }
+
+    #[test]
+    fn test_byte_enum_property() {
+        let property_type = PropertyType {
+            name: FString::from_str("ByteProperty"),
+            tags: vec![TypeTag::new(2, "ENoceFacing"), TypeTag::new(1, "/Script/GameNoce")],
+            inner_types: Vec::new(),
+        };
+        assert_eq!(property_type.enum_type().as_deref(), Some("/Script/GameNoce.ENoceFacing"));
+        assert_eq!(PropertyType::new_scalar("ByteProperty").enum_type(), None);
+
+        let property = Property {
+            name: FString::from_str("Facing"),
+            body: Some(PropertyBody {
+                property_type: property_type.clone(),
+                flags: 0,
+                value: PropertyValue::ByteEnumProperty(FString::from_str("ENoceFacing::Front")),
+            }),
+        };
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&property).unwrap();
+
+        let mut read = Cursor::new(&data).read_le::<Property>().unwrap();
+        let body = read.body.as_mut().unwrap();
+        let PropertyValue::ByteEnumProperty(value) = &mut body.value else {
+            panic!("enum value wasn't read as a ByteEnumProperty");
+        };
+        *value = FString::from_str("ENoceFacing::Left");
+        assert_eq!(body.value.type_name(), "ByteProperty");
+
+        let mut written = Vec::<u8>::new();
+        Cursor::new(&mut written).write_le(&read).unwrap();
+        let reread = Cursor::new(&written).read_le::<Property>().unwrap();
+        let body = reread.body.as_ref().unwrap();
+        assert_eq!(body.property_type.name, "ByteProperty");
+        assert!(body.value == "ENoceFacing::Left");
+
+        // values are collected from enums nested in maps too
+        let map = Property {
+            name: FString::from_str("FacingsByIndex"),
+            body: Some(PropertyBody {
+                property_type: PropertyType {
+                    name: FString::from_str("MapProperty"),
+                    tags: vec![TypeTag::new(1, "IntProperty")],
+                    inner_types: vec![property_type.clone()],
+                },
+                flags: 0,
+                value: PropertyValue::MapProperty {
+                    removed_count: 0,
+                    values: vec![(
+                        PropertyValue::IntProperty(0),
+                        PropertyValue::ByteEnumProperty(FString::from_str("ENoceFacing::Back")),
+                    )],
+                },
+            }),
+        };
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&map).unwrap();
+        let map = Cursor::new(&data).read_le::<Property>().unwrap();
+
+        // while arrays of bytes are always read as raw bytes, even when the bytes are tagged with an enum
+        let array = Property {
+            name: FString::from_str("Facings"),
+            body: Some(PropertyBody {
+                property_type: PropertyType {
+                    name: FString::from_str("ArrayProperty"),
+                    tags: vec![TypeTag::new(1, "ByteProperty"), TypeTag::new(2, "ENoceFacing"), TypeTag::new(1, "/Script/GameNoce")],
+                    inner_types: Vec::new(),
+                },
+                flags: 0,
+                value: PropertyValue::ArrayProperty { values: vec![PropertyValue::UnknownProperty(vec![0, 1])] },
+            }),
+        };
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&array).unwrap();
+        let array = Cursor::new(&data).read_le::<Property>().unwrap();
+        let PropertyValue::ArrayProperty { values } = &array.body.as_ref().unwrap().value else {
+            panic!("array wasn't read as an ArrayProperty");
+        };
+        assert!(matches!(&values[..], [PropertyValue::UnknownProperty(buf)] if buf == &[0, 1]));
+
+        let save = SaveGame::new_for_test(vec![reread, array, map, Property::new_none()]);
+        let enums = save.enum_values();
+        assert_eq!(enums.len(), 1);
+        assert_eq!(enums["/Script/GameNoce.ENoceFacing"], ["ENoceFacing::Back", "ENoceFacing::Left"]);
+
+        // a new enum byte defaults to a plain byte, since that's how one byte of data is read back
+        assert!(matches!(property_type.make_default_value(0), PropertyValue::ByteProperty(0)));
+    }
+
+    #[test]
+    fn test_indexable_mutation() {
+        let mut value = PropertyValue::StructProperty(vec![
+            Property::new_scalar("A", PropertyValue::IntProperty(1)),
+            Property::new_scalar("B", PropertyValue::IntProperty(2)),
+            Property::new_none(),
+        ]);
+        let names = |value: &PropertyValue| -> Vec<String> {
+            value.children().into_iter().map(|(key, _)| match key {
+                ChildKey::Name(name) => name.to_string(),
+                _ => panic!("struct children should be named"),
+            }).collect()
+        };
+
+        value.insert_property(2, Property::new_scalar("C", PropertyValue::IntProperty(3))).unwrap();
+        assert!(value.insert_property(4, Property::new_scalar("D", PropertyValue::IntProperty(4))).is_err());
+        assert!(value.insert_property(0, Property::new_none()).is_err());
+        assert_eq!(names(&value), ["A", "B", "C"]);
+
+        value.move_child(0, 2).unwrap();
+        assert_eq!(names(&value), ["B", "C", "A"]);
+        // the None property can't be moved
+        assert!(value.move_child(2, 3).is_err());
+
+        value.rename_key("C", "D").unwrap();
+        assert!(value.rename_key("D", "A").is_err());
+        assert!(value.rename_key("D", "None").is_err());
+        assert!(matches!(value.remove_key("B"), Some(PropertyValue::IntProperty(2))));
+        assert!(value.remove_key("None").is_none());
+        assert_eq!(names(&value), ["D", "A"]);
+        let PropertyValue::StructProperty(properties) = &value else { unreachable!() };
+        assert!(properties.last().unwrap().is_none());
+
+        let mut array = PropertyValue::ArrayProperty { values: vec![PropertyValue::IntProperty(1), PropertyValue::IntProperty(2)] };
+        array.insert_index(0, PropertyValue::IntProperty(0)).unwrap();
+        array.move_child(2, 1).unwrap();
+        assert!(matches!(array.remove_index(1), Some(PropertyValue::IntProperty(2))));
+        assert!(array.remove_index(5).is_none());
+        assert!(array.rename_key("0", "1").is_err());
+        let values: Vec<_> = array.children().into_iter().map(|(_, value)| value.summary()).collect();
+        assert_eq!(values, ["0", "1"]);
+
+        let mut map = PropertyValue::MapProperty {
+            removed_count: 0,
+            values: vec![
+                (PropertyValue::StrProperty(FString::from_str("a")), PropertyValue::IntProperty(1)),
+                (PropertyValue::StrProperty(FString::from_str("b")), PropertyValue::IntProperty(2)),
+            ],
+        };
+        assert!(map.rename_key("a", "b").is_err());
+        map.rename_key("a", "c").unwrap();
+        assert!(matches!(map.get_key("c"), Some(PropertyValue::IntProperty(1))));
+        assert!(matches!(map.remove_key("b"), Some(PropertyValue::IntProperty(2))));
+        assert!(matches!(map.children()[..], [(ChildKey::MapKey(key), _)] if key == "c"));
+
+        // keys that aren't strings are named by their string form
+        let mut map = PropertyValue::MapProperty {
+            removed_count: 0,
+            values: vec![
+                (PropertyValue::IntProperty(1), PropertyValue::IntProperty(10)),
+                (PropertyValue::IntProperty(2), PropertyValue::IntProperty(20)),
+            ],
+        };
+        assert!(map.rename_key("1", "2").is_err());
+        map.rename_key("1", "3").unwrap();
+        assert!(matches!(map.get_index(3), Some(PropertyValue::IntProperty(10))));
+        assert!(matches!(map.get_key("3"), Some(PropertyValue::IntProperty(10))));
+        assert!(matches!(map.get_key("2"), Some(PropertyValue::IntProperty(20))));
+        assert!(map.get_key("1").is_none());
+        assert!(matches!(map.remove_key("2"), Some(PropertyValue::IntProperty(20))));
+        assert!(map.remove_key("2").is_none());
+    }
+
+    #[test]
+    fn test_byte_array_mutation() {
+        let bytes_type = PropertyType {
+            name: FString::from_str("ArrayProperty"),
+            tags: vec![TypeTag::new(1, "ByteProperty")],
+            inner_types: Vec::new(),
+        };
+        let mut property = Property {
+            name: FString::from_str("Bytes"),
+            body: Some(PropertyBody {
+                property_type: bytes_type,
+                flags: 0,
+                value: PropertyValue::ArrayProperty { values: vec![PropertyValue::UnknownProperty(vec![1, 2, 3])] },
+            }),
+        };
+
+        // edits through a property reach the bytes
+        property.insert_index(1, PropertyValue::ByteProperty(9)).unwrap();
+        assert!(property.insert_index(0, PropertyValue::IntProperty(9)).is_err());
+        assert!(property.insert_index(5, PropertyValue::ByteProperty(9)).is_err());
+        assert!(property.get_index(0).is_none());
+        assert!(property.get_index_mut(0).is_none());
+
+        let mut save = SaveGame::new_for_test(vec![
+            Property::new_scalar("First", PropertyValue::IntProperty(1)),
+            property,
+            Property::new_none(),
+        ]);
+        let bytes = save.save_data.get_key_mut("Bytes").unwrap();
+        assert!(matches!(bytes.remove_index(0), Some(PropertyValue::ByteProperty(1))));
+        assert!(bytes.remove_index(3).is_none());
+        bytes.move_child(0, 2).unwrap();
+        assert!(bytes.move_child(0, 3).is_err());
+        save.save_data.move_child(1, 0).unwrap();
+
+        let mut data = Vec::<u8>::new();
+        Cursor::new(&mut data).write_le(&save).unwrap();
+        let read = SaveGame::read_from(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(read.save_data.properties[0].name, "Bytes");
+        let Some(PropertyValue::ArrayProperty { values }) = read.save_data.get_key("Bytes") else {
+            panic!("Bytes should be an array");
+        };
+        assert!(matches!(&values[..], [PropertyValue::UnknownProperty(buf)] if buf == &[2, 3, 9]));
+    }
+}
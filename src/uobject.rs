@@ -1,28 +1,75 @@
+use std::any::Any;
 use std::fmt::Debug;
 use std::io::{Cursor, Read, Seek};
 use std::str::FromStr;
 
-use binrw::{binrw, BinRead, BinWrite, BinResult, Endian};
+use binrw::{binrw, BinWrite, BinResult, Endian};
+
+use crate::registry;
+use crate::save::Guid;
 
 /// A type that can be both converted to and parsed from a string.
 pub trait Stringable: ToString {
     /// Tries to set the value of this object from the given string representation.
     ///
-    /// If the string cannot be parsed, the value is not updated, but there is no error.
+    /// If the string cannot be parsed, the value is not updated, but there is no error. Returns
+    /// whether the value was updated.
     // FromStr is not dyn compatible, so we have to go through this wrapper
-    fn try_set_from_str(&mut self, s: &str);
+    fn try_set_from_str(&mut self, s: &str) -> bool;
+
+    /// Returns this value as [`Any`] so that callers can handle specific types, such as floats, differently.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns this value as a mutable [`Any`].
+    fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
-impl<T: ToString + FromStr> Stringable for T {
-    fn try_set_from_str(&mut self, s: &str) {
+impl<T: ToString + FromStr + 'static> Stringable for T {
+    fn try_set_from_str(&mut self, s: &str) -> bool {
         if let Ok(parsed) = s.parse::<T>() {
             *self = parsed;
+            true
+        } else {
+            false
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A type that can be cloned into a boxed [`CoreUObject`].
+pub trait CloneUObject {
+    /// Clones this object into a new box.
+    // Clone is not dyn compatible, so we have to go through this wrapper
+    fn clone_box(&self) -> Box<dyn CoreUObject>;
+}
+
+impl<T: CoreUObject + Clone + 'static> CloneUObject for T {
+    fn clone_box(&self) -> Box<dyn CoreUObject> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn CoreUObject> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
 /// A core Unreal Engine 5 type.
-pub trait CoreUObject: Debug {
+pub trait CoreUObject: Debug + CloneUObject {
+    /// The name of this object's type, e.g. "Vector".
+    fn type_name(&self) -> &'static str;
+
+    /// A list of fields in this object with their names.
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)>;
+
     /// A mutable list of fields in this object with their names.
     fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)>;
 
@@ -36,6 +83,7 @@ pub trait CoreUObject: Debug {
 
 /// Write a CoreUObject to a writer.
 #[binrw::writer(writer, endian)]
+#[allow(clippy::borrowed_box)]
 pub fn write_uobject(object: &Box<dyn CoreUObject>) -> BinResult<()> {
     let bytes = object.to_bytes(endian)?;
     bytes.write_options(writer, endian, ())
@@ -59,6 +107,14 @@ fn uobject_to_bytes<'a, O: CoreUObject + BinWrite<Args<'a>=()>>(object: &O, endi
 pub struct FDateTime(u64);
 
 impl CoreUObject for FDateTime {
+    fn type_name(&self) -> &'static str {
+        "DateTime"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("Ticks", &self.0)]
+    }
+
     fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
         vec![("Ticks", &mut self.0)]
     }
@@ -83,6 +139,14 @@ impl CoreUObject for FDateTime {
 pub struct FTimespan(u64);
 
 impl CoreUObject for FTimespan {
+    fn type_name(&self) -> &'static str {
+        "Timespan"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("Ticks", &self.0)]
+    }
+
     fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
         vec![("Ticks", &mut self.0)]
     }
@@ -106,6 +170,14 @@ pub struct Vector {
 }
 
 impl CoreUObject for Vector {
+    fn type_name(&self) -> &'static str {
+        "Vector"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("X", &self.x), ("Y", &self.y), ("Z", &self.z)]
+    }
+
     fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
         vec![("X", &mut self.x), ("Y", &mut self.y), ("Z", &mut self.z)]
     }
@@ -130,6 +202,14 @@ pub struct Quat {
 }
 
 impl CoreUObject for Quat {
+    fn type_name(&self) -> &'static str {
+        "Quat"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("X", &self.x), ("Y", &self.y), ("Z", &self.z), ("W", &self.w)]
+    }
+
     fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
         vec![("X", &mut self.x), ("Y", &mut self.y), ("Z", &mut self.z), ("W", &mut self.w)]
     }
@@ -154,6 +234,14 @@ pub struct LinearColor {
 }
 
 impl CoreUObject for LinearColor {
+    fn type_name(&self) -> &'static str {
+        "LinearColor"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("R", &self.r), ("G", &self.g), ("B", &self.b), ("A", &self.a)]
+    }
+
     fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
         vec![("R", &mut self.r), ("G", &mut self.g), ("B", &mut self.b), ("A", &mut self.a)]
     }
@@ -167,26 +255,375 @@ impl CoreUObject for LinearColor {
     }
 }
 
+/// An Unreal Engine 5 2D vector.
+#[binrw]
+#[derive(Debug, Clone, Default)]
+pub struct Vector2D {
+    x: f64,
+    y: f64,
+}
+
+impl CoreUObject for Vector2D {
+    fn type_name(&self) -> &'static str {
+        "Vector2D"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("X", &self.x), ("Y", &self.y)]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![("X", &mut self.x), ("Y", &mut self.y)]
+    }
+
+    fn size(&self) -> usize {
+        16
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
+/// An Unreal Engine 5 4D vector.
+#[binrw]
+#[derive(Debug, Clone, Default)]
+pub struct Vector4 {
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+}
+
+impl CoreUObject for Vector4 {
+    fn type_name(&self) -> &'static str {
+        "Vector4"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("X", &self.x), ("Y", &self.y), ("Z", &self.z), ("W", &self.w)]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![("X", &mut self.x), ("Y", &mut self.y), ("Z", &mut self.z), ("W", &mut self.w)]
+    }
+
+    fn size(&self) -> usize {
+        32
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
+/// An Unreal Engine 5 rotation in degrees.
+#[binrw]
+#[derive(Debug, Clone, Default)]
+pub struct Rotator {
+    pitch: f64,
+    yaw: f64,
+    roll: f64,
+}
+
+impl CoreUObject for Rotator {
+    fn type_name(&self) -> &'static str {
+        "Rotator"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("Pitch", &self.pitch), ("Yaw", &self.yaw), ("Roll", &self.roll)]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![("Pitch", &mut self.pitch), ("Yaw", &mut self.yaw), ("Roll", &mut self.roll)]
+    }
+
+    fn size(&self) -> usize {
+        24
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
+/// An Unreal Engine 5 2D point with integer coordinates.
+#[binrw]
+#[derive(Debug, Clone, Default)]
+pub struct IntPoint {
+    x: i32,
+    y: i32,
+}
+
+impl CoreUObject for IntPoint {
+    fn type_name(&self) -> &'static str {
+        "IntPoint"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("X", &self.x), ("Y", &self.y)]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![("X", &mut self.x), ("Y", &mut self.y)]
+    }
+
+    fn size(&self) -> usize {
+        8
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
+/// An Unreal Engine 5 8-bit color.
+///
+/// The channels are stored in BGRA order.
+#[binrw]
+#[derive(Debug, Clone, Default)]
+pub struct Color {
+    b: u8,
+    g: u8,
+    r: u8,
+    a: u8,
+}
+
+impl CoreUObject for Color {
+    fn type_name(&self) -> &'static str {
+        "Color"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("R", &self.r), ("G", &self.g), ("B", &self.b), ("A", &self.a)]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![("R", &mut self.r), ("G", &mut self.g), ("B", &mut self.b), ("A", &mut self.a)]
+    }
+
+    fn size(&self) -> usize {
+        4
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
+/// An Unreal Engine 5 axis-aligned 3D box.
+#[binrw]
+#[derive(Debug, Clone, Default)]
+pub struct BoundingBox {
+    min: Vector,
+    max: Vector,
+    is_valid: u8,
+}
+
+impl CoreUObject for BoundingBox {
+    fn type_name(&self) -> &'static str {
+        "Box"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![
+            ("Min X", &self.min.x), ("Min Y", &self.min.y), ("Min Z", &self.min.z),
+            ("Max X", &self.max.x), ("Max Y", &self.max.y), ("Max Z", &self.max.z),
+            ("Is Valid", &self.is_valid),
+        ]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![
+            ("Min X", &mut self.min.x), ("Min Y", &mut self.min.y), ("Min Z", &mut self.min.z),
+            ("Max X", &mut self.max.x), ("Max Y", &mut self.max.y), ("Max Z", &mut self.max.z),
+            ("Is Valid", &mut self.is_valid),
+        ]
+    }
+
+    fn size(&self) -> usize {
+        49
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
+/// An Unreal Engine 5 transform, made up of a rotation, a translation and a 3D scale.
+#[binrw]
+#[derive(Debug, Clone)]
+pub struct Transform {
+    rotation: Quat,
+    translation: Vector,
+    scale: Vector,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        // the identity transform
+        Self {
+            rotation: Quat { w: 1.0, ..Quat::default() },
+            translation: Vector::default(),
+            scale: Vector { x: 1.0, y: 1.0, z: 1.0 },
+        }
+    }
+}
+
+impl CoreUObject for Transform {
+    fn type_name(&self) -> &'static str {
+        "Transform"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![
+            ("Rotation X", &self.rotation.x), ("Rotation Y", &self.rotation.y), ("Rotation Z", &self.rotation.z), ("Rotation W", &self.rotation.w),
+            ("Translation X", &self.translation.x), ("Translation Y", &self.translation.y), ("Translation Z", &self.translation.z),
+            ("Scale X", &self.scale.x), ("Scale Y", &self.scale.y), ("Scale Z", &self.scale.z),
+        ]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![
+            ("Rotation X", &mut self.rotation.x), ("Rotation Y", &mut self.rotation.y), ("Rotation Z", &mut self.rotation.z), ("Rotation W", &mut self.rotation.w),
+            ("Translation X", &mut self.translation.x), ("Translation Y", &mut self.translation.y), ("Translation Z", &mut self.translation.z),
+            ("Scale X", &mut self.scale.x), ("Scale Y", &mut self.scale.y), ("Scale Z", &mut self.scale.z),
+        ]
+    }
+
+    fn size(&self) -> usize {
+        80
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
+impl CoreUObject for Guid {
+    fn type_name(&self) -> &'static str {
+        "Guid"
+    }
+
+    fn fields(&self) -> Vec<(&'static str, &dyn Stringable)> {
+        vec![("Value", self)]
+    }
+
+    fn fields_mut(&mut self) -> Vec<(&'static str, &mut dyn Stringable)> {
+        vec![("Value", self)]
+    }
+
+    fn size(&self) -> usize {
+        16
+    }
+
+    fn to_bytes(&self, endian: Endian) -> BinResult<Vec<u8>> {
+        uobject_to_bytes(self, endian)
+    }
+}
+
 /// Tries to read a UE5 object of the given type from a reader.
+///
+/// Returns `None` if the type isn't in the [`registry`](crate::registry).
 pub fn try_read_uobject<R: Read + Seek>(type_name: &str, reader: &mut R, endian: Endian) -> BinResult<Option<Box<dyn CoreUObject>>> {
-    Ok(Some(match type_name {
-        "DateTime" => Box::new(FDateTime::read_options(reader, endian, ())?),
-        "Timespan" => Box::new(FTimespan::read_options(reader, endian, ())?),
-        "Vector" => Box::new(Vector::read_options(reader, endian, ())?),
-        "Quat" => Box::new(Quat::read_options(reader, endian, ())?),
-        "LinearColor" => Box::new(LinearColor::read_options(reader, endian, ())?),
-        _ => return Ok(None),
-    }))
+    // copy the entry out so the registry isn't locked while reading
+    let Some(uobject_type) = registry::registry().uobject_type(type_name) else {
+        return Ok(None);
+    };
+    (uobject_type.read)(reader, endian).map(Some)
 }
 
 /// Creates a default value for a given Unreal Engine 5 type.
+///
+/// Returns `None` if the type isn't in the [`registry`](crate::registry).
 pub fn make_default_uobject(type_name: &str) -> Option<Box<dyn CoreUObject>> {
-    match type_name {
-        "DateTime" => Some(Box::new(FDateTime::default())),
-        "Timespan" => Some(Box::new(FTimespan::default())),
-        "Vector" => Some(Box::new(Vector::default())),
-        "Quat" => Some(Box::new(Quat::default())),
-        "LinearColor" => Some(Box::new(LinearColor::default())),
-        _ => None,
-    }
-}
\ No newline at end of file
+    registry::registry().uobject_type(type_name).map(|uobject_type| (uobject_type.make_default)())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uobject_sizes() {
+        let type_names = [
+            "DateTime", "Timespan", "Vector", "Quat", "LinearColor", "Vector2D", "Vector4", "Rotator", "IntPoint", "Color",
+            "Box", "Transform", "Guid",
+        ];
+        for type_name in type_names {
+            let mut object = make_default_uobject(type_name).unwrap();
+            assert_eq!(object.type_name(), type_name);
+            for (_, field) in object.fields_mut() {
+                field.try_set_from_str("1");
+            }
+
+            let bytes = object.to_bytes(Endian::Little).unwrap();
+            assert_eq!(bytes.len(), object.size(), "{type_name}");
+            let read = try_read_uobject(type_name, &mut Cursor::new(&bytes), Endian::Little).unwrap().unwrap();
+            assert_eq!(read.to_bytes(Endian::Little).unwrap(), bytes, "{type_name}");
+        }
+    }
+
+    fn f64_bytes(values: &[f64]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    /// Reads an object from known bytes, checks its fields, and checks that it writes the same bytes.
+    fn assert_roundtrip(type_name: &str, bytes: &[u8], fields: &[(&str, &str)]) {
+        let object = try_read_uobject(type_name, &mut Cursor::new(bytes), Endian::Little).unwrap().unwrap();
+        let read_fields: Vec<_> = object.fields().into_iter().map(|(name, value)| (name, value.to_string())).collect();
+        let expected: Vec<_> = fields.iter().map(|&(name, value)| (name, value.to_string())).collect();
+        assert_eq!(read_fields, expected, "{type_name}");
+        assert_eq!(object.size(), bytes.len(), "{type_name}");
+        assert_eq!(object.to_bytes(Endian::Little).unwrap(), bytes, "{type_name}");
+    }
+
+    #[test]
+    fn test_uobject_roundtrip() {
+        assert_roundtrip("Vector2D", &f64_bytes(&[1.5, -2.0]), &[("X", "1.5"), ("Y", "-2")]);
+        assert_roundtrip(
+            "Vector4", &f64_bytes(&[1.0, 2.0, 3.0, 4.0]),
+            &[("X", "1"), ("Y", "2"), ("Z", "3"), ("W", "4")],
+        );
+        assert_roundtrip(
+            "Rotator", &f64_bytes(&[10.0, 90.0, -45.0]),
+            &[("Pitch", "10"), ("Yaw", "90"), ("Roll", "-45")],
+        );
+
+        let mut int_point = 7i32.to_le_bytes().to_vec();
+        int_point.extend((-3i32).to_le_bytes());
+        assert_roundtrip("IntPoint", &int_point, &[("X", "7"), ("Y", "-3")]);
+
+        // stored as BGRA
+        assert_roundtrip("Color", &[0x10, 0x20, 0x30, 0xff], &[("R", "48"), ("G", "32"), ("B", "16"), ("A", "255")]);
+
+        // the IsValid byte comes after both corners
+        let mut bounding_box = f64_bytes(&[-1.0, -2.0, -3.0, 1.0, 2.0, 3.0]);
+        bounding_box.push(1);
+        assert_roundtrip(
+            "Box", &bounding_box,
+            &[
+                ("Min X", "-1"), ("Min Y", "-2"), ("Min Z", "-3"),
+                ("Max X", "1"), ("Max Y", "2"), ("Max Z", "3"),
+                ("Is Valid", "1"),
+            ],
+        );
+
+        assert_roundtrip(
+            "Transform", &f64_bytes(&[0.0, 0.0, 0.5, 0.5, 100.0, 200.0, 300.0, 2.0, 2.0, 2.0]),
+            &[
+                ("Rotation X", "0"), ("Rotation Y", "0"), ("Rotation Z", "0.5"), ("Rotation W", "0.5"),
+                ("Translation X", "100"), ("Translation Y", "200"), ("Translation Z", "300"),
+                ("Scale X", "2"), ("Scale Y", "2"), ("Scale Z", "2"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_transform_default_is_identity() {
+        let identity = make_default_uobject("Transform").unwrap();
+        let bytes = f64_bytes(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        assert_eq!(identity.to_bytes(Endian::Little).unwrap(), bytes);
+    }
+}
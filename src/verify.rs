@@ -0,0 +1,488 @@
+//! Verification that saves survive a write/read cycle unchanged.
+//!
+//! Sizes of properties are recomputed when a save is written rather than stored, so a mistake in
+//! any size calculation would silently produce a corrupt file. The checks here catch that before it
+//! happens.
+
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{Cursor, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use binrw::{BinResult, BinWrite, BinWriterExt};
+
+use crate::diff;
+use crate::save::*;
+
+/// A difference between a save file and the result of re-serializing it
+#[derive(Debug, Clone)]
+pub struct RoundTripMismatch {
+    /// The offset of the first byte that differs
+    pub offset: usize,
+    /// The length of the original data
+    pub original_len: usize,
+    /// The length of the re-serialized data
+    pub serialized_len: usize,
+    /// The path of the innermost part of the save containing the offset, if known
+    pub path: Option<String>,
+}
+
+impl Display for RoundTripMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "re-serialized save differs from the original at offset {:#x}", self.offset)?;
+        if let Some(ref path) = self.path {
+            write!(f, " (in {path})")?;
+        }
+        if self.original_len != self.serialized_len {
+            write!(f, "; original is {} bytes, re-serialized is {} bytes", self.original_len, self.serialized_len)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RoundTripMismatch {}
+
+/// A property whose computed size doesn't match the number of bytes actually written for it
+#[derive(Debug, Clone)]
+pub struct SizeMismatch {
+    /// The path of the property
+    pub path: String,
+    /// The size that would be written to the property's data size field
+    pub declared: usize,
+    /// The number of bytes actually written
+    pub actual: usize,
+}
+
+impl Display for SizeMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} has a computed size of {} bytes but serializes to {} bytes", self.path, self.declared, self.actual)
+    }
+}
+
+impl std::error::Error for SizeMismatch {}
+
+/// Serializes a save to a byte vector.
+pub fn serialize(save: &SaveGame) -> BinResult<Vec<u8>> {
+    let mut data = Vec::new();
+    Cursor::new(&mut data).write_le(save)?;
+    Ok(data)
+}
+
+pub(crate) fn join_path(parent: &str, child: &str) -> String {
+    if parent.is_empty() || child.starts_with('[') {
+        format!("{parent}{child}")
+    } else {
+        format!("{parent}.{child}")
+    }
+}
+
+pub(crate) fn element_label(index: usize, key: Option<&PropertyValue>) -> String {
+    match key.and_then(PropertyValue::scalar_string) {
+        Some(key) => format!("[{key}]"),
+        None => format!("[{index}]"),
+    }
+}
+
+/// Finds the path of the innermost value containing the given offset, relative to the start of the value
+fn find_in_value(value: &PropertyValue, path: String, offset: usize) -> String {
+    let (mut position, children): (usize, Vec<(String, &PropertyValue)>) = match value {
+        PropertyValue::StructProperty(props) => return find_in_properties(props, &path, offset).unwrap_or(path),
+        PropertyValue::CustomStructProperty(custom_struct) => {
+            // data size + flags
+            let header_size = 5;
+            if offset < header_size {
+                return path;
+            }
+            return find_in_properties(&custom_struct.properties, &path, offset - header_size).unwrap_or(path);
+        }
+        PropertyValue::ArrayProperty { values } if !matches!(values.first(), Some(PropertyValue::UnknownProperty(_))) => {
+            (4, values.iter().enumerate().map(|(i, v)| (element_label(i, None), v)).collect())
+        }
+        PropertyValue::MapProperty { values, .. } => {
+            let mut children = Vec::with_capacity(values.len() * 2);
+            for (i, (k, v)) in values.iter().enumerate() {
+                let label = element_label(i, Some(k));
+                children.push((format!("{label}(key)"), k));
+                children.push((label, v));
+            }
+            (8, children)
+        }
+        PropertyValue::SetProperty { values, .. } => {
+            (8, values.iter().enumerate().map(|(i, v)| (element_label(i, None), v)).collect())
+        }
+        _ => return path,
+    };
+
+    for (label, child) in children {
+        let size = child.size();
+        if offset < position + size {
+            return find_in_value(child, join_path(&path, &label), offset - position);
+        }
+        position += size;
+    }
+
+    path
+}
+
+/// Finds the path of the innermost property containing the given offset, relative to the start of the property list
+fn find_in_properties(properties: &[Property], parent: &str, offset: usize) -> Option<String> {
+    let mut position = 0;
+    for property in properties {
+        let size = property.size();
+        if offset < position + size {
+            let path = join_path(parent, property.name.as_str());
+            let Some(body) = &property.body else {
+                return Some(path);
+            };
+
+            let value_start = position + property.name.byte_size() + body.property_type.size() + 4 + 1;
+            return Some(if offset < value_start {
+                path
+            } else {
+                find_in_value(&body.value, path, offset - value_start)
+            });
+        }
+        position += size;
+    }
+
+    None
+}
+
+/// Returns the path of the innermost property containing the given offset in the serialized save.
+pub fn path_at_offset(save: &SaveGame, offset: usize) -> Option<String> {
+    let mut header = Vec::new();
+    let mut writer = Cursor::new(&mut header);
+    writer.write_le(&save.header).ok()?;
+    writer.write_le(&save.custom_format_data).ok()?;
+
+    let mut position = header.len();
+    if offset < position {
+        return Some(String::from("(header)"));
+    }
+
+    position += save.save_data.type_name.byte_size() + 1;
+    if offset < position {
+        return Some(String::from("(save type)"));
+    }
+
+    find_in_properties(&save.save_data.properties, "", offset - position)
+        .or_else(|| Some(String::from("(save footer)")))
+}
+
+/// Checks that re-serializing a save produces exactly the original data.
+///
+/// On failure, the error is a [`RoundTripMismatch`] identifying the first byte that differs.
+pub fn verify_roundtrip(original: &[u8], save: &SaveGame) -> Result<()> {
+    let serialized = serialize(save)?;
+    let first_difference = original.iter().zip(&serialized).position(|(a, b)| a != b);
+    let offset = match first_difference {
+        Some(offset) => offset,
+        None if original.len() == serialized.len() => return Ok(()),
+        None => original.len().min(serialized.len()),
+    };
+
+    Err(RoundTripMismatch {
+        offset,
+        original_len: original.len(),
+        serialized_len: serialized.len(),
+        path: path_at_offset(save, offset),
+    }.into())
+}
+
+fn value_bytes(value: &PropertyValue) -> BinResult<usize> {
+    let mut data = Vec::with_capacity(value.size());
+    value.write_le(&mut Cursor::new(&mut data))?;
+    Ok(data.len())
+}
+
+fn check_value_sizes(value: &PropertyValue, path: &str) -> Result<()> {
+    match value {
+        PropertyValue::StructProperty(props) => check_property_sizes(props, path),
+        PropertyValue::CustomStructProperty(custom_struct) => check_property_sizes(&custom_struct.properties, path),
+        PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. } => {
+            for (i, value) in values.iter().enumerate() {
+                check_value_sizes(value, &join_path(path, &element_label(i, None)))?;
+            }
+            Ok(())
+        }
+        PropertyValue::MapProperty { values, .. } => {
+            for (i, (key, value)) in values.iter().enumerate() {
+                let label = element_label(i, Some(key));
+                check_value_sizes(key, &join_path(path, &format!("{label}(key)")))?;
+                check_value_sizes(value, &join_path(path, &label))?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_property_sizes(properties: &[Property], parent: &str) -> Result<()> {
+    for property in properties {
+        let Some(body) = &property.body else {
+            continue;
+        };
+
+        let path = join_path(parent, property.name.as_str());
+        // check children first so we report the innermost property with a problem
+        check_value_sizes(&body.value, &path)?;
+
+        let declared = body.value.size();
+        let actual = value_bytes(&body.value)?;
+        if declared != actual {
+            return Err(SizeMismatch { path, declared, actual }.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts the values kept as raw bytes, such as those quarantined when the save was read
+fn count_raw_values(properties: &[Property]) -> usize {
+    properties.iter().filter_map(|property| property.body.as_ref()).map(|body| count_raw_in_value(&body.value)).sum()
+}
+
+fn count_raw_in_value(value: &PropertyValue) -> usize {
+    match value {
+        PropertyValue::UnknownProperty(_) => 1,
+        PropertyValue::StructProperty(props) => count_raw_values(props),
+        PropertyValue::CustomStructProperty(custom_struct) => count_raw_values(&custom_struct.properties),
+        PropertyValue::ArrayProperty { values } | PropertyValue::SetProperty { values, .. } => values.iter().map(count_raw_in_value).sum(),
+        PropertyValue::MapProperty { values, .. } => values.iter().map(|(key, value)| count_raw_in_value(key) + count_raw_in_value(value)).sum(),
+        _ => 0,
+    }
+}
+
+/// Serializes a save, verifying that the result is consistent and can be read back identically.
+///
+/// This checks that every property's computed data size matches what is actually written, then
+/// re-parses the serialized data with the given options and checks that it re-serializes to the
+/// same bytes. Values that were quarantined when the save was read are written back as their raw
+/// bytes, so with lenient options they're quarantined again; any other value that fails to parse
+/// is an error.
+pub fn serialize_verified(save: &SaveGame, options: ParseOptions) -> Result<Vec<u8>> {
+    check_property_sizes(&save.save_data.properties, "")?;
+
+    let data = serialize(save)?;
+    let mark = options.quarantined().len();
+    let reparsed = SaveGame::read_with_options(&mut Cursor::new(&data), options.clone()).context("Serialized save could not be parsed")?;
+    let quarantined = options.quarantined().len() - mark;
+    let raw = count_raw_values(&save.save_data.properties);
+    if quarantined > raw {
+        bail!("Serialized save has {quarantined} values that can't be parsed, but only {raw} were kept as raw bytes");
+    }
+    verify_roundtrip(&data, &reparsed)?;
+
+    Ok(data)
+}
+
+/// Creates the temporary file a save is written to before it replaces the target
+///
+/// The file is named after the target and the process ID. If a file with that name already exists, e.g. one left
+/// behind by a previous run that crashed and happened to have the same process ID, a number is added to the name
+/// instead.
+fn create_temp_file(path: &Path) -> Result<(PathBuf, File)> {
+    let Some(file_name) = path.file_name() else {
+        bail!("{} is not a file path", path.display());
+    };
+    let pid = std::process::id();
+    let mut number = 0u32;
+    loop {
+        let mut temp_name = file_name.to_os_string();
+        if number == 0 {
+            temp_name.push(format!(".{pid}.tmp"));
+        } else {
+            temp_name.push(format!(".{pid}-{number}.tmp"));
+        }
+        let temp_path = path.with_file_name(temp_name);
+        match File::create_new(&temp_path) {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => number += 1,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+fn write_and_check(mut file: File, temp_path: &Path, data: &[u8], save: &SaveGame, options: ParseOptions) -> Result<()> {
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+
+    let written = std::fs::read(temp_path)?;
+    if written != data {
+        bail!("Written file doesn't contain the data that was written");
+    }
+
+    // the written data must read back as a save that serializes to exactly the same bytes
+    let reparsed = SaveGame::read_with_options(&mut Cursor::new(&written), options).context("Written save could not be parsed")?;
+    verify_roundtrip(data, &reparsed).map_err(|err| {
+        // the structural diff is only used to describe the problem, since it doesn't see every difference in the bytes
+        match diff::diff_saves(save, &reparsed).first() {
+            Some(difference) => err.context(format!("Written save doesn't match the save being written ({difference})")),
+            None => err.context("Written save doesn't match the save being written"),
+        }
+    })
+}
+
+/// A save that has been written to a temporary file and verified, waiting to replace its target
+///
+/// Dropping it without calling [`PendingWrite::commit`] deletes the temporary file and leaves the target untouched.
+#[derive(Debug)]
+pub struct PendingWrite {
+    temp_path: PathBuf,
+    path: PathBuf,
+    data: Vec<u8>,
+    committed: bool,
+}
+
+impl PendingWrite {
+    /// The data that will be written
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Replaces the target with the verified file, returning the data that was written
+    pub fn commit(mut self) -> Result<Vec<u8>> {
+        std::fs::rename(&self.temp_path, &self.path).context("Failed to replace the save with the new data")?;
+        self.committed = true;
+        Ok(std::mem::take(&mut self.data))
+    }
+}
+
+impl Drop for PendingWrite {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Writes a save to a temporary file next to the target and verifies it, without touching the target yet
+///
+/// The save is verified as in [`serialize_verified`], then the temporary file is read back, checked to contain exactly
+/// the serialized data, and re-parsed with the given options to check that it re-serializes to the same bytes. This
+/// lets the caller do something before the target is replaced, e.g. back it up, only once the new data is known to be
+/// good. The options should detect custom structs if the save was read that way, or the written save won't match.
+pub fn prepare_write(save: &SaveGame, path: &Path, options: ParseOptions) -> Result<PendingWrite> {
+    let data = serialize_verified(save, options.clone())?;
+    let (temp_path, file) = create_temp_file(path)?;
+
+    if let Err(err) = write_and_check(file, &temp_path, &data, save, options) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(err);
+    }
+
+    Ok(PendingWrite { temp_path, path: path.to_path_buf(), data, committed: false })
+}
+
+/// Writes a save to a file, making sure the original file is left untouched if anything goes wrong.
+///
+/// The save is verified as in [`serialize_verified`] and written to a temporary file in the same folder. That file is
+/// read back and checked byte-for-byte before it's renamed over the target. Returns the data that was written.
+pub fn write_verified(save: &SaveGame, path: &Path) -> Result<Vec<u8>> {
+    write_verified_with_options(save, path, ParseOptions::lenient())
+}
+
+/// Writes a save to a file as in [`write_verified`], reading it back with the given options
+///
+/// The options should detect custom structs if the save was read that way, or the written save won't match.
+pub fn write_verified_with_options(save: &SaveGame, path: &Path, options: ParseOptions) -> Result<Vec<u8>> {
+    prepare_write(save, path, options)?.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_roundtrip() {
+        let save = SaveGame::new_for_test(vec![
+            Property::new_scalar("First", PropertyValue::IntProperty(1)),
+            Property::new_scalar("Struct", PropertyValue::StructProperty(vec![
+                Property::new_scalar("Inner", PropertyValue::IntProperty(2)),
+                Property::new_none(),
+            ])),
+            Property::new_none(),
+        ]);
+
+        let mut data = serialize_verified(&save, ParseOptions::lenient()).unwrap();
+        verify_roundtrip(&data, &save).unwrap();
+
+        // corrupt the last byte of the inner property's value
+        let offset = data.len() - 4 - "None".len() - 5 - "None".len() - 5 - 1;
+        data[offset] = 0xFF;
+        let err = verify_roundtrip(&data, &save).unwrap_err();
+        let mismatch = err.downcast_ref::<RoundTripMismatch>().unwrap();
+        assert_eq!(mismatch.offset, offset);
+        assert_eq!(mismatch.path.as_deref(), Some("Struct.Inner"));
+    }
+
+    #[test]
+    fn test_write_verified() {
+        let dir = std::env::temp_dir().join(format!("shf-write-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("SaveData0.sav");
+
+        let save = SaveGame::new_for_test(vec![Property::new_scalar("Value", PropertyValue::IntProperty(1)), Property::new_none()]);
+        let data = write_verified(&save, &path).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+
+        // a value that doesn't match its declared type can't be read back the same way
+        let mut body = PropertyBody::new_scalar(PropertyValue::StrProperty(FString::from_str("hello")));
+        body.property_type = PropertyType::new_scalar("IntProperty");
+        let bad_save = SaveGame::new_for_test(vec![Property { name: FString::from_str("Value"), body: Some(body) }, Property::new_none()]);
+        assert!(write_verified(&bad_save, &path).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        // a pending write that isn't committed leaves the target alone
+        let new_save = SaveGame::new_for_test(vec![Property::new_scalar("Value", PropertyValue::IntProperty(2)), Property::new_none()]);
+        let pending = prepare_write(&new_save, &path, ParseOptions::lenient()).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        drop(pending);
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        // a temporary file left behind by an earlier run doesn't block writing
+        let stale_path = dir.join(format!("SaveData0.sav.{}.tmp", std::process::id()));
+        std::fs::write(&stale_path, b"stale").unwrap();
+        let data = write_verified(&new_save, &path).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+        assert_eq!(std::fs::read(&stale_path).unwrap(), b"stale");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_quarantined() {
+        let dir = std::env::temp_dir().join(format!("shf-quarantine-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("SaveData0.sav");
+
+        // a save containing a value that doesn't match its declared type, as written by something else
+        let mut body = PropertyBody::new_scalar(PropertyValue::IntProperty(100));
+        body.property_type = PropertyType::new_scalar("StrProperty");
+        let bad_save = SaveGame::new_for_test(vec![
+            Property::new_scalar("First", PropertyValue::IntProperty(1)),
+            Property { name: FString::from_str("Value"), body: Some(body) },
+            Property::new_none(),
+        ]);
+        let original = serialize(&bad_save).unwrap();
+
+        let (save, quarantined) = SaveGame::read_lenient(&mut Cursor::new(&original)).unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert!(matches!(save.save_data.get_key("Value"), Some(PropertyValue::UnknownProperty(_))));
+
+        // the quarantined value is written back unchanged
+        let data = write_verified(&save, &path).unwrap();
+        assert_eq!(data, original);
+        assert_eq!(std::fs::read(&path).unwrap(), original);
+
+        // reading strictly still can't parse it
+        assert!(serialize_verified(&save, ParseOptions::default()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}